@@ -36,6 +36,7 @@ Item {
     property var onCancelNetworkSpeedTest: null
     property string errorMessage: ""
     property var cpuHistory: ({})
+    property var cpuBreakdownHistory: ({})
     property var memoryHistory: ({})
     property var networkDownloadHistory: ({})
     property var networkUploadHistory: ({})
@@ -161,6 +162,7 @@ Item {
             uptime: root.uptime
             loadAverage: root.loadAverage
             history: root.cpuHistory
+            breakdownHistory: root.cpuBreakdownHistory
             historyDurationMs: root.historyDurationMs
         }
     }
@@ -28,18 +28,48 @@ Item {
         return 0;
     }
 
+    // Amostras sem leitura (`null`, como o período com o backend parado) viram NaN.
+    function sampleValue(raw) {
+        if (raw === null || raw === undefined)
+            return NaN;
+        return Number(raw);
+    }
+
     function pointValue(index) {
         if (root.series && root.series.buffer && root.series.count !== undefined) {
             if (index < 0 || index >= root.series.count)
                 return 0;
             var actualIndex = (root.series.start + index) % root.series.buffer.length;
-            return Number(root.series.buffer[actualIndex] || 0);
+            return root.sampleValue(root.series.buffer[actualIndex]);
         }
         if (root.values && index >= 0 && index < root.values.length)
-            return Number(root.values[index] || 0);
+            return root.sampleValue(root.values[index]);
         return 0;
     }
 
+    // Trechos contínuos de pontos com leitura; a linha é interrompida nos NaN.
+    function pointRuns(width, height, minValue, maxValue) {
+        var count = root.pointCount();
+        var runs = [];
+        var run = [];
+        for (var index = 0; index < count; index += 1) {
+            var value = root.pointValue(index);
+            if (isNaN(value)) {
+                if (run.length > 0)
+                    runs.push(run);
+                run = [];
+                continue;
+            }
+            run.push({
+                x: canvas.xForIndex(index, count, width),
+                y: canvas.yForValue(value, minValue, maxValue, height)
+            });
+        }
+        if (run.length > 0)
+            runs.push(run);
+        return runs;
+    }
+
     function computedMaximum() {
         if (root.maximumValue > root.minimumValue)
             return root.maximumValue;
@@ -119,29 +149,27 @@ Item {
                     if (count === 0)
                         return;
 
-                    ctx.beginPath();
-                    for (var index = 0; index < count; index += 1) {
-                        var x = xForIndex(index, count, width);
-                        var yValue = yForValue(root.pointValue(index), minValue, maxValue, height);
-                        if (index === 0)
-                            ctx.moveTo(x, yValue);
-                        else
-                            ctx.lineTo(x, yValue);
-                    }
-                    ctx.lineTo(width, height);
-                    ctx.lineTo(0, height);
-                    ctx.closePath();
+                    var runs = root.pointRuns(width, height, minValue, maxValue);
+
                     ctx.fillStyle = root.fillColor;
-                    ctx.fill();
+                    for (var runIndex = 0; runIndex < runs.length; runIndex += 1) {
+                        var run = runs[runIndex];
+                        ctx.beginPath();
+                        ctx.moveTo(run[0].x, run[0].y);
+                        for (var index = 1; index < run.length; index += 1)
+                            ctx.lineTo(run[index].x, run[index].y);
+                        ctx.lineTo(run[run.length - 1].x, height);
+                        ctx.lineTo(run[0].x, height);
+                        ctx.closePath();
+                        ctx.fill();
+                    }
 
                     ctx.beginPath();
-                    for (var lineIndex = 0; lineIndex < count; lineIndex += 1) {
-                        var lineX = xForIndex(lineIndex, count, width);
-                        var lineY = yForValue(root.pointValue(lineIndex), minValue, maxValue, height);
-                        if (lineIndex === 0)
-                            ctx.moveTo(lineX, lineY);
-                        else
-                            ctx.lineTo(lineX, lineY);
+                    for (var lineRun = 0; lineRun < runs.length; lineRun += 1) {
+                        var points = runs[lineRun];
+                        ctx.moveTo(points[0].x, points[0].y);
+                        for (var lineIndex = 1; lineIndex < points.length; lineIndex += 1)
+                            ctx.lineTo(points[lineIndex].x, points[lineIndex].y);
                     }
                     ctx.strokeStyle = root.strokeColor;
                     ctx.lineWidth = 2;
@@ -0,0 +1,220 @@
+import QtQuick 2.15
+import QtQuick.Layouts 1.15
+import org.kde.plasma.components 3.0 as PlasmaComponents3
+import ".."
+
+// Áreas empilhadas de várias séries de histórico com a mesma janela; a primeira
+// camada fica na base. Usado para o detalhamento do tempo de CPU por modo.
+Item {
+    id: root
+
+    // Lista de { label, color, series }.
+    property var layers: []
+    property real maximumValue: 100
+    property string maxLabel: "100%"
+    property string minLabel: "0%"
+    property string leftFooterText: "5 min atrás"
+    property string rightFooterText: "Agora"
+
+    implicitHeight: theme.chartHeight + 52
+    Layout.fillWidth: true
+
+    function pointCount() {
+        var count = 0;
+        for (var index = 0; index < root.layers.length; index += 1) {
+            var series = root.layers[index].series;
+            if (series && series.count !== undefined)
+                count = Math.max(count, series.count);
+        }
+        return count;
+    }
+
+    // Alinha as séries pela amostra mais recente, como o HistoryChart faz com uma só.
+    // Amostras sem leitura (`null`) viram NaN.
+    function pointValue(series, index, count) {
+        if (!series || !series.buffer || series.count === undefined)
+            return 0;
+        var offset = index - (count - series.count);
+        if (offset < 0 || offset >= series.count)
+            return 0;
+        var actualIndex = (series.start + offset) % series.buffer.length;
+        var raw = series.buffer[actualIndex];
+        if (raw === null || raw === undefined)
+            return NaN;
+        return Number(raw);
+    }
+
+    // Trechos contínuos `[início, fim]` em que nenhuma camada está sem leitura.
+    function indexRuns(count) {
+        var runs = [];
+        var runStart = -1;
+        for (var index = 0; index < count; index += 1) {
+            var missing = false;
+            for (var layerIndex = 0; layerIndex < root.layers.length; layerIndex += 1) {
+                if (isNaN(root.pointValue(root.layers[layerIndex].series, index, count)))
+                    missing = true;
+            }
+            if (missing) {
+                if (runStart >= 0)
+                    runs.push([runStart, index - 1]);
+                runStart = -1;
+            } else if (runStart < 0) {
+                runStart = index;
+            }
+        }
+        if (runStart >= 0)
+            runs.push([runStart, count - 1]);
+        return runs;
+    }
+
+    ColumnLayout {
+        anchors.fill: parent
+        spacing: theme.spacingXS
+
+        RowLayout {
+            Layout.fillWidth: true
+            Item { Layout.fillWidth: true }
+            PlasmaComponents3.Label {
+                text: root.maxLabel
+                color: theme.subduedTextColor
+                font.pixelSize: 11
+            }
+        }
+
+        Rectangle {
+            Layout.fillWidth: true
+            Layout.preferredHeight: theme.chartHeight
+            radius: theme.cardRadius
+            color: theme.elevatedSurfaceColor
+            border.color: theme.outlineColor
+
+            Canvas {
+                id: canvas
+                anchors.fill: parent
+                anchors.margins: 1
+                antialiasing: true
+
+                function xForIndex(index, count, width) {
+                    if (count <= 1)
+                        return 0;
+                    return index * (width / (count - 1));
+                }
+
+                function yForValue(value, height) {
+                    var normalized = Math.max(0, Math.min(1, value / Math.max(1e-6, root.maximumValue)));
+                    return height - (normalized * height);
+                }
+
+                onPaint: {
+                    var ctx = getContext("2d");
+                    var width = canvas.width;
+                    var height = canvas.height;
+                    var count = root.pointCount();
+                    if (ctx.reset)
+                        ctx.reset();
+                    ctx.clearRect(0, 0, width, height);
+
+                    ctx.lineWidth = 1;
+                    ctx.strokeStyle = "rgba(255,255,255,0.08)";
+                    for (var line = 1; line <= 3; line += 1) {
+                        var y = (height / 4) * line;
+                        ctx.beginPath();
+                        ctx.moveTo(0, y);
+                        ctx.lineTo(width, y);
+                        ctx.stroke();
+                    }
+
+                    if (count === 0)
+                        return;
+
+                    var baseline = [];
+                    for (var start = 0; start < count; start += 1)
+                        baseline.push(0);
+                    var runs = root.indexRuns(count);
+
+                    for (var layerIndex = 0; layerIndex < root.layers.length; layerIndex += 1) {
+                        var layer = root.layers[layerIndex];
+                        var top = [];
+                        for (var index = 0; index < count; index += 1)
+                            top.push(baseline[index] + root.pointValue(layer.series, index, count));
+
+                        ctx.fillStyle = layer.color;
+                        ctx.globalAlpha = 0.7;
+                        for (var runIndex = 0; runIndex < runs.length; runIndex += 1) {
+                            var first = runs[runIndex][0];
+                            var last = runs[runIndex][1];
+                            ctx.beginPath();
+                            ctx.moveTo(xForIndex(first, count, width), yForValue(top[first], height));
+                            for (var forward = first + 1; forward <= last; forward += 1)
+                                ctx.lineTo(xForIndex(forward, count, width), yForValue(top[forward], height));
+                            for (var backward = last; backward >= first; backward -= 1)
+                                ctx.lineTo(xForIndex(backward, count, width), yForValue(baseline[backward], height));
+                            ctx.closePath();
+                            ctx.fill();
+                        }
+                        ctx.globalAlpha = 1;
+
+                        baseline = top;
+                    }
+                }
+            }
+        }
+
+        RowLayout {
+            Layout.fillWidth: true
+            spacing: theme.spacingS
+
+            PlasmaComponents3.Label {
+                text: root.leftFooterText
+                color: theme.mutedTextColor
+                font.pixelSize: 11
+                Layout.fillWidth: true
+            }
+
+            PlasmaComponents3.Label {
+                text: root.rightFooterText
+                color: theme.mutedTextColor
+                font.pixelSize: 11
+            }
+
+            PlasmaComponents3.Label {
+                text: root.minLabel
+                color: theme.subduedTextColor
+                font.pixelSize: 11
+            }
+        }
+
+        Flow {
+            Layout.fillWidth: true
+            spacing: theme.spacingS
+
+            Repeater {
+                model: root.layers
+
+                delegate: Row {
+                    spacing: theme.spacingXS
+
+                    Rectangle {
+                        width: 8
+                        height: 8
+                        radius: 2
+                        anchors.verticalCenter: parent.verticalCenter
+                        color: modelData.color
+                    }
+
+                    PlasmaComponents3.Label {
+                        text: modelData.label
+                        color: theme.subduedTextColor
+                        font.pixelSize: 11
+                    }
+                }
+            }
+        }
+    }
+
+    onLayersChanged: canvas.requestPaint()
+    onMaximumValueChanged: canvas.requestPaint()
+    Component.onCompleted: canvas.requestPaint()
+
+    Theme { id: theme }
+}
@@ -12,7 +12,7 @@ PlasmoidItem {
     switchWidth: 480
     switchHeight: 760
 
-    property var cpuMetrics: ({ usage_percent: 0, frequency: 0, core_count: 0, name: "", per_core_usage: [], user_percent: 0, system_percent: 0, idle_percent: 0, steal_percent: 0 })
+    property var cpuMetrics: ({ usage_percent: 0, frequency: 0, core_count: 0, name: "", per_core_usage: [], user_percent: 0, system_percent: 0, idle_percent: 0, steal_percent: 0, iowait_percent: 0, irq_percent: 0, softirq_percent: 0, nice_percent: 0, guest_percent: 0, cores: [], core_classes: [], frequency_policy: {}, throttle: { throttling: false, throttled_cpus: [], core_throttle_count: 0, package_throttle_count: 0 } })
     property var memoryMetrics: ({ usage_percent: 0, used_memory: 0, total_memory: 0, available_memory: 0, total_swap: 0, used_swap: 0 })
     property var diskMetrics: ({ used_space: 0, total_space: 0, available_space: 0, disks: [], total_read_bytes_per_sec: 0, total_write_bytes_per_sec: 0 })
     property var networkMetrics: ({ total_bytes_received: 0, total_bytes_transmitted: 0, interfaces: {}, gateway_ip: null, gateway_latency_ms: null })
@@ -69,6 +69,7 @@ PlasmoidItem {
     readonly property string dbusInterface: "com.monitortray.Backend"
 
     property var cpuHistory: createEmptyHistorySeries()
+    property var cpuBreakdownHistory: ({})
     property var memoryHistory: createEmptyHistorySeries()
     property var networkDownloadHistory: createEmptyHistorySeries()
     property var networkUploadHistory: createEmptyHistorySeries()
@@ -117,6 +118,7 @@ PlasmoidItem {
         onCancelNetworkSpeedTest: root.cancelNetworkSpeedTest
         errorMessage: root.errorMessage
         cpuHistory: root.cpuHistory
+        cpuBreakdownHistory: root.cpuBreakdownHistory
         memoryHistory: root.memoryHistory
         networkDownloadHistory: root.networkDownloadHistory
         networkUploadHistory: root.networkUploadHistory
@@ -314,6 +316,16 @@ PlasmoidItem {
     function applyHistoryPayload(parsed) {
         root.historyDurationMs = parsed.history_duration_ms || root.historyDurationMs;
         root.cpuHistory = root.historyFromPayload(parsed.cpu_usage);
+        root.cpuBreakdownHistory = {
+            user: root.historyFromPayload(parsed.cpu_user),
+            nice: root.historyFromPayload(parsed.cpu_nice),
+            system: root.historyFromPayload(parsed.cpu_system),
+            iowait: root.historyFromPayload(parsed.cpu_iowait),
+            irq: root.historyFromPayload(parsed.cpu_irq),
+            softirq: root.historyFromPayload(parsed.cpu_softirq),
+            steal: root.historyFromPayload(parsed.cpu_steal),
+            guest: root.historyFromPayload(parsed.cpu_guest)
+        };
         root.memoryHistory = root.historyFromPayload(parsed.memory_usage);
         root.gpuHistory = root.historyFromPayload(parsed.gpu_usage);
         root.diskReadHistory = root.historyFromPayload(parsed.disk_read);
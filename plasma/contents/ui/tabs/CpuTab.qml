@@ -11,6 +11,7 @@ ColumnLayout {
     property int uptime: 0
     property var loadAverage: [0, 0, 0]
     property var history: ({})
+    property var breakdownHistory: ({})
     property int historyDurationMs: 5 * 60 * 1000
 
     function fmtPercent(value) {
@@ -18,6 +19,38 @@ ColumnLayout {
         return Math.round(Number(value)) + "%";
     }
 
+    function coreTypeLabel(coreType) {
+        if (coreType === "performance") return "P-core";
+        if (coreType === "efficiency") return "E-core";
+        return "Core";
+    }
+
+    // Núcleos com o tipo de cada um; em CPUs híbridas os P-cores vêm antes dos E-cores.
+    function coreRows() {
+        const usage = root.cpuMetrics && root.cpuMetrics.per_core_usage ? root.cpuMetrics.per_core_usage : [];
+        const cores = root.cpuMetrics && root.cpuMetrics.cores ? root.cpuMetrics.cores : [];
+        const rows = usage.map((value, index) => {
+            const core = cores.find(c => c.id === index);
+            return {
+                index: index,
+                usage: value,
+                frequency: core ? core.frequency_mhz : 0,
+                coreType: core && core.core_type ? core.core_type : "",
+                siblings: core && core.smt_siblings ? core.smt_siblings : []
+            };
+        });
+        const order = { performance: 0, efficiency: 1 };
+        return rows.sort((a, b) => {
+            const left = order[a.coreType] !== undefined ? order[a.coreType] : 2;
+            const right = order[b.coreType] !== undefined ? order[b.coreType] : 2;
+            return left - right || a.index - b.index;
+        });
+    }
+
+    function frequencyPolicy() {
+        return root.cpuMetrics && root.cpuMetrics.frequency_policy ? root.cpuMetrics.frequency_policy : {};
+    }
+
     function historyWindowLabel() {
         return "Últimos " + Math.max(1, Math.round(historyDurationMs / 60000)) + " min";
     }
@@ -81,6 +114,27 @@ ColumnLayout {
         }
     }
 
+    MetricCard {
+        Layout.fillWidth: true
+        title: "Time breakdown"
+        subtitle: "Tempo de CPU por modo, empilhado"
+
+        StackedHistoryChart {
+            Layout.fillWidth: true
+            leftFooterText: root.historyWindowLabel()
+            layers: [
+                { label: "User", color: theme.cpuColor, series: root.breakdownHistory.user },
+                { label: "Nice", color: "#93c5fd", series: root.breakdownHistory.nice },
+                { label: "System", color: theme.dangerColor, series: root.breakdownHistory.system },
+                { label: "IOwait", color: theme.warningColor, series: root.breakdownHistory.iowait },
+                { label: "IRQ", color: theme.memoryColor, series: root.breakdownHistory.irq },
+                { label: "SoftIRQ", color: theme.swapColor, series: root.breakdownHistory.softirq },
+                { label: "Steal", color: theme.systemColor, series: root.breakdownHistory.steal },
+                { label: "Guest", color: theme.diskColor, series: root.breakdownHistory.guest }
+            ]
+        }
+    }
+
     MetricCard {
         Layout.fillWidth: true
         title: "Details"
@@ -107,6 +161,37 @@ ColumnLayout {
             value: root.fmtPercent(root.cpuMetrics ? root.cpuMetrics.idle_percent : 0)
         }
 
+        MetricRow {
+            Layout.fillWidth: true
+            accentColor: theme.warningColor
+            label: "IOwait"
+            value: root.fmtPercent(root.cpuMetrics ? root.cpuMetrics.iowait_percent : 0)
+        }
+
+        MetricRow {
+            Layout.fillWidth: true
+            accentColor: theme.memoryColor
+            label: "IRQ / SoftIRQ"
+            value: root.fmtPercent(root.cpuMetrics ? root.cpuMetrics.irq_percent : 0)
+                + " / " + root.fmtPercent(root.cpuMetrics ? root.cpuMetrics.softirq_percent : 0)
+        }
+
+        MetricRow {
+            visible: root.cpuMetrics && root.cpuMetrics.nice_percent > 0.1
+            Layout.fillWidth: true
+            accentColor: theme.cpuColor
+            label: "Nice"
+            value: root.fmtPercent(root.cpuMetrics ? root.cpuMetrics.nice_percent : 0)
+        }
+
+        MetricRow {
+            visible: root.cpuMetrics && root.cpuMetrics.guest_percent > 0.1
+            Layout.fillWidth: true
+            accentColor: theme.diskColor
+            label: "Guest"
+            value: root.fmtPercent(root.cpuMetrics ? root.cpuMetrics.guest_percent : 0)
+        }
+
         MetricRow {
             visible: root.cpuMetrics && root.cpuMetrics.steal_percent > 0.1
             Layout.fillWidth: true
@@ -135,16 +220,32 @@ ColumnLayout {
             rowSpacing: theme.spacingXS
 
             Repeater {
-                model: root.cpuMetrics && root.cpuMetrics.per_core_usage
-                    ? root.cpuMetrics.per_core_usage
+                model: root.cpuMetrics && root.cpuMetrics.core_classes
+                    ? root.cpuMetrics.core_classes
                     : []
 
+                delegate: MetricRow {
+                    Layout.fillWidth: true
+                    Layout.columnSpan: 2
+                    accentColor: modelData.core_type === "performance" ? theme.cpuColor : theme.successColor
+                    label: root.coreTypeLabel(modelData.core_type) + "s · "
+                        + modelData.physical_core_count + " núcleos / " + modelData.cpu_count + " threads"
+                    value: root.fmtPercent(modelData.usage_percent)
+                        + (modelData.frequency_mhz > 0 ? " · " + modelData.frequency_mhz + " MHz" : "")
+                }
+            }
+
+            Repeater {
+                model: root.coreRows()
+
                 delegate: MetricRow {
                     Layout.fillWidth: true
                     dense: true
-                    accentColor: theme.cpuColor
-                    label: "Core " + String(index + 1).padStart(2, "0")
-                    value: root.fmtPercent(modelData)
+                    accentColor: modelData.coreType === "efficiency" ? theme.successColor : theme.cpuColor
+                    label: root.coreTypeLabel(modelData.coreType) + " " + String(modelData.index + 1).padStart(2, "0")
+                        + (modelData.siblings.length > 0 ? " · SMT" : "")
+                    value: root.fmtPercent(modelData.usage)
+                        + (modelData.frequency > 0 ? " · " + modelData.frequency + " MHz" : "")
                 }
             }
         }
@@ -188,6 +289,50 @@ ColumnLayout {
             label: "Todos os núcleos"
             value: root.cpuMetrics ? root.cpuMetrics.frequency + " MHz" : "-"
         }
+
+        MetricRow {
+            visible: !!root.frequencyPolicy().governor
+            Layout.fillWidth: true
+            accentColor: theme.cpuColor
+            label: "Governor"
+            value: (root.frequencyPolicy().governor || "-")
+                + (root.frequencyPolicy().energy_performance_preference
+                    ? " · " + root.frequencyPolicy().energy_performance_preference : "")
+        }
+
+        MetricRow {
+            visible: root.frequencyPolicy().scaling_max_mhz !== undefined
+                && root.frequencyPolicy().scaling_max_mhz !== null
+            Layout.fillWidth: true
+            accentColor: theme.cpuColor
+            label: "Limites"
+            value: root.frequencyPolicy().scaling_min_mhz + "–" + root.frequencyPolicy().scaling_max_mhz + " MHz"
+        }
+
+        MetricRow {
+            visible: root.frequencyPolicy().boost_enabled !== undefined
+                && root.frequencyPolicy().boost_enabled !== null
+            Layout.fillWidth: true
+            accentColor: theme.warningColor
+            label: "Boost"
+            value: root.frequencyPolicy().boost_enabled ? "Ligado" : "Desligado"
+        }
+
+        MetricRow {
+            visible: (root.cpuMetrics.cores || []).some(core => core.core_throttle_count !== undefined
+                && core.core_throttle_count !== null)
+            Layout.fillWidth: true
+            accentColor: root.cpuMetrics.throttle && root.cpuMetrics.throttle.throttling
+                ? theme.dangerColor : theme.successColor
+            label: "Throttling térmico"
+            value: {
+                const throttle = root.cpuMetrics.throttle || {}
+                const state = throttle.throttling
+                    ? "Ativo em " + throttle.throttled_cpus.map(cpu => "cpu" + cpu).join(", ")
+                    : "Inativo"
+                return state + " · " + (throttle.core_throttle_count || 0) + " eventos"
+            }
+        }
     }
 
     Theme { id: theme }
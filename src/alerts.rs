@@ -0,0 +1,944 @@
+//! Regras de alerta avaliadas sobre as métricas coletadas.
+//!
+//! Cada regra aponta para um caminho no JSON de `GetMetricsJson` (por exemplo
+//! `sensors.hottest_cpu_celsius` ou `disk.disks.*.usage_percent`) e dispara quando o
+//! valor ultrapassa um limite, fica abaixo dele ou deixa de existir. Disparo e
+//! normalização passam por duração mínima, histerese e cooldown.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::process::Command;
+use zbus::{proxy, zvariant, Connection};
+
+use crate::subprocess::{run_command, CommandError, CommandOutput};
+
+const DEFAULT_COOLDOWN_MS: u64 = 60_000;
+const DEFAULT_COMMAND_TIMEOUT_MS: u64 = 30_000;
+const NOTIFICATION_APP_NAME: &str = "Monitor Tray";
+const NOTIFICATION_ICON: &str = "utilities-system-monitor";
+const URGENCY_NORMAL: u8 = 1;
+const URGENCY_CRITICAL: u8 = 2;
+
+fn default_cooldown_ms() -> u64 {
+    DEFAULT_COOLDOWN_MS
+}
+
+fn default_command_timeout_ms() -> u64 {
+    DEFAULT_COMMAND_TIMEOUT_MS
+}
+
+/// Regra declarada em `[[alerts]]` no arquivo de configuração.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertRule {
+    /// Identificador exibido na notificação e nos sinais.
+    pub name: String,
+    /// Caminho separado por pontos no JSON de métricas; `*` percorre listas e objetos.
+    pub metric: String,
+    /// Dispara quando o valor fica acima deste limite.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub above: Option<f64>,
+    /// Dispara quando o valor fica abaixo deste limite.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub below: Option<f64>,
+    /// Dispara quando a métrica está ausente (`null` ou inexistente).
+    #[serde(default)]
+    pub missing: bool,
+    /// Tempo que a condição precisa se manter antes do disparo.
+    #[serde(default)]
+    pub for_ms: u64,
+    /// Margem que o valor precisa recuar além do limite para o alerta ser normalizado.
+    #[serde(default)]
+    pub hysteresis: f64,
+    /// Intervalo mínimo entre dois disparos da mesma regra e instância.
+    #[serde(default = "default_cooldown_ms")]
+    pub cooldown_ms: u64,
+    /// Comando executado com `sh -c` a cada disparo e normalização.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Tempo máximo de execução de `command`; o processo é encerrado ao expirar.
+    #[serde(default = "default_command_timeout_ms")]
+    pub command_timeout_ms: u64,
+}
+
+impl AlertRule {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("alerta sem nome".to_string());
+        }
+        if self.metric.trim().is_empty() {
+            return Err(format!("alerta {:?}: metric vazio", self.name));
+        }
+
+        let conditions = [self.above.is_some(), self.below.is_some(), self.missing];
+        if conditions.iter().filter(|condition| **condition).count() != 1 {
+            return Err(format!(
+                "alerta {:?}: informe exatamente uma condição entre above, below e missing",
+                self.name
+            ));
+        }
+        if !self.hysteresis.is_finite() || self.hysteresis < 0.0 {
+            return Err(format!(
+                "alerta {:?}: hysteresis deve ser maior ou igual a zero",
+                self.name
+            ));
+        }
+        if self
+            .above
+            .into_iter()
+            .chain(self.below)
+            .any(|limit| !limit.is_finite())
+        {
+            return Err(format!("alerta {:?}: limite inválido", self.name));
+        }
+        if self
+            .command
+            .as_ref()
+            .is_some_and(|command| command.trim().is_empty())
+        {
+            return Err(format!("alerta {:?}: command vazio", self.name));
+        }
+
+        Ok(())
+    }
+
+    /// Limite configurado em `above` ou `below`; `None` para regras `missing`.
+    pub fn threshold(&self) -> Option<f64> {
+        self.above.or(self.below)
+    }
+
+    fn condition(&self) -> &'static str {
+        if self.above.is_some() {
+            "above"
+        } else if self.below.is_some() {
+            "below"
+        } else {
+            "missing"
+        }
+    }
+
+    fn is_triggered(&self, value: Option<f64>) -> bool {
+        match value {
+            None => self.missing,
+            Some(value) => {
+                self.above.is_some_and(|limit| value > limit)
+                    || self.below.is_some_and(|limit| value < limit)
+            }
+        }
+    }
+
+    fn is_cleared(&self, value: Option<f64>) -> bool {
+        match value {
+            None => false,
+            Some(_) if self.missing => true,
+            Some(value) => {
+                self.above
+                    .is_some_and(|limit| value <= limit - self.hysteresis)
+                    || self
+                        .below
+                        .is_some_and(|limit| value >= limit + self.hysteresis)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertEventKind {
+    Raised,
+    Cleared,
+}
+
+/// Transição de um alerta, usada como payload de `AlertRaised`/`AlertCleared`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertEvent {
+    pub kind: AlertEventKind,
+    pub rule: String,
+    /// Caminho concreto da métrica, com `*` substituído pela instância avaliada.
+    pub metric: String,
+    pub value: Option<f64>,
+    pub threshold: Option<f64>,
+}
+
+impl AlertEvent {
+    fn key(&self) -> String {
+        format!("{}\u{0}{}", self.rule, self.metric)
+    }
+
+    pub fn summary(&self) -> String {
+        match self.kind {
+            AlertEventKind::Raised => format!("Alerta: {}", self.rule),
+            AlertEventKind::Cleared => format!("Normalizado: {}", self.rule),
+        }
+    }
+
+    pub fn body(&self) -> String {
+        match self.value {
+            Some(value) => format!("{} = {value:.1}", self.metric),
+            None => format!("{} indisponível", self.metric),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct InstanceState {
+    pending_since: Option<Instant>,
+    active: bool,
+    last_raised: Option<Instant>,
+}
+
+/// Identificador legível de um item de lista: ponto de montagem, rótulo ou nome.
+fn element_label(index: usize, element: &Value) -> String {
+    ["mount_point", "label", "name"]
+        .iter()
+        .find_map(|field| element.get(field).and_then(Value::as_str))
+        .map(str::to_string)
+        .unwrap_or_else(|| index.to_string())
+}
+
+fn resolve_into(
+    node: &Value,
+    segments: &[&str],
+    prefix: String,
+    matches: &mut Vec<(String, Option<f64>)>,
+) {
+    let Some((segment, rest)) = segments.split_first() else {
+        let value = match node {
+            Value::Number(number) => number.as_f64(),
+            Value::Bool(flag) => Some(if *flag { 1.0 } else { 0.0 }),
+            _ => None,
+        };
+        matches.push((prefix, value));
+        return;
+    };
+
+    let join = |label: &str| {
+        if prefix.is_empty() {
+            label.to_string()
+        } else {
+            format!("{prefix}.{label}")
+        }
+    };
+
+    match (node, *segment) {
+        (Value::Array(items), "*") => {
+            for (index, item) in items.iter().enumerate() {
+                let label = format!("{prefix}[{}]", element_label(index, item));
+                resolve_into(item, rest, label, matches);
+            }
+        }
+        (Value::Object(fields), "*") => {
+            for (key, item) in fields {
+                resolve_into(item, rest, join(key), matches);
+            }
+        }
+        (Value::Array(items), index) => {
+            if let Some(item) = index
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get(index))
+            {
+                resolve_into(item, rest, join(index), matches);
+            }
+        }
+        (Value::Object(fields), key) => {
+            if let Some(item) = fields.get(key) {
+                resolve_into(item, rest, join(key), matches);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Resolve `path` em `metrics`, retornando o caminho concreto e o valor de cada ocorrência.
+pub fn resolve_metric(metrics: &Value, path: &str) -> Vec<(String, Option<f64>)> {
+    let segments: Vec<&str> = path.split('.').collect();
+    let mut matches = Vec::new();
+    resolve_into(metrics, &segments, String::new(), &mut matches);
+    matches
+}
+
+/// Mantém o estado de cada regra entre ciclos do atualizador.
+#[derive(Debug, Default)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    states: HashMap<(String, String), InstanceState>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self {
+            rules,
+            states: HashMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn rule(&self, name: &str) -> Option<&AlertRule> {
+        self.rules.iter().find(|rule| rule.name == name)
+    }
+
+    /// Troca as regras preservando o estado das que continuam com o mesmo nome.
+    /// Alertas ativos de regras removidas emitem `Cleared` sem valor, para que a
+    /// notificação não fique órfã.
+    pub fn set_rules(&mut self, rules: Vec<AlertRule>) -> Vec<AlertEvent> {
+        let mut dropped: Vec<(String, String)> = self
+            .states
+            .iter()
+            .filter(|((rule, _), _)| !rules.iter().any(|candidate| candidate.name == *rule))
+            .map(|(key, _)| key.clone())
+            .collect();
+        dropped.sort_unstable();
+
+        let mut events = Vec::new();
+        for key in dropped {
+            let Some(state) = self.states.remove(&key) else {
+                continue;
+            };
+            if state.active {
+                let (rule, metric) = key;
+                events.push(AlertEvent {
+                    kind: AlertEventKind::Cleared,
+                    threshold: self.rule(&rule).and_then(AlertRule::threshold),
+                    rule,
+                    metric,
+                    value: None,
+                });
+            }
+        }
+        self.rules = rules;
+        events
+    }
+
+    /// Avalia as regras em `metrics`. Instâncias que deixaram de resolver (disco desmontado,
+    /// GPU removida) têm o estado descartado e, se o alerta estava ativo, emitem `Cleared`
+    /// sem valor.
+    pub fn evaluate(&mut self, metrics: &Value, now: Instant) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+
+        for rule in &self.rules {
+            let mut instances = resolve_metric(metrics, &rule.metric);
+            if rule.missing {
+                // Para `missing` a regra inteira é uma única instância.
+                let value = instances.iter().find_map(|(_, value)| *value);
+                instances = vec![(rule.metric.clone(), value)];
+            }
+            let mut resolved = HashSet::new();
+
+            for (metric, value) in instances {
+                if value.is_none() && !rule.missing {
+                    continue;
+                }
+                resolved.insert(metric.clone());
+
+                let state = self
+                    .states
+                    .entry((rule.name.clone(), metric.clone()))
+                    .or_default();
+                let event = |kind| AlertEvent {
+                    kind,
+                    rule: rule.name.clone(),
+                    metric: metric.clone(),
+                    value,
+                    threshold: rule.threshold(),
+                };
+
+                if state.active {
+                    if rule.is_cleared(value) {
+                        state.active = false;
+                        state.pending_since = None;
+                        events.push(event(AlertEventKind::Cleared));
+                    }
+                    continue;
+                }
+
+                if !rule.is_triggered(value) {
+                    state.pending_since = None;
+                    continue;
+                }
+
+                let pending_since = *state.pending_since.get_or_insert(now);
+                let held = now.duration_since(pending_since) >= Duration::from_millis(rule.for_ms);
+                let cooled_down = state.last_raised.is_none_or(|last| {
+                    now.duration_since(last) >= Duration::from_millis(rule.cooldown_ms)
+                });
+                if held && cooled_down {
+                    state.active = true;
+                    state.last_raised = Some(now);
+                    events.push(event(AlertEventKind::Raised));
+                }
+            }
+
+            let mut gone: Vec<String> = self
+                .states
+                .keys()
+                .filter(|(name, metric)| *name == rule.name && !resolved.contains(metric))
+                .map(|(_, metric)| metric.clone())
+                .collect();
+            gone.sort_unstable();
+            for metric in gone {
+                let Some(state) = self.states.remove(&(rule.name.clone(), metric.clone())) else {
+                    continue;
+                };
+                if state.active {
+                    events.push(AlertEvent {
+                        kind: AlertEventKind::Cleared,
+                        rule: rule.name.clone(),
+                        metric,
+                        value: None,
+                        threshold: rule.threshold(),
+                    });
+                }
+            }
+        }
+
+        events
+    }
+}
+
+fn format_optional(value: Option<f64>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+/// Executa o `command` da regra para `event`.
+///
+/// Regra, métrica, valor e limite chegam em variáveis `MONITOR_TRAY_ALERT_*`; o stdin
+/// recebe um JSON com o evento e o snapshot de métricas avaliado.
+pub async fn run_alert_command(
+    rule: &AlertRule,
+    event: &AlertEvent,
+    metrics: &Value,
+) -> Result<String, String> {
+    let Some(command_line) = rule.command.as_deref() else {
+        return Ok(String::new());
+    };
+    let kind = match event.kind {
+        AlertEventKind::Raised => "raised",
+        AlertEventKind::Cleared => "cleared",
+    };
+    let input = serde_json::to_vec(&json!({ "event": event, "metrics": metrics }))
+        .map_err(|error| error.to_string())?;
+
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(command_line)
+        .env("MONITOR_TRAY_ALERT_EVENT", kind)
+        .env("MONITOR_TRAY_ALERT_RULE", &event.rule)
+        .env("MONITOR_TRAY_ALERT_METRIC", &event.metric)
+        .env("MONITOR_TRAY_ALERT_VALUE", format_optional(event.value))
+        .env(
+            "MONITOR_TRAY_ALERT_THRESHOLD",
+            format_optional(event.threshold),
+        )
+        .env("MONITOR_TRAY_ALERT_CONDITION", rule.condition());
+
+    let output = run_command(
+        &mut command,
+        Some(input),
+        std::future::pending(),
+        Duration::from_millis(rule.command_timeout_ms),
+    )
+    .await
+    .map_err(|error| match error {
+        CommandError::NotFound => "`sh` não encontrado".to_string(),
+        CommandError::Spawn(error) => format!("falha ao iniciar comando: {error}"),
+        CommandError::Wait(error) => format!("falha ao aguardar comando: {error}"),
+        CommandError::Cancelled => "comando cancelado".to_string(),
+        CommandError::TimedOut => format!(
+            "comando excedeu o tempo limite de {} ms",
+            rule.command_timeout_ms
+        ),
+    })?;
+
+    let CommandOutput {
+        status,
+        stdout,
+        stderr,
+    } = output;
+    if !status.success() {
+        let detail = if stderr.is_empty() { stdout } else { stderr };
+        return Err(format!("comando terminou com {status}: {detail}"));
+    }
+    Ok(stdout)
+}
+
+#[proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, zvariant::Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+}
+
+/// Envia notificações de desktop, reaproveitando a notificação anterior da mesma
+/// regra e instância para que a normalização substitua o aviso de disparo.
+#[derive(Debug, Default)]
+pub struct AlertNotifier {
+    notification_ids: HashMap<String, u32>,
+}
+
+impl AlertNotifier {
+    pub async fn notify(
+        &mut self,
+        connection: &Connection,
+        event: &AlertEvent,
+    ) -> zbus::Result<()> {
+        let proxy = NotificationsProxy::new(connection).await?;
+        let key = event.key();
+        let replaces_id = self.notification_ids.get(&key).copied().unwrap_or(0);
+        let urgency = match event.kind {
+            AlertEventKind::Raised => URGENCY_CRITICAL,
+            AlertEventKind::Cleared => URGENCY_NORMAL,
+        };
+        let hints = HashMap::from([("urgency", zvariant::Value::U8(urgency))]);
+
+        let id = proxy
+            .notify(
+                NOTIFICATION_APP_NAME,
+                replaces_id,
+                NOTIFICATION_ICON,
+                &event.summary(),
+                &event.body(),
+                &[],
+                hints,
+                -1,
+            )
+            .await?;
+
+        match event.kind {
+            AlertEventKind::Raised => {
+                self.notification_ids.insert(key, id);
+            }
+            AlertEventKind::Cleared => {
+                self.notification_ids.remove(&key);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use serde_json::json;
+    use zbus::interface;
+
+    use super::*;
+
+    fn rule(metric: &str) -> AlertRule {
+        AlertRule {
+            name: "teste".to_string(),
+            metric: metric.to_string(),
+            above: None,
+            below: None,
+            missing: false,
+            for_ms: 0,
+            hysteresis: 0.0,
+            cooldown_ms: 0,
+            command: None,
+            command_timeout_ms: DEFAULT_COMMAND_TIMEOUT_MS,
+        }
+    }
+
+    fn cpu_temperature(value: f64) -> Value {
+        json!({ "sensors": { "hottest_cpu_celsius": value } })
+    }
+
+    fn kinds(events: &[AlertEvent]) -> Vec<AlertEventKind> {
+        events.iter().map(|event| event.kind).collect()
+    }
+
+    #[test]
+    fn test_validate_requires_exactly_one_condition() {
+        assert!(rule("cpu.usage_percent").validate().is_err());
+        assert!(AlertRule {
+            above: Some(90.0),
+            missing: true,
+            ..rule("cpu.usage_percent")
+        }
+        .validate()
+        .is_err());
+        assert!(AlertRule {
+            above: Some(90.0),
+            ..rule("cpu.usage_percent")
+        }
+        .validate()
+        .is_ok());
+    }
+
+    #[test]
+    fn test_resolve_metric_expands_wildcards_with_element_labels() {
+        let metrics = json!({
+            "disk": { "disks": [
+                { "mount_point": "/", "usage_percent": 40.0 },
+                { "mount_point": "/home", "usage_percent": 97.0 }
+            ] },
+            "load_average": [1.0, 2.0, 3.0]
+        });
+
+        assert_eq!(
+            resolve_metric(&metrics, "disk.disks.*.usage_percent"),
+            vec![
+                ("disk.disks[/].usage_percent".to_string(), Some(40.0)),
+                ("disk.disks[/home].usage_percent".to_string(), Some(97.0)),
+            ]
+        );
+        assert_eq!(
+            resolve_metric(&metrics, "load_average.1"),
+            vec![("load_average.1".to_string(), Some(2.0))]
+        );
+        assert!(resolve_metric(&metrics, "network.gateway_latency_ms").is_empty());
+    }
+
+    #[test]
+    fn test_alert_waits_for_duration_and_clears_with_hysteresis() {
+        let mut engine = AlertEngine::new(vec![AlertRule {
+            above: Some(90.0),
+            for_ms: 10_000,
+            hysteresis: 5.0,
+            ..rule("sensors.hottest_cpu_celsius")
+        }]);
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+
+        assert!(engine.evaluate(&cpu_temperature(95.0), at(0)).is_empty());
+        assert!(engine.evaluate(&cpu_temperature(95.0), at(5)).is_empty());
+        let raised = engine.evaluate(&cpu_temperature(96.0), at(10));
+        assert_eq!(kinds(&raised), vec![AlertEventKind::Raised]);
+        assert_eq!(raised[0].value, Some(96.0));
+
+        // Dentro da faixa de histerese o alerta continua ativo.
+        assert!(engine.evaluate(&cpu_temperature(88.0), at(11)).is_empty());
+        assert_eq!(
+            kinds(&engine.evaluate(&cpu_temperature(85.0), at(12))),
+            vec![AlertEventKind::Cleared]
+        );
+    }
+
+    #[test]
+    fn test_condition_interrupted_before_duration_does_not_fire() {
+        let mut engine = AlertEngine::new(vec![AlertRule {
+            above: Some(90.0),
+            for_ms: 10_000,
+            ..rule("sensors.hottest_cpu_celsius")
+        }]);
+        let start = Instant::now();
+
+        engine.evaluate(&cpu_temperature(95.0), start);
+        engine.evaluate(&cpu_temperature(80.0), start + Duration::from_secs(6));
+        let events = engine.evaluate(&cpu_temperature(95.0), start + Duration::from_secs(12));
+
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_cooldown_delays_next_raise() {
+        let mut engine = AlertEngine::new(vec![AlertRule {
+            above: Some(90.0),
+            cooldown_ms: 60_000,
+            ..rule("sensors.hottest_cpu_celsius")
+        }]);
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+
+        assert_eq!(engine.evaluate(&cpu_temperature(95.0), at(0)).len(), 1);
+        assert_eq!(engine.evaluate(&cpu_temperature(80.0), at(1)).len(), 1);
+        assert!(engine.evaluate(&cpu_temperature(95.0), at(30)).is_empty());
+        assert_eq!(
+            kinds(&engine.evaluate(&cpu_temperature(95.0), at(61))),
+            vec![AlertEventKind::Raised]
+        );
+    }
+
+    #[test]
+    fn test_missing_metric_rule_fires_and_clears() {
+        let mut engine = AlertEngine::new(vec![AlertRule {
+            missing: true,
+            for_ms: 30_000,
+            ..rule("network.gateway_latency_ms")
+        }]);
+        let start = Instant::now();
+        let without_latency = json!({ "network": { "gateway_latency_ms": null } });
+
+        assert!(engine.evaluate(&without_latency, start).is_empty());
+        let raised = engine.evaluate(&without_latency, start + Duration::from_secs(30));
+        assert_eq!(kinds(&raised), vec![AlertEventKind::Raised]);
+        assert_eq!(raised[0].value, None);
+
+        let cleared = engine.evaluate(
+            &json!({ "network": { "gateway_latency_ms": 3.5 } }),
+            start + Duration::from_secs(31),
+        );
+        assert_eq!(kinds(&cleared), vec![AlertEventKind::Cleared]);
+    }
+
+    #[test]
+    fn test_wildcard_rules_track_each_instance() {
+        let mut engine = AlertEngine::new(vec![AlertRule {
+            above: Some(95.0),
+            ..rule("disk.disks.*.usage_percent")
+        }]);
+        let metrics = json!({ "disk": { "disks": [
+            { "mount_point": "/", "usage_percent": 96.0 },
+            { "mount_point": "/home", "usage_percent": 50.0 }
+        ] } });
+
+        let events = engine.evaluate(&metrics, Instant::now());
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].metric, "disk.disks[/].usage_percent");
+    }
+
+    #[test]
+    fn test_vanished_instance_clears_active_alert_and_drops_state() {
+        let mut engine = AlertEngine::new(vec![AlertRule {
+            above: Some(95.0),
+            ..rule("disk.disks.*.usage_percent")
+        }]);
+        let start = Instant::now();
+        let mounted = json!({ "disk": { "disks": [
+            { "mount_point": "/", "usage_percent": 50.0 },
+            { "mount_point": "/mnt/usb", "usage_percent": 99.0 }
+        ] } });
+        let unmounted = json!({ "disk": { "disks": [
+            { "mount_point": "/", "usage_percent": 50.0 }
+        ] } });
+
+        assert_eq!(
+            kinds(&engine.evaluate(&mounted, start)),
+            vec![AlertEventKind::Raised]
+        );
+        let cleared = engine.evaluate(&unmounted, start + Duration::from_secs(1));
+        assert_eq!(kinds(&cleared), vec![AlertEventKind::Cleared]);
+        assert_eq!(cleared[0].metric, "disk.disks[/mnt/usb].usage_percent");
+        assert_eq!(cleared[0].value, None);
+        assert_eq!(engine.states.len(), 1);
+
+        assert!(engine
+            .evaluate(&unmounted, start + Duration::from_secs(2))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_set_rules_clears_active_alerts_of_removed_rules() {
+        let mut engine = AlertEngine::new(vec![
+            AlertRule {
+                above: Some(90.0),
+                ..rule("sensors.hottest_cpu_celsius")
+            },
+            AlertRule {
+                name: "kept".to_string(),
+                above: Some(90.0),
+                ..rule("sensors.hottest_cpu_celsius")
+            },
+        ]);
+        let kept = engine.rule("kept").cloned().expect("rule should exist");
+        assert_eq!(
+            engine
+                .evaluate(&cpu_temperature(95.0), Instant::now())
+                .len(),
+            2
+        );
+
+        let cleared = engine.set_rules(vec![kept]);
+
+        assert_eq!(kinds(&cleared), vec![AlertEventKind::Cleared]);
+        assert_eq!(cleared[0].rule, "teste");
+        assert_eq!(cleared[0].metric, "sensors.hottest_cpu_celsius");
+        assert_eq!(cleared[0].value, None);
+        assert_eq!(cleared[0].threshold, Some(90.0));
+        assert_eq!(engine.states.len(), 1);
+        assert_eq!(engine.set_rules(Vec::new()).len(), 1);
+        assert!(engine.states.is_empty());
+    }
+
+    /// `(replaces_id, summary, body, urgency)` de cada chamada recebida.
+    type ReceivedNotification = (u32, String, String, u8);
+
+    #[tokio::test]
+    async fn test_alert_command_receives_environment_and_stdin_snapshot() {
+        let rule = AlertRule {
+            above: Some(90.0),
+            command: Some(
+                "echo \"$MONITOR_TRAY_ALERT_EVENT $MONITOR_TRAY_ALERT_METRIC \
+                 $MONITOR_TRAY_ALERT_VALUE $MONITOR_TRAY_ALERT_THRESHOLD \
+                 $MONITOR_TRAY_ALERT_CONDITION\"; cat"
+                    .to_string(),
+            ),
+            ..rule("sensors.hottest_cpu_celsius")
+        };
+        let event = AlertEvent {
+            kind: AlertEventKind::Raised,
+            rule: rule.name.clone(),
+            metric: rule.metric.clone(),
+            value: Some(92.5),
+            threshold: rule.threshold(),
+        };
+
+        let output = run_alert_command(&rule, &event, &cpu_temperature(92.5))
+            .await
+            .expect("command should succeed");
+        let (environment, stdin) = output.split_once('\n').expect("should print two parts");
+
+        assert_eq!(
+            environment.split_whitespace().collect::<Vec<_>>(),
+            vec![
+                "raised",
+                "sensors.hottest_cpu_celsius",
+                "92.5",
+                "90",
+                "above"
+            ]
+        );
+        let stdin: Value = serde_json::from_str(stdin).expect("stdin should be JSON");
+        assert_eq!(stdin["event"]["rule"], "teste");
+        assert_eq!(stdin["metrics"]["sensors"]["hottest_cpu_celsius"], 92.5);
+    }
+
+    #[tokio::test]
+    async fn test_alert_command_reports_failures_and_timeouts() {
+        let event = AlertEvent {
+            kind: AlertEventKind::Cleared,
+            rule: "teste".to_string(),
+            metric: "cpu.usage_percent".to_string(),
+            value: None,
+            threshold: None,
+        };
+        let failing = AlertRule {
+            missing: true,
+            command: Some("echo quebrou >&2; exit 3".to_string()),
+            ..rule("cpu.usage_percent")
+        };
+        let slow = AlertRule {
+            command: Some("sleep 5".to_string()),
+            command_timeout_ms: 50,
+            ..failing.clone()
+        };
+
+        let error = run_alert_command(&failing, &event, &Value::Null)
+            .await
+            .expect_err("non-zero exit should fail");
+        assert!(error.contains("quebrou"));
+        let error = run_alert_command(&slow, &event, &Value::Null)
+            .await
+            .expect_err("slow command should time out");
+        assert!(error.contains("tempo limite"));
+    }
+
+    struct FakeNotifications {
+        received: Arc<Mutex<Vec<ReceivedNotification>>>,
+    }
+
+    #[interface(name = "org.freedesktop.Notifications")]
+    impl FakeNotifications {
+        #[allow(clippy::too_many_arguments)]
+        fn notify(
+            &self,
+            _app_name: &str,
+            replaces_id: u32,
+            _app_icon: &str,
+            summary: &str,
+            body: &str,
+            _actions: Vec<String>,
+            hints: HashMap<String, zvariant::OwnedValue>,
+            _expire_timeout: i32,
+        ) -> u32 {
+            let urgency = hints
+                .get("urgency")
+                .and_then(|value| u8::try_from(value).ok())
+                .unwrap_or_default();
+            let mut received = self.received.lock().unwrap();
+            received.push((replaces_id, summary.to_string(), body.to_string(), urgency));
+            received.len() as u32 + 41
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notifier_sends_and_replaces_notifications_on_private_bus() {
+        let (server_stream, client_stream) =
+            tokio::net::UnixStream::pair().expect("should create socket pair");
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let guid = zbus::Guid::generate();
+
+        let (server, client) = tokio::try_join!(
+            zbus::connection::Builder::unix_stream(server_stream)
+                .server(guid)
+                .expect("guid should be valid")
+                .p2p()
+                .serve_at(
+                    "/org/freedesktop/Notifications",
+                    FakeNotifications {
+                        received: received.clone(),
+                    },
+                )
+                .expect("should register fake server")
+                .build(),
+            zbus::connection::Builder::unix_stream(client_stream)
+                .p2p()
+                .build(),
+        )
+        .expect("p2p connections should be established");
+
+        let mut notifier = AlertNotifier::default();
+        let raised = AlertEvent {
+            kind: AlertEventKind::Raised,
+            rule: "cpu quente".to_string(),
+            metric: "sensors.hottest_cpu_celsius".to_string(),
+            value: Some(93.0),
+            threshold: Some(90.0),
+        };
+        let cleared = AlertEvent {
+            kind: AlertEventKind::Cleared,
+            value: Some(70.0),
+            ..raised.clone()
+        };
+        notifier
+            .notify(&client, &raised)
+            .await
+            .expect("raise notification should be delivered");
+        notifier
+            .notify(&client, &cleared)
+            .await
+            .expect("clear notification should be delivered");
+
+        let received = received.lock().unwrap().clone();
+        assert_eq!(
+            received,
+            vec![
+                (
+                    0,
+                    "Alerta: cpu quente".to_string(),
+                    "sensors.hottest_cpu_celsius = 93.0".to_string(),
+                    URGENCY_CRITICAL
+                ),
+                (
+                    42,
+                    "Normalizado: cpu quente".to_string(),
+                    "sensors.hottest_cpu_celsius = 70.0".to_string(),
+                    URGENCY_NORMAL
+                ),
+            ]
+        );
+        drop(server);
+    }
+}
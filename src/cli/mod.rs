@@ -0,0 +1,941 @@
+//! Interface de linha de comando: subcomandos, seleção de seções e modos sem DBus.
+
+pub mod statusbar;
+pub mod table;
+
+use std::error::Error;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::time::MissedTickBehavior;
+
+use crate::collect_metrics;
+use crate::config::{config_path, load_config, MonitorConfig};
+use crate::dbus::client::connect_backend;
+use crate::dbus::run_dbus_service;
+use crate::exporters::mqtt::{
+    run_mqtt, MqttBroker, MqttGroup, MqttSettings, DEFAULT_DISCOVERY_PREFIX, DEFAULT_TOPIC_PREFIX,
+    PASSWORD_ENV,
+};
+use crate::exporters::prometheus::{run_exporter, ListenAddress, DEFAULT_LISTEN_ADDRESS};
+use crate::exporters::push::{
+    run_push, PushEndpoint, PushFormat, PushSink, DEFAULT_BATCH_SIZE, DEFAULT_PREFIX,
+};
+use crate::exporters::textfile::run_textfile;
+use crate::monitor::{
+    history_path, CpuMetrics, DiskMetrics, GpuInfo, HistoryMetrics, HistoryRange, MemoryMetrics,
+    NetworkMetrics, NetworkSpeedTestState, PressureMetrics, ProcessInfo, SensorMetrics,
+    SlowSubsystems, SystemInfo, SystemMetrics, SystemMonitor,
+};
+use crate::recorder::query_snapshots;
+use crate::speedtest::NetworkSpeedTestManager;
+use crate::tray::{run_tray, TrayIndicator};
+use crate::tui::run_tui;
+use crate::unix_time::unix_now;
+
+use self::statusbar::{
+    run_statusbar, BarFormat, StatusBar, Template, Threshold, DEFAULT_CRITICAL_COLOR,
+    DEFAULT_TEMPLATE, DEFAULT_TOOLTIP, DEFAULT_WARNING_COLOR,
+};
+
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+/// Abaixo disso a janela de medição de CPU (~200 ms) domina o intervalo.
+pub const MIN_WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "monitor-tray",
+    version,
+    about = "Backend de métricas do Monitor Tray",
+    after_help = "Sem subcomando, o binário inicia em modo DBus."
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Inicia o backend DBus para o Plasmoid KDE
+    Dbus,
+    /// Imprime métricas em JSON, uma vez ou continuamente (NDJSON)
+    Json(JsonArgs),
+    /// Imprime o histórico do serviço em execução ou, sem ele, o último checkpoint salvo
+    History(HistoryArgs),
+    /// Executa um teste manual de velocidade e imprime o resultado
+    Speedtest(OutputArgs),
+    /// Interface interativa no terminal com as abas do plasmoid
+    Tui(TuiArgs),
+    /// Serve as métricas no formato Prometheus em `/metrics`
+    Prometheus(PrometheusArgs),
+    /// Grava periodicamente um `.prom` para o textfile collector do node_exporter
+    Textfile(TextfileArgs),
+    /// Envia as métricas em line protocol do InfluxDB ou plaintext do Graphite
+    Push(PushArgs),
+    /// Publica sensores, GPU, CPU e memória via MQTT com discovery do Home Assistant
+    Mqtt(MqttArgs),
+    /// Exporta os snapshots gravados em SQLite num intervalo de tempo
+    Query(QueryArgs),
+    /// Linha para Waybar, i3bar/swaybar ou polybar a partir de um template
+    Bar(BarArgs),
+    /// Ícone StatusNotifierItem para bandejas fora do Plasma (GNOME, XFCE, sway)
+    Tray(TrayArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct OutputArgs {
+    /// JSON indentado
+    #[arg(long)]
+    pub pretty: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct JsonArgs {
+    /// Seções incluídas, separadas por vírgula; sem a opção, imprime o snapshot completo
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub section: Vec<Section>,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    /// Formato da saída: JSON ou tabelas legíveis no terminal
+    #[arg(long, value_enum, default_value_t = OutputFormat::Json)]
+    pub format: OutputFormat,
+    /// Imprime uma amostra por linha até ser interrompido
+    #[arg(long, conflicts_with = "pretty")]
+    pub watch: bool,
+    /// Intervalo entre amostras do `--watch` (`500ms`, `2s` ou segundos)
+    #[arg(long, value_parser = parse_duration, default_value = "1s", requires = "watch")]
+    pub interval: Duration,
+    /// Encerra o `--watch` após N amostras
+    #[arg(long, requires = "watch")]
+    pub count: Option<u64>,
+}
+
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    /// Série do histórico, como `cpu_usage` ou `sensor_hottest_cpu_temperature`
+    #[arg(long)]
+    pub series: Option<String>,
+    /// Agregados de longo prazo da série: `1h`, `24h` ou `7d`
+    #[arg(long, value_parser = parse_history_range, requires = "series")]
+    pub range: Option<HistoryRange>,
+    /// Exporta as amostras em CSV, uma linha por instante; `--series` aceita várias séries
+    /// separadas por vírgula
+    #[arg(long, conflicts_with_all = ["range", "pretty"])]
+    pub csv: bool,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct QueryArgs {
+    /// Início em UTC: `2024-05-01`, `2024-05-01T14:30`, segundos Unix ou uma duração atrás (`6h`, `7d`)
+    #[arg(long, value_parser = parse_time, default_value = "24h")]
+    pub from: u64,
+    /// Fim, no mesmo formato; sem a opção, agora
+    #[arg(long, value_parser = parse_time)]
+    pub to: Option<u64>,
+    /// Banco SQLite; sem a opção, o `[recorder].path` da configuração
+    #[arg(long)]
+    pub database: Option<PathBuf>,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Table,
+}
+
+#[derive(Debug, Args)]
+pub struct TuiArgs {
+    /// Coleta no próprio processo mesmo com o serviço DBus em execução
+    #[arg(long)]
+    pub local: bool,
+    /// Intervalo de atualização (`500ms`, `2s` ou segundos)
+    #[arg(long, value_parser = parse_duration, default_value = "1s")]
+    pub interval: Duration,
+}
+
+#[derive(Debug, Args)]
+pub struct TrayArgs {
+    /// Valor desenhado no ícone; um clique alterna entre eles
+    #[arg(long, value_enum, default_value_t = TrayIndicator::Cpu)]
+    pub indicator: TrayIndicator,
+    /// Coleta no próprio processo mesmo com o serviço DBus em execução
+    #[arg(long)]
+    pub local: bool,
+    /// Intervalo de atualização (`500ms`, `2s` ou segundos)
+    #[arg(long, value_parser = parse_duration, default_value = "2s")]
+    pub interval: Duration,
+}
+
+#[derive(Debug, Args)]
+pub struct PrometheusArgs {
+    /// `127.0.0.1:PORTA`, `[::1]:PORTA` ou `unix:/caminho/do/socket`
+    #[arg(long, default_value = DEFAULT_LISTEN_ADDRESS)]
+    pub listen: ListenAddress,
+    /// Intervalo de coleta entre scrapes (`500ms`, `2s` ou segundos)
+    #[arg(long, value_parser = parse_duration, default_value = "1s")]
+    pub interval: Duration,
+}
+
+#[derive(Debug, Args)]
+pub struct TextfileArgs {
+    /// Diretório do textfile collector (`--collector.textfile.directory`)
+    #[arg(long)]
+    pub dir: PathBuf,
+    /// Intervalo entre gravações (`500ms`, `2s` ou segundos)
+    #[arg(long, value_parser = parse_duration, default_value = "15s")]
+    pub interval: Duration,
+    /// Grava o arquivo uma vez e sai, para uso com cron ou timers do systemd
+    #[arg(long)]
+    pub once: bool,
+    /// Executa um teste de velocidade local a cada intervalo (por exemplo `6h`)
+    #[arg(long, value_parser = parse_duration, conflicts_with = "once")]
+    pub speedtest_interval: Option<Duration>,
+}
+
+#[derive(Debug, Args)]
+pub struct BarArgs {
+    /// Protocolo da barra de status
+    #[arg(long, value_enum, default_value_t = BarFormat::Waybar)]
+    pub format: BarFormat,
+    /// Texto da barra, com campos como `{cpu.usage_percent}` ou `{sensors.hottest_cpu_celsius:.1}`
+    #[arg(long, default_value = DEFAULT_TEMPLATE)]
+    pub template: Template,
+    /// Tooltip do Waybar, no mesmo formato; `\n` quebra a linha
+    #[arg(long, default_value = DEFAULT_TOOLTIP)]
+    pub tooltip: Template,
+    /// Estado de alerta quando a métrica passa do limite (`cpu.usage_percent>80`); repetível
+    #[arg(long)]
+    pub warning: Vec<Threshold>,
+    /// Estado crítico, no mesmo formato de `--warning`; repetível
+    #[arg(long)]
+    pub critical: Vec<Threshold>,
+    /// Cor do estado de alerta no i3bar e no polybar
+    #[arg(long, default_value = DEFAULT_WARNING_COLOR)]
+    pub warning_color: String,
+    /// Cor do estado crítico no i3bar e no polybar
+    #[arg(long, default_value = DEFAULT_CRITICAL_COLOR)]
+    pub critical_color: String,
+    /// Intervalo entre atualizações (`500ms`, `2s` ou segundos)
+    #[arg(long, value_parser = parse_duration, default_value = "2s")]
+    pub interval: Duration,
+    /// Imprime uma única atualização e sai, para o `interval` do módulo do Waybar
+    #[arg(long)]
+    pub once: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct PushArgs {
+    /// Destino `udp://host:porta` ou `tcp://host:porta`
+    #[arg(long)]
+    pub endpoint: PushEndpoint,
+    /// Formato das linhas enviadas
+    #[arg(long, value_enum, default_value_t = PushFormat::Influx)]
+    pub format: PushFormat,
+    /// Intervalo entre coletas (`500ms`, `2s` ou segundos)
+    #[arg(long, value_parser = parse_duration, default_value = "10s")]
+    pub interval: Duration,
+    /// Intervalo máximo entre envios; linhas de várias coletas seguem no mesmo lote
+    #[arg(long, value_parser = parse_duration, default_value = "10s")]
+    pub flush_interval: Duration,
+    /// Linhas por lote; um lote cheio é enviado sem esperar o `--flush-interval`
+    #[arg(long, default_value_t = DEFAULT_BATCH_SIZE)]
+    pub batch_size: usize,
+    /// Prefixo dos caminhos no Graphite
+    #[arg(long, default_value = DEFAULT_PREFIX)]
+    pub prefix: String,
+}
+
+#[derive(Debug, Args)]
+pub struct MqttArgs {
+    /// Broker `mqtt://host[:porta]`; a senha, se houver, vem de `MONITOR_TRAY_MQTT_PASSWORD`
+    #[arg(long)]
+    pub broker: MqttBroker,
+    /// Usuário do broker
+    #[arg(long)]
+    pub username: Option<String>,
+    /// Grupos publicados, separados por vírgula
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = MqttGroup::ALL)]
+    pub publish: Vec<MqttGroup>,
+    /// Prefixo dos tópicos de estado e disponibilidade (`<prefixo>/<host>/state`)
+    #[arg(long, default_value = DEFAULT_TOPIC_PREFIX)]
+    pub topic_prefix: String,
+    /// Prefixo do MQTT discovery configurado no Home Assistant
+    #[arg(long, default_value = DEFAULT_DISCOVERY_PREFIX)]
+    pub discovery_prefix: String,
+    /// Intervalo entre publicações (`500ms`, `2s` ou segundos)
+    #[arg(long, value_parser = parse_duration, default_value = "10s")]
+    pub interval: Duration,
+}
+
+/// Seções de `json --section`, agrupadas pelo mesmo corte rápido/lento/histórico do backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Section {
+    Cpu,
+    Memory,
+    Disk,
+    Network,
+    /// Pressure Stall Information de `/proc/pressure` e do cgroup configurado
+    #[value(alias = "psi")]
+    Pressure,
+    /// `uptime`, `load_average` e `system_info`
+    #[value(alias = "system_info")]
+    System,
+    Sensors,
+    #[value(alias = "gpus")]
+    Gpu,
+    #[value(alias = "top_processes")]
+    Processes,
+    History,
+}
+
+impl Section {
+    fn is_fast(self) -> bool {
+        matches!(
+            self,
+            Section::Cpu
+                | Section::Memory
+                | Section::Disk
+                | Section::Network
+                | Section::Pressure
+                | Section::System
+        )
+    }
+}
+
+/// Aceita os modos antigos `--dbus`, `--json` e `--watch` como atalhos dos subcomandos.
+pub fn normalize_legacy_args(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut args: Vec<String> = args.into_iter().collect();
+    if let Some(mode) = args.get(1) {
+        let replacement: &[&str] = match mode.as_str() {
+            "--dbus" => &["dbus"],
+            "--json" => &["json"],
+            "--watch" => &["json", "--watch"],
+            _ => &[],
+        };
+        if !replacement.is_empty() {
+            args.splice(1..2, replacement.iter().map(|arg| arg.to_string()));
+        }
+    }
+    args
+}
+
+/// Interpreta durações como `500ms`, `2s`, `5m`, `6h`, `7d` ou `1.5` (segundos).
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (number, scale) = if let Some(number) = value.strip_suffix("ms") {
+        (number, 0.001)
+    } else if let Some(number) = value.strip_suffix('s') {
+        (number, 1.0)
+    } else if let Some(number) = value.strip_suffix('m') {
+        (number, 60.0)
+    } else if let Some(number) = value.strip_suffix('h') {
+        (number, 3600.0)
+    } else if let Some(number) = value.strip_suffix('d') {
+        (number, 86_400.0)
+    } else {
+        (value, 1.0)
+    };
+
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        .map(|seconds| Duration::from_secs_f64(seconds * scale))
+        .ok_or_else(|| format!("duração inválida: {value:?}"))
+}
+
+/// Dias desde 1970-01-01 para uma data do calendário gregoriano proléptico.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Interpreta instantes em UTC como `2024-05-01`, `2024-05-01T14:30[:00][Z]`,
+/// segundos Unix ou uma duração atrás (`6h`, `7d`); retorna milissegundos Unix.
+pub fn parse_time(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let invalid = || format!("instante inválido: {value:?}");
+    if !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return value
+            .parse::<u64>()
+            .map(|seconds| seconds.saturating_mul(1000))
+            .map_err(|_| invalid());
+    }
+    if let Ok(ago) = parse_duration(value) {
+        return Ok(unix_now().saturating_sub(ago).as_millis() as u64);
+    }
+
+    let value = value.strip_suffix('Z').unwrap_or(value);
+    let (date, time) = value.split_once(['T', ' ']).unwrap_or((value, "00:00"));
+    let date: Vec<i64> = date
+        .split('-')
+        .map(|part| part.parse().map_err(|_| invalid()))
+        .collect::<Result<_, _>>()?;
+    let time: Vec<i64> = time
+        .split(':')
+        .map(|part| part.parse().map_err(|_| invalid()))
+        .collect::<Result<_, _>>()?;
+    let (&[year, month, day], &[hour, minute, ..]) = (date.as_slice(), time.as_slice()) else {
+        return Err(invalid());
+    };
+    let second = time.get(2).copied().unwrap_or(0);
+    let valid = (1970..=9999).contains(&year)
+        && (1..=12).contains(&month)
+        && (1..=31).contains(&day)
+        && (0..24).contains(&hour)
+        && (0..60).contains(&minute)
+        && (0..60).contains(&second)
+        && time.len() <= 3;
+    if !valid {
+        return Err(invalid());
+    }
+    let seconds = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second;
+    Ok(seconds as u64 * 1000)
+}
+
+fn parse_history_range(value: &str) -> Result<HistoryRange, String> {
+    HistoryRange::parse(value)
+        .ok_or_else(|| format!("janela inválida: {value:?} (use 1h, 24h ou 7d)"))
+}
+
+/// Carrega a configuração do usuário para os modos de linha de comando,
+/// caindo no padrão quando o arquivo é inválido.
+pub fn load_cli_config() -> MonitorConfig {
+    load_config(&config_path()).unwrap_or_else(|error| {
+        eprintln!("monitor-tray: usando configuração padrão: {error}");
+        MonitorConfig::default()
+    })
+}
+
+fn render<T: Serialize>(value: &T, pretty: bool) -> serde_json::Result<String> {
+    if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    }
+}
+
+/// Snapshot de `json`: com todas as seções tem o mesmo formato de `GetMetricsJson`;
+/// com `--section`, só as seções pedidas aparecem no JSON.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SectionSnapshot {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<CpuMetrics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryMetrics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk: Option<DiskMetrics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkMetrics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensors: Option<SensorMetrics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gpus: Option<Vec<GpuInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_processes: Option<Vec<ProcessInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_info: Option<SystemInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uptime: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_average: Option<(f64, f64, f64)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<PressureMetrics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history: Option<HistoryMetrics>,
+}
+
+impl From<SystemMetrics> for SectionSnapshot {
+    fn from(metrics: SystemMetrics) -> Self {
+        Self {
+            cpu: Some(metrics.cpu),
+            memory: Some(metrics.memory),
+            disk: Some(metrics.disk),
+            network: Some(metrics.network),
+            sensors: Some(metrics.sensors),
+            gpus: Some(metrics.gpus),
+            top_processes: Some(metrics.top_processes),
+            system_info: Some(metrics.system_info),
+            uptime: Some(metrics.uptime),
+            load_average: Some(metrics.load_average),
+            pressure: Some(metrics.pressure),
+            history: None,
+        }
+    }
+}
+
+/// Coleta apenas o necessário para `sections`: CPU sozinha não dispara o probe de GPU
+/// nem o scan de processos. Sem seções, retorna o snapshot completo de `GetMetricsJson`.
+pub async fn collect_sections(
+    monitor: &mut SystemMonitor,
+    sections: &[Section],
+) -> SectionSnapshot {
+    if sections.is_empty() {
+        return collect_metrics(monitor).await.into();
+    }
+
+    if sections.iter().any(|section| section.is_fast()) {
+        monitor.update_fast_metrics().await;
+    }
+    let subsystems = SlowSubsystems {
+        sensors: sections.contains(&Section::Sensors),
+        gpus: sections.contains(&Section::Gpu),
+        processes: sections.contains(&Section::Processes),
+    };
+    if subsystems != SlowSubsystems::NONE {
+        monitor.refresh_slow_subsystems(false, subsystems).await;
+    }
+
+    let mut snapshot = SectionSnapshot::default();
+    for section in sections {
+        match section {
+            Section::Cpu => snapshot.cpu = Some(monitor.get_cpu_metrics()),
+            Section::Memory => snapshot.memory = Some(monitor.get_memory_metrics()),
+            Section::Disk => snapshot.disk = Some(monitor.get_disk_metrics()),
+            Section::Network => snapshot.network = Some(monitor.get_network_metrics()),
+            Section::Pressure => snapshot.pressure = Some(monitor.get_pressure_metrics()),
+            Section::System => {
+                let fast = monitor.get_fast_metrics();
+                snapshot.uptime = Some(fast.uptime);
+                snapshot.load_average = Some(fast.load_average);
+                snapshot.system_info = Some(monitor.get_system_info());
+            }
+            Section::Sensors => snapshot.sensors = Some(monitor.get_sensor_metrics()),
+            Section::Gpu => snapshot.gpus = Some(monitor.get_gpus()),
+            Section::Processes => snapshot.top_processes = Some(monitor.get_top_processes()),
+            Section::History => snapshot.history = Some(monitor.get_history_metrics()),
+        }
+    }
+
+    snapshot
+}
+
+/// Imprime um snapshot por linha (NDJSON) a cada `interval`, reaproveitando o mesmo
+/// monitor para que as taxas sejam calculadas entre amostras.
+/// Para após `count` linhas, se informado, ou quando a saída é fechada.
+pub async fn watch_metrics<W: Write>(
+    monitor: &mut SystemMonitor,
+    out: &mut W,
+    sections: &[Section],
+    interval: Duration,
+    count: Option<u64>,
+) -> Result<(), Box<dyn Error>> {
+    let mut ticker = tokio::time::interval(interval.max(MIN_WATCH_INTERVAL));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut emitted = 0;
+
+    while count.is_none_or(|count| emitted < count) {
+        ticker.tick().await;
+        let line = serde_json::to_string(&collect_sections(monitor, sections).await)?;
+
+        match writeln!(out, "{line}").and_then(|_| out.flush()) {
+            Ok(()) => {}
+            // Consumidor como `head` encerrou o pipe: fim normal do stream.
+            Err(error) if error.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+            Err(error) => return Err(error.into()),
+        }
+        emitted += 1;
+    }
+
+    Ok(())
+}
+
+async fn run_json(args: JsonArgs) -> Result<(), Box<dyn Error>> {
+    if args.watch && args.format != OutputFormat::Json {
+        return Err("--watch emite NDJSON e não aceita --format table".into());
+    }
+    let mut monitor = SystemMonitor::with_config(load_cli_config());
+    if args.watch {
+        let mut out = io::stdout().lock();
+        return watch_metrics(
+            &mut monitor,
+            &mut out,
+            &args.section,
+            args.interval,
+            args.count,
+        )
+        .await;
+    }
+
+    let snapshot = collect_sections(&mut monitor, &args.section).await;
+    match args.format {
+        OutputFormat::Json => println!("{}", render(&snapshot, args.output.pretty)?),
+        OutputFormat::Table => print!("{}", table::render_snapshot(&snapshot)),
+    }
+    Ok(())
+}
+
+/// Consulta o histórico no serviço em execução; `None` quando não há serviço no barramento.
+async fn history_from_service(args: &HistoryArgs) -> Result<Option<Value>, Box<dyn Error>> {
+    let Some(proxy) = connect_backend().await else {
+        return Ok(None);
+    };
+
+    let payload = match (&args.series, args.range) {
+        (Some(series), Some(range)) => proxy.history_range_json(series, range.as_str()).await?,
+        _ => proxy.history_metrics_json().await?,
+    };
+    Ok(Some(serde_json::from_str(&payload)?))
+}
+
+/// Monitor local com o histórico do último checkpoint, para quando o serviço não responde.
+fn saved_history_monitor() -> SystemMonitor {
+    let path = history_path();
+    eprintln!(
+        "monitor-tray: serviço DBus indisponível; usando histórico salvo em {}",
+        path.display()
+    );
+    let mut monitor = SystemMonitor::with_config(load_cli_config());
+    if let Err(error) = monitor.restore_history(&path) {
+        eprintln!("monitor-tray: histórico salvo ignorado: {error}");
+    }
+    monitor
+}
+
+async fn run_history_csv(args: HistoryArgs) -> Result<(), Box<dyn Error>> {
+    let series = args.series.unwrap_or_default();
+    let csv = match connect_backend().await {
+        Some(proxy) => proxy.history_csv(&series).await?,
+        None => {
+            let names: Vec<&str> = series
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .collect();
+            saved_history_monitor().get_history_csv(&names)?
+        }
+    };
+    print!("{csv}");
+    Ok(())
+}
+
+async fn run_history(args: HistoryArgs) -> Result<(), Box<dyn Error>> {
+    if args.csv {
+        return run_history_csv(args).await;
+    }
+    let payload = match history_from_service(&args).await? {
+        Some(payload) => payload,
+        None => {
+            let monitor = saved_history_monitor();
+            match (&args.series, args.range) {
+                (Some(series), Some(range)) => serde_json::to_value(
+                    monitor
+                        .get_history_range(series, range)
+                        .ok_or_else(|| format!("série desconhecida: {series}"))?,
+                )?,
+                _ => serde_json::to_value(monitor.get_history_metrics())?,
+            }
+        }
+    };
+
+    let payload = match (&args.series, args.range) {
+        (Some(series), None) => payload
+            .get(series)
+            .cloned()
+            .ok_or_else(|| format!("série desconhecida: {series}"))?,
+        _ => payload,
+    };
+    println!("{}", render(&payload, args.output.pretty)?);
+    Ok(())
+}
+
+fn run_query(args: QueryArgs) -> Result<(), Box<dyn Error>> {
+    let path = args
+        .database
+        .unwrap_or_else(|| load_cli_config().recorder.database_path());
+    let to = args.to.unwrap_or_else(|| unix_now().as_millis() as u64);
+    if args.from > to {
+        return Err("--from deve ser anterior a --to".into());
+    }
+    let snapshots = query_snapshots(&path, args.from, to)?;
+    println!("{}", render(&Value::Array(snapshots), args.output.pretty)?);
+    Ok(())
+}
+
+async fn run_speedtest(args: OutputArgs) -> Result<(), Box<dyn Error>> {
+    let manager = NetworkSpeedTestManager::new();
+    let mut status = manager.subscribe();
+    manager.start().await;
+
+    loop {
+        let current = status.borrow_and_update().clone();
+        if !matches!(current.state, NetworkSpeedTestState::Running) {
+            break;
+        }
+        eprintln!(
+            "monitor-tray: speed test: {}",
+            serde_json::to_value(&current.phase)?
+                .as_str()
+                .unwrap_or_default()
+        );
+
+        tokio::select! {
+            changed = status.changed() => changed?,
+            _ = tokio::signal::ctrl_c() => {
+                manager.cancel().await;
+                status.changed().await?;
+            }
+        }
+    }
+
+    let result = manager.get_status().await;
+    println!("{}", render(&result, args.pretty)?);
+    match result.state {
+        NetworkSpeedTestState::Success => Ok(()),
+        _ => Err(result
+            .error
+            .unwrap_or_else(|| "speed test não concluído".to_string())
+            .into()),
+    }
+}
+
+pub async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    match cli.command {
+        None | Some(CliCommand::Dbus) => run_dbus_service().await,
+        Some(CliCommand::Json(args)) => run_json(args).await,
+        Some(CliCommand::History(args)) => run_history(args).await,
+        Some(CliCommand::Speedtest(args)) => run_speedtest(args).await,
+        Some(CliCommand::Query(args)) => run_query(args),
+        Some(CliCommand::Tui(args)) => run_tui(args.local, args.interval).await,
+        Some(CliCommand::Tray(args)) => {
+            run_tray(
+                args.local,
+                args.indicator,
+                args.interval.max(MIN_WATCH_INTERVAL),
+            )
+            .await
+        }
+        Some(CliCommand::Bar(args)) => {
+            let monitor = SystemMonitor::with_config(load_cli_config());
+            let bar = StatusBar {
+                format: args.format,
+                text: args.template,
+                tooltip: args.tooltip,
+                warning: args.warning,
+                critical: args.critical,
+                warning_color: args.warning_color,
+                critical_color: args.critical_color,
+            };
+            run_statusbar(
+                monitor,
+                bar,
+                args.interval.max(MIN_WATCH_INTERVAL),
+                args.once,
+            )
+            .await
+        }
+        Some(CliCommand::Push(args)) => {
+            let monitor = SystemMonitor::with_config(load_cli_config());
+            let sink = PushSink::new(args.endpoint, args.format, args.prefix, args.batch_size);
+            run_push(
+                monitor,
+                sink,
+                args.interval.max(MIN_WATCH_INTERVAL),
+                args.flush_interval,
+            )
+            .await
+        }
+        Some(CliCommand::Mqtt(args)) => {
+            let monitor = SystemMonitor::with_config(load_cli_config());
+            let settings = MqttSettings {
+                broker: args.broker,
+                username: args.username,
+                password: std::env::var(PASSWORD_ENV).ok(),
+                topic_prefix: args.topic_prefix,
+                discovery_prefix: args.discovery_prefix,
+                groups: args.publish,
+            };
+            run_mqtt(monitor, settings, args.interval.max(MIN_WATCH_INTERVAL)).await
+        }
+        Some(CliCommand::Textfile(args)) => {
+            let monitor = SystemMonitor::with_config(load_cli_config());
+            run_textfile(
+                monitor,
+                args.dir,
+                args.interval.max(MIN_WATCH_INTERVAL),
+                args.once,
+                args.speedtest_interval,
+            )
+            .await
+        }
+        Some(CliCommand::Prometheus(args)) => {
+            let monitor = SystemMonitor::with_config(load_cli_config());
+            run_exporter(monitor, args.listen, args.interval.max(MIN_WATCH_INTERVAL)).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(normalize_legacy_args(
+            std::iter::once("monitor-tray")
+                .chain(args.iter().copied())
+                .map(str::to_string),
+        ))
+    }
+
+    #[test]
+    fn test_parse_duration_accepts_units_and_plain_seconds() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("6h"), Ok(Duration::from_secs(6 * 3600)));
+        assert!(parse_duration("rápido").is_err());
+        assert!(parse_duration("-1s").is_err());
+    }
+
+    #[test]
+    fn test_parse_time_accepts_dates_unix_seconds_and_durations_ago() {
+        assert_eq!(parse_time("2024-05-01"), Ok(1_714_521_600_000));
+        assert_eq!(parse_time("2024-05-01T14:30Z"), Ok(1_714_573_800_000));
+        assert_eq!(parse_time("2000-02-29 23:59:59"), Ok(951_868_799_000));
+        assert_eq!(parse_time("1714521600"), Ok(1_714_521_600_000));
+        let week_ago = parse_time("7d").unwrap();
+        let expected = unix_now().as_millis() as u64 - 7 * 86_400_000;
+        assert!(expected.abs_diff(week_ago) < 5_000);
+        assert!(parse_time("2024-13-01").is_err());
+        assert!(parse_time("2024-05").is_err());
+        assert!(parse_time("ontem").is_err());
+    }
+
+    #[test]
+    fn test_cli_parses_sections_and_legacy_modes() {
+        let Ok(Cli {
+            command: Some(CliCommand::Json(args)),
+        }) = parse(&["json", "--section", "cpu,gpus,sensors", "--pretty"])
+        else {
+            panic!("json subcommand should parse");
+        };
+        assert_eq!(
+            args.section,
+            vec![Section::Cpu, Section::Gpu, Section::Sensors]
+        );
+        assert!(args.output.pretty);
+        assert_eq!(args.format, OutputFormat::Json);
+        assert!(
+            parse(&["json", "--format", "table"]).is_ok_and(|cli| matches!(
+                cli.command,
+                Some(CliCommand::Json(JsonArgs {
+                    format: OutputFormat::Table,
+                    ..
+                }))
+            ))
+        );
+
+        assert!(matches!(
+            parse(&["--dbus"]),
+            Ok(Cli {
+                command: Some(CliCommand::Dbus)
+            })
+        ));
+        let Ok(Cli {
+            command: Some(CliCommand::Json(watch)),
+        }) = parse(&["--watch", "--interval", "2s", "--count", "3"])
+        else {
+            panic!("legacy --watch should map to json --watch");
+        };
+        assert!(watch.watch);
+        assert_eq!(watch.interval, Duration::from_secs(2));
+        assert_eq!(watch.count, Some(3));
+        assert!(parse(&[]).is_ok_and(|cli| cli.command.is_none()));
+        assert!(parse(&["tui", "--local", "--interval", "2s"]).is_ok_and(|cli| matches!(
+            cli.command,
+            Some(CliCommand::Tui(TuiArgs { local: true, interval })) if interval == Duration::from_secs(2)
+        )));
+    }
+
+    #[test]
+    fn test_cli_rejects_invalid_combinations() {
+        assert!(parse(&["prometheus", "--listen", "0.0.0.0:9882"]).is_err());
+        assert!(parse(&["textfile"]).is_err());
+        assert!(parse(&["push", "--endpoint", "127.0.0.1:8089"]).is_err());
+        assert!(parse(&["mqtt", "--broker", "mqtt://broker.lan", "--publish", "disk"]).is_err());
+        assert!(parse(&["bar", "--template", "{cpu.usage_percent"]).is_err());
+        assert!(parse(&["bar", "--critical", "cpu.usage_percent=90"]).is_err());
+        assert!(parse(&[
+            "push",
+            "--endpoint",
+            "udp://127.0.0.1:8089",
+            "--format",
+            "json"
+        ])
+        .is_err());
+        assert!(parse(&[
+            "textfile",
+            "--dir",
+            "/tmp",
+            "--once",
+            "--speedtest-interval",
+            "60"
+        ])
+        .is_err());
+        assert!(parse(&["prometheus", "--listen", "unix:/tmp/monitor-tray.sock"]).is_ok());
+        assert!(parse(&["json", "--section", "cpu,bogus"]).is_err());
+        assert!(parse(&["json", "--count", "3"]).is_err());
+        assert!(parse(&["json", "--watch", "--pretty"]).is_err());
+        assert!(parse(&["history", "--range", "1h"]).is_err());
+        assert!(parse(&["history", "--series", "cpu_usage", "--range", "2h"]).is_err());
+        assert!(parse(&["history", "--series", "cpu_usage", "--range", "24h"]).is_ok());
+        assert!(parse(&["history", "--csv", "--series", "cpu_usage", "--range", "1h"]).is_err());
+        assert!(parse(&["history", "--csv", "--pretty"]).is_err());
+        assert!(parse(&["history", "--csv", "--series", "cpu_usage,gpu_usage"]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_collect_sections_skips_unselected_slow_subsystems() {
+        let mut monitor = SystemMonitor::new_empty();
+
+        let snapshot = collect_sections(&mut monitor, &[Section::Cpu, Section::Sensors]).await;
+        let payload = serde_json::to_value(&snapshot).expect("sections should serialize");
+
+        let keys: Vec<&String> = payload
+            .as_object()
+            .expect("payload should be an object")
+            .keys()
+            .collect();
+        assert_eq!(keys, vec!["cpu", "sensors"]);
+        assert!(monitor.last_gpu_refresh.is_none());
+        assert!(monitor.cached_top_processes.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_watch_metrics_prints_one_json_object_per_line() {
+        let mut monitor = SystemMonitor::new_empty();
+        let mut out = Vec::new();
+
+        watch_metrics(
+            &mut monitor,
+            &mut out,
+            &[Section::Memory],
+            MIN_WATCH_INTERVAL,
+            Some(2),
+        )
+        .await
+        .expect("watch should finish after count samples");
+
+        let output = String::from_utf8(out).expect("output should be UTF-8");
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            let value: Value = serde_json::from_str(line).expect("each line should be JSON");
+            assert!(value.get("memory").is_some());
+        }
+    }
+}
@@ -0,0 +1,480 @@
+//! Saída para barras de status fora do Plasma: módulo custom do Waybar (JSON por linha),
+//! protocolo do i3bar/swaybar e texto com tags de cor do polybar.
+//!
+//! O texto vem de um template com campos `{caminho}` sobre o JSON de `GetMetricsJson`,
+//! como `{cpu.usage_percent}% {sensors.hottest_cpu_celsius}°C`; limites de alerta e
+//! crítico mudam a classe CSS (Waybar) ou a cor (i3bar e polybar).
+
+use std::error::Error;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use tokio::time::MissedTickBehavior;
+
+use crate::alerts::resolve_metric;
+use crate::collect_metrics;
+use crate::monitor::SystemMonitor;
+
+pub const DEFAULT_TEMPLATE: &str =
+    "CPU {cpu.usage_percent}% RAM {memory.usage_percent}% {sensors.hottest_cpu_celsius}°C";
+pub const DEFAULT_TOOLTIP: &str = "{cpu.name}\\n\
+    CPU {cpu.usage_percent}% a {cpu.frequency} MHz, carga {load_average.0:.2}\\n\
+    RAM {memory.used_memory:.1} de {memory.total_memory:.1} GB\\n\
+    Mais quente: {sensors.hottest_label} a {sensors.hottest_temperature_celsius:.1}°C";
+pub const DEFAULT_WARNING_COLOR: &str = "#ebcb8b";
+pub const DEFAULT_CRITICAL_COLOR: &str = "#bf616a";
+const MODULE_NAME: &str = "monitor_tray";
+const MISSING: &str = "-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BarFormat {
+    /// JSON do módulo `custom` do Waybar (`return-type: json`)
+    Waybar,
+    /// Protocolo do i3bar, também aceito pelo swaybar
+    I3bar,
+    /// Texto com tags `%{F#cor}` para `custom/script` com `tail = true`
+    Polybar,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Field { path: String, precision: usize },
+}
+
+/// Template com campos `{caminho[:.N]}`; números saem com `N` casas (padrão 0),
+/// `{{`/`}}` escrevem chaves literais e `\n` quebra a linha do tooltip.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.replace("\\n", "\n");
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = value.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut field = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(ch) => field.push(ch),
+                            None => return Err(format!("campo sem `}}` no template: {{{field}")),
+                        }
+                    }
+                    let (path, precision) = match field.split_once(":.") {
+                        Some((path, precision)) => (
+                            path,
+                            precision
+                                .parse()
+                                .map_err(|_| format!("precisão inválida em {{{field}}}"))?,
+                        ),
+                        None => (field.as_str(), 0),
+                    };
+                    if path.is_empty() || path.split('.').any(str::is_empty) {
+                        return Err(format!("campo inválido no template: {{{field}}}"));
+                    }
+                    parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    parts.push(Part::Field {
+                        path: path.to_string(),
+                        precision,
+                    });
+                }
+                '}' => return Err("`}` sem `{` correspondente no template".to_string()),
+                _ => literal.push(ch),
+            }
+        }
+        parts.push(Part::Literal(literal));
+        parts.retain(|part| !matches!(part, Part::Literal(text) if text.is_empty()));
+        Ok(Self { parts })
+    }
+}
+
+impl Template {
+    pub fn render(&self, metrics: &Value) -> String {
+        let mut output = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => output.push_str(text),
+                Part::Field { path, precision } => {
+                    match metrics.pointer(&format!("/{}", path.replace('.', "/"))) {
+                        Some(Value::Number(number)) if number.is_f64() => {
+                            let _ = write!(
+                                output,
+                                "{:.*}",
+                                precision,
+                                number.as_f64().unwrap_or_default()
+                            );
+                        }
+                        Some(Value::Number(number)) => {
+                            let _ = write!(output, "{number}");
+                        }
+                        Some(Value::String(text)) if !text.is_empty() => output.push_str(text),
+                        Some(Value::Bool(flag)) => {
+                            let _ = write!(output, "{flag}");
+                        }
+                        _ => output.push_str(MISSING),
+                    }
+                }
+            }
+        }
+        output
+    }
+
+    /// Primeiro campo cujo caminho não existe em `metrics`. Listas vazias e valores
+    /// `null` são aceitos, já que GPUs e sensores podem aparecer só depois.
+    fn unknown_field(&self, metrics: &Value) -> Option<&str> {
+        self.parts.iter().find_map(|part| match part {
+            Part::Field { path, .. } if !path_exists(metrics, path) => Some(path.as_str()),
+            _ => None,
+        })
+    }
+}
+
+fn path_exists(metrics: &Value, path: &str) -> bool {
+    let mut node = metrics;
+    for segment in path.split('.') {
+        node = match node {
+            Value::Object(_) if segment == "*" => return true,
+            Value::Object(fields) => match fields.get(segment) {
+                Some(child) => child,
+                None => return false,
+            },
+            Value::Array(items) => match segment.parse::<usize>().ok() {
+                Some(index) => match items.get(index) {
+                    Some(child) => child,
+                    None => return true,
+                },
+                None => return segment == "*",
+            },
+            Value::Null => return true,
+            _ => return false,
+        };
+    }
+    true
+}
+
+/// Limite `caminho>valor` ou `caminho<valor`; `*` no caminho percorre listas, como nos alertas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Threshold {
+    path: String,
+    limit: f64,
+    above: bool,
+}
+
+impl FromStr for Threshold {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("limite inválido: {value:?} (use caminho>valor ou caminho<valor)");
+        let (index, above) = value
+            .find('>')
+            .map(|index| (index, true))
+            .or_else(|| value.find('<').map(|index| (index, false)))
+            .ok_or_else(invalid)?;
+        let path = value[..index].trim();
+        let limit = value[index + 1..]
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|limit| limit.is_finite())
+            .ok_or_else(invalid)?;
+        if path.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            path: path.to_string(),
+            limit,
+            above,
+        })
+    }
+}
+
+impl Threshold {
+    fn is_crossed(&self, metrics: &Value) -> bool {
+        resolve_metric(metrics, &self.path)
+            .into_iter()
+            .filter_map(|(_, value)| value)
+            .any(|value| {
+                if self.above {
+                    value > self.limit
+                } else {
+                    value < self.limit
+                }
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BarState {
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl BarState {
+    /// Classe CSS do Waybar, também usada como `alt` para `format-icons`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BarState::Normal => "normal",
+            BarState::Warning => "warning",
+            BarState::Critical => "critical",
+        }
+    }
+}
+
+pub struct StatusBar {
+    pub format: BarFormat,
+    pub text: Template,
+    pub tooltip: Template,
+    pub warning: Vec<Threshold>,
+    pub critical: Vec<Threshold>,
+    pub warning_color: String,
+    pub critical_color: String,
+}
+
+impl StatusBar {
+    pub fn state(&self, metrics: &Value) -> BarState {
+        let crossed = |thresholds: &[Threshold]| {
+            thresholds
+                .iter()
+                .any(|threshold| threshold.is_crossed(metrics))
+        };
+        if crossed(&self.critical) {
+            BarState::Critical
+        } else if crossed(&self.warning) {
+            BarState::Warning
+        } else {
+            BarState::Normal
+        }
+    }
+
+    fn color(&self, state: BarState) -> Option<&str> {
+        match state {
+            BarState::Normal => None,
+            BarState::Warning => Some(&self.warning_color),
+            BarState::Critical => Some(&self.critical_color),
+        }
+    }
+
+    /// Cabeçalho do stream: o i3bar espera a versão do protocolo e a abertura da lista infinita.
+    pub fn header(&self) -> Option<&'static str> {
+        (self.format == BarFormat::I3bar).then_some("{\"version\":1}\n[")
+    }
+
+    /// Uma atualização da barra, sem a quebra de linha final.
+    pub fn render_line(&self, metrics: &Value) -> String {
+        let state = self.state(metrics);
+        let text = self.text.render(metrics);
+        match self.format {
+            BarFormat::Waybar => json!({
+                "text": text,
+                "tooltip": self.tooltip.render(metrics),
+                "class": state.as_str(),
+                "alt": state.as_str(),
+            })
+            .to_string(),
+            BarFormat::I3bar => {
+                let mut block = json!({
+                    "name": MODULE_NAME,
+                    "full_text": text.replace('\n', " "),
+                    "urgent": state == BarState::Critical,
+                });
+                if let Some(color) = self.color(state) {
+                    block["color"] = json!(color);
+                }
+                format!("[{block}],")
+            }
+            BarFormat::Polybar => {
+                let text = text.replace('\n', " ");
+                match self.color(state) {
+                    Some(color) => format!("%{{F{color}}}{text}%{{F-}}"),
+                    None => text,
+                }
+            }
+        }
+    }
+
+    /// Recusa campos e limites que não existem no snapshot, em vez de imprimir `-` para sempre.
+    fn validate(&self, metrics: &Value) -> Result<(), String> {
+        if let Some(path) = [&self.text, &self.tooltip]
+            .iter()
+            .find_map(|template| template.unknown_field(metrics))
+        {
+            return Err(format!("campo desconhecido no template: {path}"));
+        }
+        match self
+            .warning
+            .iter()
+            .chain(&self.critical)
+            .find(|threshold| !path_exists(metrics, &threshold.path))
+        {
+            Some(threshold) => Err(format!(
+                "métrica desconhecida no limite: {}",
+                threshold.path
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Imprime uma atualização da barra a cada `interval` até a saída ser fechada,
+/// ou uma única vez com `once` (para o `interval` do próprio Waybar).
+pub async fn run_statusbar(
+    mut monitor: SystemMonitor,
+    bar: StatusBar,
+    interval: Duration,
+    once: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut out = io::stdout().lock();
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut validated = false;
+    let mut header = bar.header();
+
+    loop {
+        ticker.tick().await;
+        let metrics = serde_json::to_value(collect_metrics(&mut monitor).await)?;
+        if !validated {
+            bar.validate(&metrics)?;
+            validated = true;
+        }
+
+        let mut line = String::new();
+        if let Some(header) = header.take() {
+            line.push_str(header);
+            line.push('\n');
+        }
+        line.push_str(&bar.render_line(&metrics));
+        match writeln!(out, "{line}").and_then(|_| out.flush()) {
+            Ok(()) => {}
+            // A barra foi recarregada e fechou o pipe: fim normal.
+            Err(error) if error.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+            Err(error) => return Err(error.into()),
+        }
+        if once {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics() -> Value {
+        json!({
+            "cpu": { "usage_percent": 42.6, "frequency": 3600, "name": "Ryzen" },
+            "sensors": { "hottest_cpu_celsius": 91.26, "hottest_gpu_celsius": null },
+            "disk": { "disks": [
+                { "mount_point": "/", "usage_percent": 50.0 },
+                { "mount_point": "/home", "usage_percent": 97.0 },
+            ] },
+            "gpus": [],
+        })
+    }
+
+    fn bar(format: BarFormat) -> StatusBar {
+        StatusBar {
+            format,
+            text: "{cpu.usage_percent}% {sensors.hottest_cpu_celsius:.1}°C"
+                .parse()
+                .unwrap(),
+            tooltip: "{cpu.name}\\n{cpu.frequency} MHz".parse().unwrap(),
+            warning: vec!["cpu.usage_percent>40".parse().unwrap()],
+            critical: vec![],
+            warning_color: DEFAULT_WARNING_COLOR.to_string(),
+            critical_color: DEFAULT_CRITICAL_COLOR.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_template_renders_fields_precision_and_missing_values() {
+        let template: Template = "{{{cpu.usage_percent}}} {sensors.hottest_cpu_celsius:.2} \
+                                  {sensors.hottest_gpu_celsius} {gpus.0.name} {cpu.frequency}"
+            .parse()
+            .unwrap();
+        assert_eq!(template.render(&metrics()), "{43} 91.26 - - 3600");
+
+        assert!("{cpu.usage_percent".parse::<Template>().is_err());
+        assert!("{cpu.usage_percent:.x}".parse::<Template>().is_err());
+        assert!("cpu}".parse::<Template>().is_err());
+        assert!("{cpu..usage}".parse::<Template>().is_err());
+    }
+
+    #[test]
+    fn test_state_picks_worst_crossed_threshold() {
+        let mut bar = bar(BarFormat::Waybar);
+        assert_eq!(bar.state(&metrics()), BarState::Warning);
+
+        bar.critical = vec!["disk.disks.*.usage_percent>95".parse().unwrap()];
+        assert_eq!(bar.state(&metrics()), BarState::Critical);
+
+        bar.warning = vec!["cpu.usage_percent<10".parse().unwrap()];
+        bar.critical.clear();
+        assert_eq!(bar.state(&metrics()), BarState::Normal);
+        assert!("cpu.usage_percent=10".parse::<Threshold>().is_err());
+    }
+
+    #[test]
+    fn test_render_line_per_bar_format() {
+        let waybar: Value =
+            serde_json::from_str(&bar(BarFormat::Waybar).render_line(&metrics())).unwrap();
+        assert_eq!(
+            waybar,
+            json!({
+                "text": "43% 91.3°C",
+                "tooltip": "Ryzen\n3600 MHz",
+                "class": "warning",
+                "alt": "warning",
+            })
+        );
+
+        let i3bar = bar(BarFormat::I3bar);
+        assert_eq!(i3bar.header(), Some("{\"version\":1}\n["));
+        let line = i3bar.render_line(&metrics());
+        let blocks: Value = serde_json::from_str(line.trim_end_matches(',')).unwrap();
+        assert_eq!(blocks[0]["full_text"], "43% 91.3°C");
+        assert_eq!(blocks[0]["color"], DEFAULT_WARNING_COLOR);
+        assert_eq!(blocks[0]["urgent"], false);
+
+        assert_eq!(
+            bar(BarFormat::Polybar).render_line(&metrics()),
+            "%{F#ebcb8b}43% 91.3°C%{F-}"
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_paths() {
+        let mut bar = bar(BarFormat::Polybar);
+        assert!(bar.validate(&metrics()).is_ok());
+
+        bar.text = "{gpus.0.usage_percent} {cpu.usage}".parse().unwrap();
+        assert_eq!(
+            bar.validate(&metrics()).unwrap_err(),
+            "campo desconhecido no template: cpu.usage"
+        );
+
+        bar.text = "{cpu.usage_percent}".parse().unwrap();
+        bar.critical = vec!["sensors.hottest>90".parse().unwrap()];
+        assert!(bar.validate(&metrics()).is_err());
+    }
+}
@@ -0,0 +1,844 @@
+//! Renderização de `json --format table`: blocos de texto alinhados, no espírito de
+//! `sensors` e `top`, a partir do mesmo snapshot que alimenta o JSON.
+
+use std::fmt::Write;
+
+use crate::monitor::{
+    CpuCoreType, CpuMetrics, DiskMetrics, GpuInfo, GpuVendor, HistoryMetrics, MemoryMetrics,
+    NetworkMetrics, PressureMetrics, PressureResource, ProcessInfo, SensorMetrics, SystemInfo,
+    TemperatureSensor,
+};
+
+use super::SectionSnapshot;
+
+const BAR_WIDTH: usize = 20;
+const INDENT: &str = "  ";
+const MISSING: &str = "-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Right,
+}
+
+/// Tabela com cabeçalho e colunas dimensionadas pelo conteúdo.
+struct Table {
+    columns: Vec<(&'static str, Align)>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn new(columns: &[(&'static str, Align)]) -> Self {
+        Self {
+            columns: columns.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn row(&mut self, cells: Vec<String>) {
+        debug_assert_eq!(cells.len(), self.columns.len());
+        self.rows.push(cells);
+    }
+
+    fn render(&self, out: &mut String) {
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, (header, _))| {
+                self.rows
+                    .iter()
+                    .map(|row| text_width(&row[index]))
+                    .chain(std::iter::once(text_width(header)))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let header: Vec<String> = self
+            .columns
+            .iter()
+            .map(|(header, _)| header.to_string())
+            .collect();
+        for cells in std::iter::once(&header).chain(&self.rows) {
+            let line: Vec<String> = cells
+                .iter()
+                .zip(&self.columns)
+                .zip(&widths)
+                .map(|((cell, (_, align)), width)| pad(cell, *width, *align))
+                .collect();
+            let _ = writeln!(out, "{INDENT}{}", line.join("  ").trim_end());
+        }
+    }
+}
+
+fn text_width(text: &str) -> usize {
+    text.chars().count()
+}
+
+fn pad(text: &str, width: usize, align: Align) -> String {
+    let fill = " ".repeat(width.saturating_sub(text_width(text)));
+    match align {
+        Align::Left => format!("{text}{fill}"),
+        Align::Right => format!("{fill}{text}"),
+    }
+}
+
+/// Pares rótulo/valor com os rótulos alinhados, como em `Host   nome`.
+fn render_fields(out: &mut String, fields: &[(&str, String)]) {
+    let width = fields
+        .iter()
+        .map(|(label, _)| text_width(label))
+        .max()
+        .unwrap_or(0);
+    for (label, value) in fields {
+        let _ = writeln!(out, "{INDENT}{}  {value}", pad(label, width, Align::Left));
+    }
+}
+
+pub(crate) fn bar(percent: f32) -> String {
+    let ratio = if percent.is_finite() {
+        (percent / 100.0).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let filled = (ratio * BAR_WIDTH as f32).round() as usize;
+    format!("[{}{}]", "|".repeat(filled), " ".repeat(BAR_WIDTH - filled))
+}
+
+pub(crate) fn format_percent(percent: f32) -> String {
+    format!("{percent:.1}%")
+}
+
+/// Bytes em unidades binárias (`KiB`, `MiB`, ...), com uma casa decimal acima de 1 KiB.
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+pub(crate) fn format_rate(bytes_per_sec: u64) -> String {
+    format!("{}/s", format_bytes(bytes_per_sec))
+}
+
+pub(crate) fn format_gib(gib: f64) -> String {
+    format!("{gib:.1} GiB")
+}
+
+pub(crate) fn format_celsius(celsius: f32) -> String {
+    format!("{celsius:.1} °C")
+}
+
+pub(crate) fn format_uptime(seconds: u64) -> String {
+    let days = seconds / 86_400;
+    let hours = seconds % 86_400 / 3_600;
+    let minutes = seconds % 3_600 / 60;
+    if days > 0 {
+        format!("{days}d {hours}h {minutes}min")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}min")
+    } else {
+        format!("{minutes}min")
+    }
+}
+
+pub(crate) fn or_missing<T>(value: Option<T>, format: impl FnOnce(T) -> String) -> String {
+    value.map(format).unwrap_or_else(|| MISSING.to_string())
+}
+
+pub(crate) fn render_system(
+    out: &mut String,
+    info: Option<&SystemInfo>,
+    uptime: Option<u64>,
+    load_average: Option<(f64, f64, f64)>,
+) {
+    let mut fields = Vec::new();
+    if let Some(info) = info {
+        fields.push(("Host", info.hostname.clone()));
+        fields.push((
+            "SO",
+            format!("{} {}", info.os_name, info.os_version)
+                .trim()
+                .to_string(),
+        ));
+        fields.push(("Kernel", info.kernel_version.clone()));
+        fields.push(("Arquitetura", info.architecture.clone()));
+        fields.push(("Processos", info.process_count.to_string()));
+    }
+    if let Some(uptime) = uptime {
+        fields.push(("Uptime", format_uptime(uptime)));
+    }
+    if let Some((one, five, fifteen)) = load_average {
+        fields.push(("Carga", format!("{one:.2} {five:.2} {fifteen:.2}")));
+    }
+    render_fields(out, &fields);
+}
+
+pub(crate) fn cpu_heading(cpu: &CpuMetrics) -> String {
+    format!(
+        "CPU: {} ({} núcleos, {} MHz)",
+        if cpu.name.is_empty() { "?" } else { &cpu.name },
+        cpu.core_count,
+        cpu.frequency
+    )
+}
+
+pub(crate) fn core_type_label(core_type: CpuCoreType) -> &'static str {
+    match core_type {
+        CpuCoreType::Performance => "P-core",
+        CpuCoreType::Efficiency => "E-core",
+    }
+}
+
+/// Governor, limites, boost e throttling; linhas sem dados (VMs, containers) são omitidas.
+fn render_cpu_frequency_policy(out: &mut String, cpu: &CpuMetrics) {
+    let policy = &cpu.frequency_policy;
+    let mut fields = Vec::new();
+    if let Some(governor) = &policy.governor {
+        fields.push(("Governor", governor.clone()));
+    }
+    if let Some(preference) = &policy.energy_performance_preference {
+        fields.push(("EPP", preference.clone()));
+    }
+    if let (Some(min), Some(max)) = (policy.scaling_min_mhz, policy.scaling_max_mhz) {
+        fields.push(("Limites", format!("{min}–{max} MHz")));
+    }
+    if let Some(boost) = policy.boost_enabled {
+        fields.push((
+            "Boost",
+            if boost { "ligado" } else { "desligado" }.to_string(),
+        ));
+    }
+    if cpu
+        .cores
+        .iter()
+        .any(|core| core.core_throttle_count.is_some())
+    {
+        let throttle = &cpu.throttle;
+        let state = if throttle.throttling {
+            let cpus: Vec<String> = throttle
+                .throttled_cpus
+                .iter()
+                .map(|cpu| format!("cpu{cpu}"))
+                .collect();
+            format!("ativo em {}", cpus.join(", "))
+        } else {
+            "inativo".to_string()
+        };
+        fields.push((
+            "Throttling",
+            format!(
+                "{state} · {} eventos de núcleo · {} de pacote",
+                throttle.core_throttle_count, throttle.package_throttle_count
+            ),
+        ));
+    }
+    render_fields(out, &fields);
+}
+
+fn render_cpu(out: &mut String, cpu: &CpuMetrics) {
+    let label_width = text_width(&format!(
+        "cpu{}",
+        cpu.per_core_usage.len().saturating_sub(1)
+    ))
+    .max(text_width("Total"))
+    .max(
+        cpu.core_classes
+            .iter()
+            .map(|class| text_width(core_type_label(class.core_type)))
+            .max()
+            .unwrap_or(0),
+    );
+    let _ = writeln!(
+        out,
+        "{INDENT}{}  {} {:>6}  user {} · nice {} · system {} · iowait {} · irq {} · softirq {} · steal {} · guest {} · idle {}",
+        pad("Total", label_width, Align::Left),
+        bar(cpu.usage_percent),
+        format_percent(cpu.usage_percent),
+        format_percent(cpu.user_percent),
+        format_percent(cpu.nice_percent),
+        format_percent(cpu.system_percent),
+        format_percent(cpu.iowait_percent),
+        format_percent(cpu.irq_percent),
+        format_percent(cpu.softirq_percent),
+        format_percent(cpu.steal_percent),
+        format_percent(cpu.guest_percent),
+        format_percent(cpu.idle_percent),
+    );
+    for class in &cpu.core_classes {
+        let _ = writeln!(
+            out,
+            "{INDENT}{}  {} {:>6}  {} CPUs · {} núcleos · {} MHz",
+            pad(core_type_label(class.core_type), label_width, Align::Left),
+            bar(class.usage_percent),
+            format_percent(class.usage_percent),
+            class.cpu_count,
+            class.physical_core_count,
+            class.frequency_mhz,
+        );
+    }
+    if !cpu.cores.is_empty() {
+        let label_width = cpu
+            .cores
+            .iter()
+            .map(|core| text_width(&format!("cpu{}", core.id)))
+            .max()
+            .unwrap_or(0)
+            .max(label_width);
+        for core in &cpu.cores {
+            let core_type = core
+                .core_type
+                .map(|core_type| format!(" · {}", core_type_label(core_type)))
+                .unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "{INDENT}{}  {} {:>6}  user {} · nice {} · system {} · iowait {} · irq {} · softirq {} · steal {} · guest {} · idle {} · {} MHz{}",
+                pad(&format!("cpu{}", core.id), label_width, Align::Left),
+                bar(core.usage_percent),
+                format_percent(core.usage_percent),
+                format_percent(core.user_percent),
+                format_percent(core.nice_percent),
+                format_percent(core.system_percent),
+                format_percent(core.iowait_percent),
+                format_percent(core.irq_percent),
+                format_percent(core.softirq_percent),
+                format_percent(core.steal_percent),
+                format_percent(core.guest_percent),
+                format_percent(core.idle_percent),
+                core.frequency_mhz,
+                core_type,
+            );
+        }
+        return;
+    }
+    for (index, usage) in cpu.per_core_usage.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "{INDENT}{}  {} {:>6}",
+            pad(&format!("cpu{index}"), label_width, Align::Left),
+            bar(*usage),
+            format_percent(*usage),
+        );
+    }
+}
+
+pub(crate) fn render_memory(out: &mut String, memory: &MemoryMetrics) {
+    let _ = writeln!(
+        out,
+        "{INDENT}RAM   {} {:>6}  {} usados de {}, {} disponíveis",
+        bar(memory.usage_percent),
+        format_percent(memory.usage_percent),
+        format_gib(memory.used_memory),
+        format_gib(memory.total_memory),
+        format_gib(memory.available_memory),
+    );
+    if memory.total_swap > 0.0 {
+        let swap_percent = (memory.used_swap / memory.total_swap * 100.0) as f32;
+        let _ = writeln!(
+            out,
+            "{INDENT}Swap  {} {:>6}  {} usados de {}",
+            bar(swap_percent),
+            format_percent(swap_percent),
+            format_gib(memory.used_swap),
+            format_gib(memory.total_swap),
+        );
+    } else {
+        let _ = writeln!(out, "{INDENT}Swap  desativado");
+    }
+}
+
+pub(crate) fn render_disks(out: &mut String, disk: &DiskMetrics) {
+    let mut table = Table::new(&[
+        ("Dispositivo", Align::Left),
+        ("Montagem", Align::Left),
+        ("Tamanho", Align::Right),
+        ("Usado", Align::Right),
+        ("Livre", Align::Right),
+        ("Uso", Align::Right),
+        ("Leitura", Align::Right),
+        ("Escrita", Align::Right),
+    ]);
+    for info in &disk.disks {
+        table.row(vec![
+            info.name.clone(),
+            info.mount_point.clone(),
+            format_gib(info.total_space),
+            format_gib(info.used_space),
+            format_gib(info.available_space),
+            format_percent(info.usage_percent),
+            format_rate(info.read_bytes_per_sec),
+            format_rate(info.write_bytes_per_sec),
+        ]);
+    }
+    let total_percent = if disk.total_space > 0.0 {
+        (disk.used_space / disk.total_space * 100.0) as f32
+    } else {
+        0.0
+    };
+    table.row(vec![
+        "total".to_string(),
+        String::new(),
+        format_gib(disk.total_space),
+        format_gib(disk.used_space),
+        format_gib(disk.available_space),
+        format_percent(total_percent),
+        format_rate(disk.total_read_bytes_per_sec),
+        format_rate(disk.total_write_bytes_per_sec),
+    ]);
+    table.render(out);
+}
+
+pub(crate) fn render_pressure(out: &mut String, pressure: &PressureMetrics) {
+    let mut table = Table::new(&[
+        ("Origem", Align::Left),
+        ("Recurso", Align::Left),
+        ("Tipo", Align::Left),
+        ("avg10", Align::Right),
+        ("avg60", Align::Right),
+        ("avg300", Align::Right),
+        ("Travado", Align::Right),
+    ]);
+    let mut add = |origin: &str, name: &str, resource: &Option<PressureResource>| {
+        let Some(resource) = resource else {
+            return;
+        };
+        for (kind, stall) in std::iter::once(("some", &resource.some))
+            .chain(resource.full.as_ref().map(|full| ("full", full)))
+        {
+            table.row(vec![
+                origin.to_string(),
+                name.to_string(),
+                kind.to_string(),
+                format_percent(stall.avg10),
+                format_percent(stall.avg60),
+                format_percent(stall.avg300),
+                format_percent(stall.stall_percent),
+            ]);
+        }
+    };
+    add("sistema", "cpu", &pressure.cpu);
+    add("sistema", "memory", &pressure.memory);
+    add("sistema", "io", &pressure.io);
+    if let Some(cgroup) = &pressure.cgroup {
+        add(&cgroup.path, "cpu", &cgroup.cpu);
+        add(&cgroup.path, "memory", &cgroup.memory);
+        add(&cgroup.path, "io", &cgroup.io);
+    }
+    if table.rows.is_empty() {
+        let _ = writeln!(out, "{INDENT}PSI indisponível neste kernel");
+    } else {
+        table.render(out);
+    }
+}
+
+pub(crate) fn render_network(out: &mut String, network: &NetworkMetrics) {
+    let mut interfaces: Vec<_> = network.interfaces.iter().collect();
+    interfaces.sort_by_key(|(name, _)| *name);
+
+    let mut table = Table::new(&[
+        ("Interface", Align::Left),
+        ("Estado", Align::Left),
+        ("Recebido", Align::Right),
+        ("Enviado", Align::Right),
+        ("Pacotes RX", Align::Right),
+        ("Pacotes TX", Align::Right),
+        ("Erros RX", Align::Right),
+        ("Erros TX", Align::Right),
+    ]);
+    for (name, interface) in interfaces {
+        table.row(vec![
+            name.clone(),
+            if interface.is_up { "up" } else { "down" }.to_string(),
+            format_bytes(interface.bytes_received),
+            format_bytes(interface.bytes_transmitted),
+            interface.packets_received.to_string(),
+            interface.packets_transmitted.to_string(),
+            interface.errors_received.to_string(),
+            interface.errors_transmitted.to_string(),
+        ]);
+    }
+    table.render(out);
+
+    let target = network
+        .latency_target
+        .as_deref()
+        .or(network.gateway_ip.as_deref())
+        .unwrap_or("gateway");
+    let _ = writeln!(
+        out,
+        "{INDENT}Latência ({target}): {}",
+        or_missing(network.gateway_latency_ms, |ms| format!("{ms:.1} ms"))
+    );
+}
+
+/// Agrupa as temperaturas por chip hwmon, na ordem em que os chips aparecem.
+pub(crate) fn group_by_chip(
+    temperatures: &[TemperatureSensor],
+) -> Vec<(&str, Vec<&TemperatureSensor>)> {
+    let mut groups: Vec<(&str, Vec<&TemperatureSensor>)> = Vec::new();
+    for sensor in temperatures {
+        match groups.iter_mut().find(|(chip, _)| *chip == sensor.chip) {
+            Some((_, sensors)) => sensors.push(sensor),
+            None => groups.push((&sensor.chip, vec![sensor])),
+        }
+    }
+    groups
+}
+
+pub(crate) fn render_sensors(out: &mut String, sensors: &SensorMetrics) {
+    if sensors.temperatures.is_empty()
+        && sensors.fans.is_empty()
+        && sensors.voltages.is_empty()
+        && sensors.currents.is_empty()
+        && sensors.powers.is_empty()
+    {
+        let _ = writeln!(out, "{INDENT}Nenhum sensor encontrado");
+        return;
+    }
+
+    if !sensors.temperatures.is_empty() {
+        let mut table = Table::new(&[
+            ("Chip", Align::Left),
+            ("Sensor", Align::Left),
+            ("Temperatura", Align::Right),
+            ("Máx", Align::Right),
+            ("Crítica", Align::Right),
+        ]);
+        for (chip, group) in group_by_chip(&sensors.temperatures) {
+            for (index, sensor) in group.into_iter().enumerate() {
+                table.row(vec![
+                    if index == 0 { chip } else { "" }.to_string(),
+                    sensor.label.clone(),
+                    format_celsius(sensor.temperature_celsius),
+                    or_missing(sensor.max_celsius, format_celsius),
+                    or_missing(sensor.critical_celsius, format_celsius),
+                ]);
+            }
+        }
+        table.render(out);
+        let _ = writeln!(
+            out,
+            "{INDENT}Média {} · mais quente {} {}",
+            or_missing(sensors.average_temperature_celsius, format_celsius),
+            sensors.hottest_label,
+            or_missing(sensors.hottest_temperature_celsius, format_celsius),
+        );
+    }
+
+    if !sensors.fans.is_empty() {
+        let mut table = Table::new(&[
+            ("Ventoinha", Align::Left),
+            ("RPM", Align::Right),
+            ("Duty", Align::Right),
+        ]);
+        for fan in &sensors.fans {
+            table.row(vec![
+                fan.label.clone(),
+                fan.rpm.to_string(),
+                or_missing(fan.duty_percent, format_percent),
+            ]);
+        }
+        table.render(out);
+    }
+
+    let electrical: Vec<(String, String)> = sensors
+        .voltages
+        .iter()
+        .map(|sensor| (sensor.label.clone(), format!("{:.2} V", sensor.volts)))
+        .chain(
+            sensors
+                .currents
+                .iter()
+                .map(|sensor| (sensor.label.clone(), format!("{:.2} A", sensor.amps))),
+        )
+        .chain(
+            sensors
+                .powers
+                .iter()
+                .map(|sensor| (sensor.label.clone(), format!("{:.1} W", sensor.watts))),
+        )
+        .collect();
+    if !electrical.is_empty() {
+        let mut table = Table::new(&[("Elétrico", Align::Left), ("Valor", Align::Right)]);
+        for (label, value) in electrical {
+            table.row(vec![label, value]);
+        }
+        table.render(out);
+    }
+}
+
+pub(crate) fn vendor_name(vendor: &GpuVendor) -> &'static str {
+    match vendor {
+        GpuVendor::Amd => "AMD",
+        GpuVendor::Nvidia => "NVIDIA",
+        GpuVendor::Intel => "Intel",
+        GpuVendor::Unknown => "?",
+    }
+}
+
+pub(crate) fn render_gpus(out: &mut String, gpus: &[GpuInfo]) {
+    if gpus.is_empty() {
+        let _ = writeln!(out, "{INDENT}Nenhuma GPU detectada");
+        return;
+    }
+
+    let mut table = Table::new(&[
+        ("GPU", Align::Left),
+        ("Fabricante", Align::Left),
+        ("Uso", Align::Right),
+        ("VRAM", Align::Right),
+        ("Temp", Align::Right),
+        ("Potência", Align::Right),
+        ("Clock", Align::Right),
+        ("Ventoinha", Align::Right),
+    ]);
+    for gpu in gpus {
+        let vram = match (gpu.vram_used_gb, gpu.vram_total_gb) {
+            (Some(used), Some(total)) => format!("{used:.1}/{}", format_gib(total)),
+            (Some(used), None) => format_gib(used),
+            _ => MISSING.to_string(),
+        };
+        let clock = match (gpu.shader_clock_mhz, gpu.memory_clock_mhz) {
+            (Some(shader), Some(memory)) => format!("{shader}/{memory} MHz"),
+            (Some(shader), None) => format!("{shader} MHz"),
+            _ => MISSING.to_string(),
+        };
+        let fan = match (gpu.fan_rpm, gpu.fan_duty_percent) {
+            (Some(rpm), _) => format!("{rpm} RPM"),
+            (None, Some(duty)) => format_percent(duty),
+            _ => MISSING.to_string(),
+        };
+        table.row(vec![
+            gpu.name.clone(),
+            vendor_name(&gpu.vendor).to_string(),
+            or_missing(gpu.usage_percent, format_percent),
+            vram,
+            or_missing(gpu.temperature_celsius, format_celsius),
+            or_missing(gpu.power_watts, |watts| format!("{watts:.1} W")),
+            clock,
+            fan,
+        ]);
+    }
+    table.render(out);
+}
+
+pub(crate) fn render_processes(out: &mut String, processes: &[ProcessInfo]) {
+    let mut table = Table::new(&[
+        ("PID", Align::Right),
+        ("Processo", Align::Left),
+        ("CPU", Align::Right),
+        ("Memória", Align::Right),
+    ]);
+    for process in processes {
+        table.row(vec![
+            process.pid.to_string(),
+            process.name.clone(),
+            format_percent(process.cpu_percent),
+            format!("{:.1} MB", process.memory_mb),
+        ]);
+    }
+    table.render(out);
+}
+
+fn history_heading(history: &HistoryMetrics) -> String {
+    format!(
+        "Histórico (janela de {})",
+        format_uptime(history.history_duration_ms / 1000)
+    )
+}
+
+fn render_history(out: &mut String, history: &HistoryMetrics) {
+    let mut table = Table::new(&[
+        ("Série", Align::Left),
+        ("Amostras", Align::Right),
+        ("Última", Align::Right),
+        ("Mín", Align::Right),
+        ("Média", Align::Right),
+        ("Máx", Align::Right),
+    ]);
+    for (name, series) in history.series() {
+        // Intervalos sem leitura (`NaN`, como o backend parado) ficam fora das estatísticas.
+        let samples: Vec<f64> = series.samples().filter(|value| value.is_finite()).collect();
+        let stat = |value: Option<f64>| or_missing(value, |value| format!("{value:.1}"));
+        let average =
+            (!samples.is_empty()).then(|| samples.iter().sum::<f64>() / samples.len() as f64);
+        table.row(vec![
+            name.to_string(),
+            series.count.to_string(),
+            stat(samples.last().copied()),
+            stat(samples.iter().copied().reduce(f64::min)),
+            stat(average),
+            stat(samples.iter().copied().reduce(f64::max)),
+        ]);
+    }
+    table.render(out);
+}
+
+/// Renderiza as seções presentes em `snapshot`, separadas por uma linha em branco.
+pub fn render_snapshot(snapshot: &SectionSnapshot) -> String {
+    let mut blocks = Vec::new();
+    let mut block = |heading: &str, render: &dyn Fn(&mut String)| {
+        let mut out = format!("{heading}\n");
+        render(&mut out);
+        blocks.push(out);
+    };
+
+    if snapshot.system_info.is_some()
+        || snapshot.uptime.is_some()
+        || snapshot.load_average.is_some()
+    {
+        block("Sistema", &|out| {
+            render_system(
+                out,
+                snapshot.system_info.as_ref(),
+                snapshot.uptime,
+                snapshot.load_average,
+            )
+        });
+    }
+    if let Some(cpu) = &snapshot.cpu {
+        block(&cpu_heading(cpu), &|out| {
+            render_cpu(out, cpu);
+            render_cpu_frequency_policy(out, cpu);
+        });
+    }
+    if let Some(memory) = &snapshot.memory {
+        block("Memória", &|out| render_memory(out, memory));
+    }
+    if let Some(disk) = &snapshot.disk {
+        block("Discos", &|out| render_disks(out, disk));
+    }
+    if let Some(network) = &snapshot.network {
+        block("Rede", &|out| render_network(out, network));
+    }
+    if let Some(pressure) = &snapshot.pressure {
+        block("Pressão (PSI)", &|out| render_pressure(out, pressure));
+    }
+    if let Some(sensors) = &snapshot.sensors {
+        block("Sensores", &|out| render_sensors(out, sensors));
+    }
+    if let Some(gpus) = &snapshot.gpus {
+        block("GPUs", &|out| render_gpus(out, gpus));
+    }
+    if let Some(processes) = &snapshot.top_processes {
+        block("Processos", &|out| render_processes(out, processes));
+    }
+    if let Some(history) = &snapshot.history {
+        block(&history_heading(history), &|out| {
+            render_history(out, history)
+        });
+    }
+
+    blocks.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::{CpuFrequencyPolicy, CpuThrottleMetrics};
+
+    fn temperature(chip: &str, label: &str, celsius: f32) -> TemperatureSensor {
+        TemperatureSensor {
+            label: label.to_string(),
+            chip: chip.to_string(),
+            temperature_celsius: celsius,
+            max_celsius: None,
+            critical_celsius: Some(100.0),
+        }
+    }
+
+    #[test]
+    fn test_format_bytes_uses_binary_units() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+        assert_eq!(format_rate(2048), "2.0 KiB/s");
+    }
+
+    #[test]
+    fn test_bar_clamps_percent() {
+        assert_eq!(bar(50.0), format!("[{}{}]", "|".repeat(10), " ".repeat(10)));
+        assert_eq!(bar(150.0), format!("[{}]", "|".repeat(BAR_WIDTH)));
+        assert_eq!(bar(f32::NAN), format!("[{}]", " ".repeat(BAR_WIDTH)));
+    }
+
+    #[test]
+    fn test_table_aligns_columns_by_widest_cell() {
+        let mut table = Table::new(&[("Nome", Align::Left), ("Valor", Align::Right)]);
+        table.row(vec!["cpu".to_string(), "5".to_string()]);
+        table.row(vec!["memória".to_string(), "12345".to_string()]);
+        let mut out = String::new();
+        table.render(&mut out);
+
+        assert_eq!(
+            out,
+            "  Nome     Valor\n  cpu          5\n  memória  12345\n"
+        );
+    }
+
+    #[test]
+    fn test_sensors_are_grouped_by_chip_in_first_seen_order() {
+        let temperatures = vec![
+            temperature("k10temp", "Tctl", 60.0),
+            temperature("amdgpu", "edge", 50.0),
+            temperature("k10temp", "Tccd1", 55.0),
+        ];
+        let groups: Vec<(&str, Vec<&str>)> = group_by_chip(&temperatures)
+            .into_iter()
+            .map(|(chip, sensors)| {
+                (
+                    chip,
+                    sensors.iter().map(|sensor| sensor.label.as_str()).collect(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            groups,
+            vec![("k10temp", vec!["Tctl", "Tccd1"]), ("amdgpu", vec!["edge"])]
+        );
+    }
+
+    #[test]
+    fn test_render_snapshot_only_prints_selected_sections() {
+        let snapshot = SectionSnapshot {
+            cpu: Some(CpuMetrics {
+                usage_percent: 25.0,
+                user_percent: 20.0,
+                system_percent: 5.0,
+                idle_percent: 75.0,
+                steal_percent: 0.0,
+                iowait_percent: 0.0,
+                irq_percent: 0.0,
+                softirq_percent: 0.0,
+                nice_percent: 0.0,
+                guest_percent: 0.0,
+                core_count: 2,
+                per_core_usage: vec![10.0, 40.0],
+                frequency: 3600,
+                name: "Test CPU".to_string(),
+                cores: Vec::new(),
+                core_classes: Vec::new(),
+                frequency_policy: CpuFrequencyPolicy::default(),
+                throttle: CpuThrottleMetrics::default(),
+            }),
+            gpus: Some(Vec::new()),
+            ..SectionSnapshot::default()
+        };
+
+        let output = render_snapshot(&snapshot);
+
+        assert!(output.starts_with("CPU: Test CPU (2 núcleos, 3600 MHz)\n"));
+        assert!(output.contains("  cpu1   [||||||||            ]  40.0%\n"));
+        assert!(output.contains("\nGPUs\n  Nenhuma GPU detectada\n"));
+        assert!(!output.contains("Memória"));
+    }
+}
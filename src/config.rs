@@ -0,0 +1,572 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use inotify::{Inotify, WatchMask};
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+use crate::alerts::AlertRule;
+
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+pub const RECORDER_FILE_NAME: &str = "metrics.sqlite";
+
+const DEFAULT_REFRESH_INTERVAL_MS: u64 = 1000;
+const DEFAULT_TOP_PROCESSES: usize = 15;
+const DEFAULT_HISTORY_DURATION_MS: u64 = 5 * 60 * 1000;
+/// Mede latência a cada N ciclos (~10s com sampleInterval de 1500ms).
+const DEFAULT_LATENCY_INTERVAL_CYCLES: u32 = 7;
+/// Atualiza GPU com menor frequência para evitar scan de DRM e `nvidia-smi` em todo ciclo.
+const DEFAULT_GPU_INTERVAL_CYCLES: u32 = 3;
+/// Atualiza sensores com menor frequência, sem perder responsividade percebida.
+const DEFAULT_SENSOR_INTERVAL_CYCLES: u32 = 2;
+/// Atualiza processos com menor frequência, reduzindo custo de `/proc/<pid>`.
+const DEFAULT_PROCESS_INTERVAL_CYCLES: u32 = 2;
+/// Frequência muda pouco; não precisa ser atualizada em todo tick.
+const DEFAULT_CPU_FREQUENCY_INTERVAL_CYCLES: u32 = 10;
+
+/// Um snapshot por minuto no gravador SQLite.
+const DEFAULT_RECORDER_INTERVAL_MS: u64 = 60 * 1000;
+const DEFAULT_RECORDER_RETENTION_DAYS: u32 = 30;
+
+const MIN_REFRESH_INTERVAL_MS: u64 = 250;
+const MAX_REFRESH_INTERVAL_MS: u64 = 60_000;
+const MIN_HISTORY_DURATION_MS: u64 = 30 * 1000;
+const MAX_HISTORY_DURATION_MS: u64 = 60 * 60 * 1000;
+const MAX_TOP_PROCESSES: usize = 200;
+const MAX_INTERVAL_CYCLES: u32 = 3600;
+const MIN_RECORDER_INTERVAL_MS: u64 = 1000;
+const MAX_RECORDER_INTERVAL_MS: u64 = 60 * 60 * 1000;
+const MAX_RECORDER_RETENTION_DAYS: u32 = 3650;
+/// Checkpoints do histórico a cada décimo da janela, entre 15 s e 1 min: uma queda
+/// perde no máximo esse trecho, sem regravar o arquivo a cada ciclo.
+const MIN_HISTORY_CHECKPOINT_INTERVAL_MS: u64 = 15 * 1000;
+const MAX_HISTORY_CHECKPOINT_INTERVAL_MS: u64 = 60 * 1000;
+
+/// Parâmetros de coleta ajustáveis em tempo de execução.
+///
+/// Lido de `$XDG_CONFIG_HOME/monitor-tray/config.toml`; campos ausentes usam o padrão
+/// e chaves desconhecidas são rejeitadas, como em `with_json_patch`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MonitorConfig {
+    /// Intervalo entre ciclos do atualizador em background do serviço DBus.
+    pub refresh_interval_ms: u64,
+    /// Janela dos buffers de histórico.
+    pub history_duration_ms: u64,
+    /// Quantidade de processos retornados em `top_processes`.
+    pub top_processes: usize,
+    pub latency_interval_cycles: u32,
+    pub gpu_interval_cycles: u32,
+    pub sensor_interval_cycles: u32,
+    pub process_interval_cycles: u32,
+    pub cpu_frequency_interval_cycles: u32,
+    /// Host medido com `ping`; `None` mede o gateway padrão.
+    pub latency_target: Option<String>,
+    /// Rótulos de sensores omitidos de `SensorMetrics` (comparação sem diferenciar maiúsculas).
+    /// Temperaturas também podem ser identificadas como `"chip: rótulo"`.
+    pub hidden_sensors: Vec<String>,
+    /// cgroup v2, relativo a `/sys/fs/cgroup`, cujos `*.pressure` são lidos junto com
+    /// `/proc/pressure` (por exemplo `user.slice/user-1000.slice`).
+    pub pressure_cgroup: Option<String>,
+    /// Regras de alerta avaliadas a cada ciclo do atualizador (`[[alerts]]` no TOML).
+    pub alerts: Vec<AlertRule>,
+    /// Gravação periódica de snapshots em SQLite (`[recorder]` no TOML).
+    pub recorder: RecorderConfig,
+}
+
+/// Gravador de snapshots do serviço DBus; desativado por padrão.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RecorderConfig {
+    pub enabled: bool,
+    /// Intervalo entre snapshots gravados, independente do `refresh_interval_ms`.
+    pub interval_ms: u64,
+    /// Snapshots mais antigos que isso são apagados.
+    pub retention_days: u32,
+    /// Banco SQLite; `None` usa `$XDG_STATE_HOME/monitor-tray/metrics.sqlite`.
+    pub path: Option<PathBuf>,
+}
+
+impl Default for RecorderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_ms: DEFAULT_RECORDER_INTERVAL_MS,
+            retention_days: DEFAULT_RECORDER_RETENTION_DAYS,
+            path: None,
+        }
+    }
+}
+
+impl RecorderConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+
+    pub fn retention(&self) -> Duration {
+        Duration::from_secs(u64::from(self.retention_days) * 24 * 60 * 60)
+    }
+
+    pub fn database_path(&self) -> PathBuf {
+        self.path
+            .clone()
+            .unwrap_or_else(|| state_dir().join(RECORDER_FILE_NAME))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(MIN_RECORDER_INTERVAL_MS..=MAX_RECORDER_INTERVAL_MS).contains(&self.interval_ms) {
+            return Err(format!(
+                "recorder.interval_ms deve estar entre {MIN_RECORDER_INTERVAL_MS} e {MAX_RECORDER_INTERVAL_MS}"
+            ));
+        }
+        if !(1..=MAX_RECORDER_RETENTION_DAYS).contains(&self.retention_days) {
+            return Err(format!(
+                "recorder.retention_days deve estar entre 1 e {MAX_RECORDER_RETENTION_DAYS}"
+            ));
+        }
+        if self.path.as_ref().is_some_and(|path| !path.is_absolute()) {
+            return Err("recorder.path deve ser um caminho absoluto".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            refresh_interval_ms: DEFAULT_REFRESH_INTERVAL_MS,
+            history_duration_ms: DEFAULT_HISTORY_DURATION_MS,
+            top_processes: DEFAULT_TOP_PROCESSES,
+            latency_interval_cycles: DEFAULT_LATENCY_INTERVAL_CYCLES,
+            gpu_interval_cycles: DEFAULT_GPU_INTERVAL_CYCLES,
+            sensor_interval_cycles: DEFAULT_SENSOR_INTERVAL_CYCLES,
+            process_interval_cycles: DEFAULT_PROCESS_INTERVAL_CYCLES,
+            cpu_frequency_interval_cycles: DEFAULT_CPU_FREQUENCY_INTERVAL_CYCLES,
+            latency_target: None,
+            hidden_sensors: Vec::new(),
+            pressure_cgroup: None,
+            alerts: Vec::new(),
+            recorder: RecorderConfig::default(),
+        }
+    }
+}
+
+impl MonitorConfig {
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_millis(self.refresh_interval_ms)
+    }
+
+    /// Intervalo entre checkpoints do histórico em disco, derivado de `history_duration_ms`.
+    pub fn history_checkpoint_interval(&self) -> Duration {
+        Duration::from_millis((self.history_duration_ms / 10).clamp(
+            MIN_HISTORY_CHECKPOINT_INTERVAL_MS,
+            MAX_HISTORY_CHECKPOINT_INTERVAL_MS,
+        ))
+    }
+
+    /// Rejeita valores fora das faixas suportadas pelo coletor.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_REFRESH_INTERVAL_MS..=MAX_REFRESH_INTERVAL_MS).contains(&self.refresh_interval_ms)
+        {
+            return Err(format!(
+                "refresh_interval_ms deve estar entre {MIN_REFRESH_INTERVAL_MS} e {MAX_REFRESH_INTERVAL_MS}"
+            ));
+        }
+        if !(MIN_HISTORY_DURATION_MS..=MAX_HISTORY_DURATION_MS).contains(&self.history_duration_ms)
+        {
+            return Err(format!(
+                "history_duration_ms deve estar entre {MIN_HISTORY_DURATION_MS} e {MAX_HISTORY_DURATION_MS}"
+            ));
+        }
+        if !(1..=MAX_TOP_PROCESSES).contains(&self.top_processes) {
+            return Err(format!(
+                "top_processes deve estar entre 1 e {MAX_TOP_PROCESSES}"
+            ));
+        }
+
+        let cycles = [
+            ("latency_interval_cycles", self.latency_interval_cycles),
+            ("gpu_interval_cycles", self.gpu_interval_cycles),
+            ("sensor_interval_cycles", self.sensor_interval_cycles),
+            ("process_interval_cycles", self.process_interval_cycles),
+            (
+                "cpu_frequency_interval_cycles",
+                self.cpu_frequency_interval_cycles,
+            ),
+        ];
+        for (name, value) in cycles {
+            if !(1..=MAX_INTERVAL_CYCLES).contains(&value) {
+                return Err(format!("{name} deve estar entre 1 e {MAX_INTERVAL_CYCLES}"));
+            }
+        }
+
+        if let Some(target) = &self.latency_target {
+            // O alvo vira argumento do `ping`: não pode ser vazio nem parecer uma opção.
+            let valid = !target.is_empty()
+                && !target.starts_with('-')
+                && target.chars().all(|character| {
+                    character.is_ascii_alphanumeric() || ".-:_".contains(character)
+                });
+            if !valid {
+                return Err(format!("latency_target inválido: {target:?}"));
+            }
+        }
+
+        if let Some(cgroup) = &self.pressure_cgroup {
+            let path = Path::new(cgroup);
+            let valid = !cgroup.is_empty()
+                && path
+                    .components()
+                    .all(|component| matches!(component, std::path::Component::Normal(_)));
+            if !valid {
+                return Err(format!(
+                    "pressure_cgroup deve ser relativo a /sys/fs/cgroup, sem '..': {cgroup:?}"
+                ));
+            }
+        }
+
+        self.recorder.validate()?;
+
+        for (index, rule) in self.alerts.iter().enumerate() {
+            rule.validate()?;
+            if self.alerts[..index]
+                .iter()
+                .any(|other| other.name == rule.name)
+            {
+                return Err(format!("alerta duplicado: {:?}", rule.name));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Interpreta e valida o conteúdo TOML do arquivo de configuração.
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        let config: Self = toml::from_str(content).map_err(|error| error.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Aplica um objeto JSON parcial sobre esta configuração e valida o resultado.
+    /// Chaves desconhecidas são rejeitadas para não ignorar erros de digitação.
+    pub fn with_json_patch(&self, patch: &str) -> Result<Self, String> {
+        let patch: serde_json::Value =
+            serde_json::from_str(patch).map_err(|error| error.to_string())?;
+        let Some(patch) = patch.as_object() else {
+            return Err("a configuração deve ser um objeto JSON".to_string());
+        };
+
+        let mut merged = serde_json::to_value(self).map_err(|error| error.to_string())?;
+        let Some(fields) = merged.as_object_mut() else {
+            return Err("configuração atual inválida".to_string());
+        };
+        for (key, value) in patch {
+            if !fields.contains_key(key) {
+                return Err(format!("campo de configuração desconhecido: {key}"));
+            }
+            fields.insert(key.clone(), value.clone());
+        }
+
+        let config: Self = serde_json::from_value(merged).map_err(|error| error.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Indica se `label` (ou `"chip: label"`) está na lista de sensores ocultos.
+    pub fn is_sensor_hidden(&self, chip: Option<&str>, label: &str) -> bool {
+        self.hidden_sensors.iter().any(|hidden| {
+            hidden.eq_ignore_ascii_case(label)
+                || chip
+                    .map(|chip| hidden.eq_ignore_ascii_case(&format!("{chip}: {label}")))
+                    .unwrap_or(false)
+        })
+    }
+}
+
+/// Resolve um diretório XDG, usando `fallback` relativo a `$HOME` quando a variável não existe.
+pub(crate) fn xdg_dir(variable: &str, fallback: &str) -> PathBuf {
+    std::env::var_os(variable)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .unwrap_or_else(|| {
+            std::env::var_os("HOME")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("/tmp"))
+                .join(fallback)
+        })
+}
+
+/// Diretório de configuração: `$XDG_CONFIG_HOME/monitor-tray/`.
+pub fn config_dir() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", ".config").join("monitor-tray")
+}
+
+/// Diretório de estado persistente (histórico): `$XDG_STATE_HOME/monitor-tray/`.
+pub fn state_dir() -> PathBuf {
+    xdg_dir("XDG_STATE_HOME", ".local/state").join("monitor-tray")
+}
+
+pub fn config_path() -> PathBuf {
+    config_dir().join(CONFIG_FILE_NAME)
+}
+
+/// Carrega a configuração de `path`.
+/// Arquivo ausente resulta na configuração padrão; arquivo inválido resulta em erro.
+pub fn load_config(path: &Path) -> Result<MonitorConfig, String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => MonitorConfig::from_toml(&content)
+            .map_err(|error| format!("{}: {error}", path.display())),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(MonitorConfig::default()),
+        Err(error) => Err(format!("{}: {error}", path.display())),
+    }
+}
+
+/// Grava a configuração em `path` de forma atômica (arquivo temporário + rename).
+pub fn save_config(path: &Path, config: &MonitorConfig) -> Result<(), String> {
+    let content = toml::to_string_pretty(config).map_err(|error| error.to_string())?;
+    let directory = path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+    std::fs::create_dir_all(&directory)
+        .map_err(|error| format!("{}: {error}", directory.display()))?;
+
+    let temp_path = path.with_extension("toml.tmp");
+    std::fs::write(&temp_path, content)
+        .and_then(|_| std::fs::rename(&temp_path, path))
+        .map_err(|error| format!("{}: {error}", path.display()))
+}
+
+/// Observa `path` com inotify e publica cada nova configuração válida em `updates`.
+///
+/// O diretório pai é observado (e criado se necessário) para acompanhar editores que
+/// substituem o arquivo via rename. Conteúdo inválido é reportado em stderr e ignorado,
+/// mantendo a última configuração aplicada.
+pub fn watch_config(path: PathBuf, updates: watch::Sender<MonitorConfig>) -> std::io::Result<()> {
+    let directory = path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+    std::fs::create_dir_all(&directory)?;
+
+    let mut inotify = Inotify::init()?;
+    inotify.watches().add(
+        &directory,
+        WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO | WatchMask::DELETE,
+    )?;
+
+    let file_name = path.file_name().map(|name| name.to_os_string());
+
+    std::thread::Builder::new()
+        .name("config-watcher".to_string())
+        .spawn(move || {
+            let mut buffer = [0u8; 4096];
+            loop {
+                let Ok(events) = inotify.read_events_blocking(&mut buffer) else {
+                    break;
+                };
+                let touches_config = events
+                    .into_iter()
+                    .any(|event| event.name.map(|name| name.to_os_string()) == file_name);
+                if !touches_config {
+                    continue;
+                }
+
+                match load_config(&path) {
+                    Ok(config) => {
+                        updates.send_if_modified(|current| {
+                            let changed = *current != config;
+                            *current = config;
+                            changed
+                        });
+                    }
+                    Err(error) => eprintln!("monitor-tray: configuração ignorada: {error}"),
+                }
+
+                if updates.is_closed() {
+                    break;
+                }
+            }
+        })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml_fills_missing_fields_with_defaults() {
+        let config = MonitorConfig::from_toml("top_processes = 5\nlatency_interval_cycles = 20\n")
+            .expect("partial config should parse");
+
+        assert_eq!(config.top_processes, 5);
+        assert_eq!(config.latency_interval_cycles, 20);
+        assert_eq!(config.history_duration_ms, DEFAULT_HISTORY_DURATION_MS);
+        assert_eq!(config.refresh_interval_ms, DEFAULT_REFRESH_INTERVAL_MS);
+    }
+
+    #[test]
+    fn test_history_checkpoint_interval_follows_history_window() {
+        let with_window = |history_duration_ms| MonitorConfig {
+            history_duration_ms,
+            ..MonitorConfig::default()
+        };
+
+        assert_eq!(
+            MonitorConfig::default().history_checkpoint_interval(),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            with_window(MIN_HISTORY_DURATION_MS).history_checkpoint_interval(),
+            Duration::from_secs(15)
+        );
+        assert_eq!(
+            with_window(MAX_HISTORY_DURATION_MS).history_checkpoint_interval(),
+            Duration::from_secs(60)
+        );
+    }
+
+    #[test]
+    fn test_from_toml_reads_alert_rules() {
+        let config = MonitorConfig::from_toml(
+            r#"
+top_processes = 5
+
+[[alerts]]
+name = "CPU quente"
+metric = "sensors.hottest_cpu_celsius"
+above = 90.0
+for_ms = 10000
+
+[[alerts]]
+name = "Sem gateway"
+metric = "network.gateway_latency_ms"
+missing = true
+"#,
+        )
+        .expect("alert rules should parse");
+
+        assert_eq!(config.alerts.len(), 2);
+        assert_eq!(config.alerts[0].above, Some(90.0));
+        assert_eq!(config.alerts[0].for_ms, 10_000);
+        assert!(config.alerts[1].missing);
+        assert!(MonitorConfig::from_toml(&toml::to_string_pretty(&config).unwrap()).is_ok());
+
+        let duplicated = "[[alerts]]\nname = \"a\"\nmetric = \"cpu.usage_percent\"\nabove = 1.0\n";
+        assert!(MonitorConfig::from_toml(&duplicated.repeat(2)).is_err());
+        assert!(MonitorConfig::from_toml(
+            "[[alerts]]\nname = \"a\"\nmetric = \"cpu.usage_percent\"\nabove = 1.0\nabov = 2.0\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_from_toml_rejects_unknown_keys() {
+        let error = MonitorConfig::from_toml("refresh_interval = 2000")
+            .expect_err("typo should be rejected");
+        assert!(error.contains("refresh_interval"), "{error}");
+        assert!(MonitorConfig::from_toml("[recorder]\nenable = true").is_err());
+        assert!(MonitorConfig::default()
+            .with_json_patch(r#"{"recorder": {"enabled": true, "interval": 1}}"#)
+            .is_err());
+        assert!(MonitorConfig::default()
+            .with_json_patch(r#"{"recorder": {"enabled": true}}"#)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_from_toml_rejects_out_of_range_values() {
+        assert!(MonitorConfig::from_toml("gpu_interval_cycles = 0").is_err());
+        assert!(MonitorConfig::from_toml("refresh_interval_ms = 10").is_err());
+        assert!(MonitorConfig::from_toml("top_processes = \"muitos\"").is_err());
+        assert!(MonitorConfig::from_toml("[recorder]\nretention_days = 0").is_err());
+        assert!(MonitorConfig::from_toml("[recorder]\npath = \"metrics.sqlite\"").is_err());
+        assert!(MonitorConfig::from_toml("pressure_cgroup = \"../etc\"").is_err());
+        assert!(MonitorConfig::from_toml("pressure_cgroup = \"/user.slice\"").is_err());
+        assert!(MonitorConfig::from_toml("pressure_cgroup = \"user.slice/app.slice\"").is_ok());
+        let recorder = MonitorConfig::from_toml("[recorder]\nenabled = true\n")
+            .expect("recorder section should parse")
+            .recorder;
+        assert!(recorder.enabled);
+        assert_eq!(recorder.interval_ms, DEFAULT_RECORDER_INTERVAL_MS);
+    }
+
+    #[test]
+    fn test_with_json_patch_keeps_fields_not_present_in_patch() {
+        let current = MonitorConfig {
+            top_processes: 8,
+            ..MonitorConfig::default()
+        };
+
+        let patched = current
+            .with_json_patch(
+                r#"{"latency_target": "1.1.1.1", "hidden_sensors": ["acpitz: Temp 1"]}"#,
+            )
+            .expect("patch should apply");
+
+        assert_eq!(patched.top_processes, 8);
+        assert_eq!(patched.latency_target.as_deref(), Some("1.1.1.1"));
+        assert_eq!(patched.hidden_sensors, vec!["acpitz: Temp 1".to_string()]);
+    }
+
+    #[test]
+    fn test_with_json_patch_rejects_unknown_and_invalid_fields() {
+        let current = MonitorConfig::default();
+
+        assert!(current.with_json_patch(r#"{"top_process": 3}"#).is_err());
+        assert!(current.with_json_patch(r#"{"top_processes": 0}"#).is_err());
+        assert!(current.with_json_patch("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_latency_target_that_looks_like_an_option() {
+        let mut config = MonitorConfig {
+            latency_target: Some("-f".to_string()),
+            ..MonitorConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        config.latency_target = Some("1.1.1.1".to_string());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_is_sensor_hidden_matches_label_or_chip_and_label() {
+        let config = MonitorConfig {
+            hidden_sensors: vec!["acpitz: Temp 1".to_string(), "nct6798: Fan 3".to_string()],
+            ..MonitorConfig::default()
+        };
+
+        assert!(config.is_sensor_hidden(Some("acpitz"), "Temp 1"));
+        assert!(config.is_sensor_hidden(None, "NCT6798: fan 3"));
+        assert!(!config.is_sensor_hidden(Some("coretemp"), "Temp 1"));
+    }
+
+    #[test]
+    fn test_save_config_round_trips_through_toml() {
+        let path = crate::test_support::temp_fixture_dir().join(CONFIG_FILE_NAME);
+        let config = MonitorConfig {
+            top_processes: 8,
+            latency_target: Some("example.com".to_string()),
+            hidden_sensors: vec!["acpitz: Temp 1".to_string()],
+            ..MonitorConfig::default()
+        };
+
+        save_config(&path, &config).expect("config should be saved");
+        let loaded = load_config(&path).expect("saved config should load");
+
+        assert_eq!(loaded, config);
+        std::fs::remove_dir_all(path.parent().expect("path should have a parent"))
+            .expect("should clean config dir");
+    }
+
+    #[test]
+    fn test_load_config_returns_defaults_when_file_is_missing() {
+        let config = load_config(Path::new("/nonexistent/monitor-tray/config.toml"))
+            .expect("missing file should not be an error");
+
+        assert_eq!(config, MonitorConfig::default());
+    }
+}
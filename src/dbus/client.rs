@@ -0,0 +1,46 @@
+//! Proxy cliente de `com.monitortray.Backend`, usado pelos modos de linha de comando
+//! para consultar um serviço já em execução.
+
+use zbus::{proxy, Connection};
+
+#[proxy(
+    interface = "com.monitortray.Backend",
+    default_service = "com.monitortray.Backend",
+    default_path = "/com/monitortray/Backend"
+)]
+pub trait Backend {
+    fn ping(&self) -> zbus::Result<String>;
+
+    fn fast_metrics_json(&self) -> zbus::Result<String>;
+
+    fn slow_metrics_json(&self) -> zbus::Result<String>;
+
+    fn history_metrics_json(&self) -> zbus::Result<String>;
+
+    fn history_range_json(&self, series: &str, range: &str) -> zbus::Result<String>;
+
+    fn history_csv(&self, series: &str) -> zbus::Result<String>;
+
+    fn start_network_speed_test(&self) -> zbus::Result<bool>;
+
+    fn cancel_network_speed_test(&self) -> zbus::Result<bool>;
+
+    fn get_network_speed_test_status_json(&self) -> zbus::Result<String>;
+
+    #[zbus(signal)]
+    fn fast_metrics_updated(&self, payload: String) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn slow_metrics_updated(&self, payload: String) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn speed_test_status_changed(&self, payload: String) -> zbus::Result<()>;
+}
+
+/// Conecta ao serviço no barramento de sessão; `None` quando ele não está em execução.
+pub async fn connect_backend() -> Option<BackendProxy<'static>> {
+    let connection = Connection::session().await.ok()?;
+    let proxy = BackendProxy::new(&connection).await.ok()?;
+    proxy.ping().await.ok()?;
+    Some(proxy)
+}
@@ -0,0 +1,573 @@
+pub mod client;
+mod typed;
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::{watch, Mutex, RwLock};
+use zbus::{interface, Connection, SignalContext};
+
+use crate::{
+    alerts::{run_alert_command, AlertEngine, AlertEvent, AlertEventKind, AlertNotifier},
+    cancel_network_speed_test, collect_history_metrics_json, collect_metrics_json,
+    collect_slow_metrics_json,
+    config::{config_path, load_config, save_config, watch_config, MonitorConfig, RecorderConfig},
+    get_network_speed_test_status_json,
+    monitor::{history_path, HistoryRange, NetworkSpeedTestStatus, SystemMetrics, SystemMonitor},
+    recorder::MetricsRecorder,
+    speedtest::NetworkSpeedTestManager,
+    start_network_speed_test,
+    unix_time::unix_now,
+    DBUS_OBJECT_PATH, DBUS_SERVICE_NAME,
+};
+
+pub use typed::TypedMetricsBackend;
+use typed::{publish_key_scalars, KeyScalars};
+
+pub struct MetricsBackend {
+    monitor: Arc<Mutex<SystemMonitor>>,
+    caches: BackendCaches,
+    speed_test: NetworkSpeedTestManager,
+    config: watch::Sender<MonitorConfig>,
+    config_path: PathBuf,
+}
+
+/// Caches compartilhados entre as interfaces DBus e o atualizador em background.
+#[derive(Clone)]
+struct BackendCaches {
+    fast_metrics: Arc<RwLock<String>>,
+    history_metrics: Arc<RwLock<String>>,
+    key_scalars: Arc<RwLock<KeyScalars>>,
+}
+
+impl Default for MetricsBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsBackend {
+    pub fn new() -> Self {
+        Self::with_config(MonitorConfig::default(), config_path())
+    }
+
+    /// Cria o backend com `config`, restaurando o histórico salvo em [`history_path`];
+    /// alterações feitas via `SetConfig`/`ResetConfig` são persistidas em `config_path`.
+    pub fn with_config(config: MonitorConfig, config_path: PathBuf) -> Self {
+        let (config_sender, _) = watch::channel(config.clone());
+        let mut monitor = SystemMonitor::with_config(config);
+        if let Err(error) = monitor.restore_history(&history_path()) {
+            eprintln!("monitor-tray: histórico salvo ignorado: {error}");
+        }
+        let initial_fast_metrics =
+            serde_json::to_string(&monitor.get_fast_metrics()).unwrap_or_else(|_| "{}".to_string());
+        let initial_history_metrics =
+            collect_history_metrics_json(&monitor).unwrap_or_else(|_| "{}".to_string());
+
+        Self {
+            monitor: Arc::new(Mutex::new(monitor)),
+            caches: BackendCaches {
+                fast_metrics: Arc::new(RwLock::new(initial_fast_metrics)),
+                history_metrics: Arc::new(RwLock::new(initial_history_metrics)),
+                key_scalars: Arc::new(RwLock::new(KeyScalars::default())),
+            },
+            speed_test: NetworkSpeedTestManager::new(),
+            config: config_sender,
+            config_path,
+        }
+    }
+
+    /// Canal de configuração aplicado pelo atualizador; pode ser alimentado
+    /// por outras fontes, como o observador do arquivo de configuração.
+    pub fn config_sender(&self) -> watch::Sender<MonitorConfig> {
+        self.config.clone()
+    }
+
+    /// Persiste `config` e a publica para o atualizador aplicar no próximo ciclo.
+    fn store_config(&self, config: MonitorConfig) -> zbus::fdo::Result<String> {
+        save_config(&self.config_path, &config).map_err(zbus::fdo::Error::Failed)?;
+        let payload = serde_json::to_string(&config)
+            .map_err(|err| zbus::fdo::Error::Failed(err.to_string()))?;
+        self.config.send_replace(config);
+        Ok(payload)
+    }
+
+    /// Cria a interface tipada `com.monitortray.Backend2` compartilhando o
+    /// mesmo monitor, caches e speed test desta instância.
+    pub fn typed_backend(&self) -> TypedMetricsBackend {
+        TypedMetricsBackend {
+            monitor: self.monitor.clone(),
+            key_scalars: self.caches.key_scalars.clone(),
+            speed_test: self.speed_test.clone(),
+        }
+    }
+
+    /// Inicia o atualizador de caches e o repasse de status do speed test.
+    /// Os sinais são emitidos em `signal_context`, que deve apontar para o
+    /// object path onde este backend será servido. Cada configuração publicada
+    /// em [`MetricsBackend::config_sender`] é aplicada ao monitor no início do ciclo seguinte.
+    pub fn spawn_background_tasks(&self, signal_context: SignalContext<'static>) {
+        spawn_metrics_updater(
+            self.monitor.clone(),
+            self.caches.clone(),
+            signal_context.clone(),
+            self.config.subscribe(),
+        );
+        spawn_speed_test_status_forwarder(self.speed_test.subscribe(), signal_context);
+    }
+}
+
+async fn refresh_backend_caches(
+    monitor: &Arc<Mutex<SystemMonitor>>,
+    caches: &BackendCaches,
+    signal_context: &SignalContext<'static>,
+    force_slow_refresh: bool,
+) {
+    let mut locked_monitor = monitor.lock().await;
+    locked_monitor.update_fast_metrics().await;
+    let slow_refreshed = locked_monitor
+        .refresh_slow_metrics(force_slow_refresh)
+        .await;
+
+    let fast_metrics_json = serde_json::to_string(&locked_monitor.get_fast_metrics())
+        .unwrap_or_else(|_| "{}".to_string());
+    let slow_metrics_json = slow_refreshed
+        .then(|| serde_json::to_string(&locked_monitor.get_slow_metrics()).ok())
+        .flatten();
+    let history_metrics_json =
+        collect_history_metrics_json(&locked_monitor).unwrap_or_else(|_| "{}".to_string());
+    let key_scalars = KeyScalars::from_monitor(&locked_monitor);
+    drop(locked_monitor);
+
+    *caches.fast_metrics.write().await = fast_metrics_json.clone();
+    *caches.history_metrics.write().await = history_metrics_json;
+
+    // Falhas ao emitir sinais não devem interromper o ciclo de coleta.
+    let _ = MetricsBackend::fast_metrics_updated(signal_context, &fast_metrics_json).await;
+    if let Some(payload) = slow_metrics_json {
+        let _ = MetricsBackend::slow_metrics_updated(signal_context, &payload).await;
+    }
+    let _ = publish_key_scalars(&caches.key_scalars, key_scalars, signal_context).await;
+}
+
+fn spawn_metrics_updater(
+    monitor: Arc<Mutex<SystemMonitor>>,
+    caches: BackendCaches,
+    signal_context: SignalContext<'static>,
+    mut config_updates: watch::Receiver<MonitorConfig>,
+) {
+    tokio::spawn(async move {
+        refresh_backend_caches(&monitor, &caches, &signal_context, true).await;
+        let (mut refresh_interval, mut checkpoint_interval, alert_rules, recorder_config) = {
+            let monitor = monitor.lock().await;
+            let config = monitor.config();
+            (
+                config.refresh_interval(),
+                config.history_checkpoint_interval(),
+                config.alerts.clone(),
+                config.recorder.clone(),
+            )
+        };
+        let mut alerts = AlertEngine::new(alert_rules);
+        let mut notifier = AlertNotifier::default();
+        let mut last_checkpoint = Instant::now();
+        let mut recorder = SnapshotRecorder::new(recorder_config);
+
+        loop {
+            tokio::time::sleep(refresh_interval).await;
+
+            if config_updates.has_changed().unwrap_or(false) {
+                let config = config_updates.borrow_and_update().clone();
+                refresh_interval = config.refresh_interval();
+                checkpoint_interval = config.history_checkpoint_interval();
+                let cleared = alerts.set_rules(config.alerts.clone());
+                recorder.apply_config(config.recorder.clone());
+                let payload = serde_json::to_string(&config);
+                monitor.lock().await.apply_config(config);
+                if let Ok(payload) = payload {
+                    let _ = MetricsBackend::config_changed(&signal_context, &payload).await;
+                }
+                // Fora de `evaluate`: após remover a última regra ele não roda mais.
+                dispatch_alert_events(&signal_context, &mut notifier, cleared).await;
+            }
+
+            refresh_backend_caches(&monitor, &caches, &signal_context, false).await;
+
+            let throttling = monitor.lock().await.take_throttling_started();
+            if let Some(payload) =
+                throttling.and_then(|throttle| serde_json::to_string(&throttle).ok())
+            {
+                let _ = MetricsBackend::throttling_started(&signal_context, &payload).await;
+            }
+
+            if !alerts.is_empty() {
+                let snapshot = serde_json::to_value(monitor.lock().await.get_all_metrics());
+                if let Ok(snapshot) = snapshot {
+                    let events = alerts.evaluate(&snapshot, Instant::now());
+                    spawn_alert_commands(&alerts, &events, snapshot);
+                    dispatch_alert_events(&signal_context, &mut notifier, events).await;
+                }
+            }
+
+            if recorder.is_due() {
+                recorder.record(&monitor).await;
+            }
+
+            if last_checkpoint.elapsed() >= checkpoint_interval {
+                last_checkpoint = Instant::now();
+                checkpoint_history(&monitor).await;
+            }
+        }
+    });
+}
+
+/// Estado do gravador SQLite no atualizador: decide quando gravar e repassa o snapshot
+/// a uma thread própria, para que transações, a limpeza da retenção e a espera do
+/// `busy_timeout` não atrasem sinais, alertas e propriedades tipadas.
+struct SnapshotRecorder {
+    config: RecorderConfig,
+    last_record: Option<Instant>,
+    worker: Option<std::sync::mpsc::Sender<RecorderMessage>>,
+}
+
+enum RecorderMessage {
+    Configure(RecorderConfig),
+    Record(Box<SystemMetrics>, u64),
+}
+
+impl SnapshotRecorder {
+    fn new(config: RecorderConfig) -> Self {
+        Self {
+            config,
+            last_record: None,
+            worker: None,
+        }
+    }
+
+    fn apply_config(&mut self, config: RecorderConfig) {
+        if let Some(worker) = &self.worker {
+            let _ = worker.send(RecorderMessage::Configure(config.clone()));
+        }
+        self.config = config;
+    }
+
+    fn is_due(&self) -> bool {
+        self.config.enabled
+            && self
+                .last_record
+                .is_none_or(|last| last.elapsed() >= self.config.interval())
+    }
+
+    async fn record(&mut self, monitor: &Arc<Mutex<SystemMonitor>>) {
+        self.last_record = Some(Instant::now());
+        let metrics = monitor.lock().await.get_all_metrics();
+        let timestamp_ms = unix_now().as_millis() as u64;
+        let config = self.config.clone();
+        let worker = self.worker.get_or_insert_with(|| {
+            let (sender, receiver) = std::sync::mpsc::channel();
+            std::thread::spawn(move || run_recorder_worker(config, receiver));
+            sender
+        });
+        if worker
+            .send(RecorderMessage::Record(Box::new(metrics), timestamp_ms))
+            .is_err()
+        {
+            self.worker = None;
+        }
+    }
+}
+
+/// Thread do gravador: abre o banco na primeira gravação e o reabre quando `[recorder]`
+/// muda. Uma falha ao abrir só é repetida após nova configuração.
+fn run_recorder_worker(
+    mut config: RecorderConfig,
+    messages: std::sync::mpsc::Receiver<RecorderMessage>,
+) {
+    let mut database: Option<MetricsRecorder> = None;
+    let mut failed = false;
+    for message in messages {
+        match message {
+            RecorderMessage::Configure(new_config) => {
+                if new_config.path != config.path || !new_config.enabled {
+                    database = None;
+                }
+                if let Some(database) = &mut database {
+                    database.set_retention(new_config.retention());
+                }
+                if new_config != config {
+                    failed = false;
+                }
+                config = new_config;
+            }
+            RecorderMessage::Record(metrics, timestamp_ms) => {
+                if !config.enabled || failed {
+                    continue;
+                }
+                if database.is_none() {
+                    let path = config.database_path();
+                    match MetricsRecorder::open(&path, config.retention()) {
+                        Ok(opened) => database = Some(opened),
+                        Err(error) => {
+                            eprintln!("monitor-tray: gravador desativado: {error}");
+                            failed = true;
+                            continue;
+                        }
+                    }
+                }
+                if let Some(database) = &mut database {
+                    if let Err(error) = database.record(&metrics, timestamp_ms) {
+                        eprintln!("monitor-tray: falha ao gravar snapshot: {error}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Executa em background o `command` das regras com eventos, sem atrasar o ciclo de coleta.
+fn spawn_alert_commands(alerts: &AlertEngine, events: &[AlertEvent], snapshot: serde_json::Value) {
+    let snapshot = Arc::new(snapshot);
+    for event in events {
+        let Some(rule) = alerts
+            .rule(&event.rule)
+            .filter(|rule| rule.command.is_some())
+        else {
+            continue;
+        };
+        let (rule, event, snapshot) = (rule.clone(), event.clone(), snapshot.clone());
+        tokio::spawn(async move {
+            if let Err(error) = run_alert_command(&rule, &event, &snapshot).await {
+                eprintln!(
+                    "monitor-tray: comando do alerta {:?} falhou: {error}",
+                    rule.name
+                );
+            }
+        });
+    }
+}
+
+/// Emite `AlertRaised`/`AlertCleared` e envia a notificação de desktop de cada evento.
+async fn dispatch_alert_events(
+    signal_context: &SignalContext<'static>,
+    notifier: &mut AlertNotifier,
+    events: Vec<AlertEvent>,
+) {
+    for event in events {
+        if let Ok(payload) = serde_json::to_string(&event) {
+            let _ = match event.kind {
+                AlertEventKind::Raised => {
+                    MetricsBackend::alert_raised(signal_context, &payload).await
+                }
+                AlertEventKind::Cleared => {
+                    MetricsBackend::alert_cleared(signal_context, &payload).await
+                }
+            };
+        }
+        if let Err(error) = notifier.notify(signal_context.connection(), &event).await {
+            eprintln!("monitor-tray: falha ao enviar notificação de alerta: {error}");
+        }
+    }
+}
+
+async fn checkpoint_history(monitor: &Arc<Mutex<SystemMonitor>>) {
+    if let Err(error) = monitor.lock().await.save_history(&history_path()) {
+        eprintln!("monitor-tray: falha ao salvar histórico: {error}");
+    }
+}
+
+/// Repassa cada mudança de status do speed test como sinal DBus.
+fn spawn_speed_test_status_forwarder(
+    mut status: watch::Receiver<NetworkSpeedTestStatus>,
+    signal_context: SignalContext<'static>,
+) {
+    tokio::spawn(async move {
+        while status.changed().await.is_ok() {
+            let payload = serde_json::to_string(&*status.borrow_and_update());
+            if let Ok(payload) = payload {
+                let _ = MetricsBackend::speed_test_status_changed(&signal_context, &payload).await;
+            }
+        }
+    });
+}
+
+#[interface(name = "com.monitortray.Backend")]
+impl MetricsBackend {
+    async fn ping(&self) -> &str {
+        "ok"
+    }
+
+    async fn get_metrics_json(&self) -> zbus::fdo::Result<String> {
+        let mut monitor = self.monitor.lock().await;
+        collect_metrics_json(&mut monitor)
+            .await
+            .map_err(|err| zbus::fdo::Error::Failed(err.to_string()))
+    }
+
+    async fn fast_metrics_json(&self) -> zbus::fdo::Result<String> {
+        Ok(self.caches.fast_metrics.read().await.clone())
+    }
+
+    async fn slow_metrics_json(&self) -> zbus::fdo::Result<String> {
+        let mut monitor = self.monitor.lock().await;
+        let needs_warmup = monitor.cached_sensors.is_none()
+            || monitor.cached_top_processes.is_none()
+            || monitor.cached_gpus.is_empty();
+
+        if needs_warmup {
+            collect_slow_metrics_json(&mut monitor)
+                .await
+                .map_err(|err| zbus::fdo::Error::Failed(err.to_string()))
+        } else {
+            serde_json::to_string(&monitor.get_slow_metrics())
+                .map_err(|err| zbus::fdo::Error::Failed(err.to_string()))
+        }
+    }
+
+    async fn history_metrics_json(&self) -> zbus::fdo::Result<String> {
+        Ok(self.caches.history_metrics.read().await.clone())
+    }
+
+    /// Agregados min/média/máx de uma série do histórico em `range` (`1h`, `24h` ou `7d`).
+    async fn history_range_json(&self, series: &str, range: &str) -> zbus::fdo::Result<String> {
+        let range = HistoryRange::parse(range).ok_or_else(|| {
+            zbus::fdo::Error::InvalidArgs(format!("janela desconhecida: {range}"))
+        })?;
+        let metrics = self
+            .monitor
+            .lock()
+            .await
+            .get_history_range(series, range)
+            .ok_or_else(|| {
+                zbus::fdo::Error::InvalidArgs(format!("série desconhecida: {series}"))
+            })?;
+        serde_json::to_string(&metrics).map_err(|err| zbus::fdo::Error::Failed(err.to_string()))
+    }
+
+    /// Séries do histórico em CSV, uma linha por instante; `series` é uma lista separada
+    /// por vírgulas e vazia exporta todas.
+    async fn history_csv(&self, series: &str) -> zbus::fdo::Result<String> {
+        let names: Vec<&str> = series
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect();
+        self.monitor
+            .lock()
+            .await
+            .get_history_csv(&names)
+            .map_err(zbus::fdo::Error::InvalidArgs)
+    }
+
+    async fn start_network_speed_test(&self) -> zbus::fdo::Result<bool> {
+        Ok(start_network_speed_test(&self.speed_test).await)
+    }
+
+    async fn cancel_network_speed_test(&self) -> zbus::fdo::Result<bool> {
+        Ok(cancel_network_speed_test(&self.speed_test).await)
+    }
+
+    async fn get_network_speed_test_status_json(&self) -> zbus::fdo::Result<String> {
+        get_network_speed_test_status_json(&self.speed_test)
+            .await
+            .map_err(|err| zbus::fdo::Error::Failed(err.to_string()))
+    }
+
+    async fn get_config(&self) -> zbus::fdo::Result<String> {
+        serde_json::to_string(&*self.config.borrow())
+            .map_err(|err| zbus::fdo::Error::Failed(err.to_string()))
+    }
+
+    /// Aplica uma configuração completa ou parcial em JSON sobre a atual,
+    /// persiste em disco e retorna a configuração resultante.
+    async fn set_config(&self, config_json: &str) -> zbus::fdo::Result<String> {
+        let current = self.config.borrow().clone();
+        let config = current
+            .with_json_patch(config_json)
+            .map_err(zbus::fdo::Error::InvalidArgs)?;
+        self.store_config(config)
+    }
+
+    /// Restaura e persiste a configuração padrão.
+    async fn reset_config(&self) -> zbus::fdo::Result<String> {
+        self.store_config(MonitorConfig::default())
+    }
+
+    /// Emitido quando uma nova configuração é aplicada ao monitor.
+    #[zbus(signal)]
+    async fn config_changed(signal_context: &SignalContext<'_>, payload: &str) -> zbus::Result<()>;
+
+    /// Emitido quando uma regra de alerta dispara.
+    #[zbus(signal)]
+    async fn alert_raised(signal_context: &SignalContext<'_>, payload: &str) -> zbus::Result<()>;
+
+    /// Emitido quando um alerta ativo volta ao normal.
+    #[zbus(signal)]
+    async fn alert_cleared(signal_context: &SignalContext<'_>, payload: &str) -> zbus::Result<()>;
+
+    /// Emitido quando os contadores de throttling térmico da CPU voltam a subir.
+    #[zbus(signal)]
+    async fn throttling_started(
+        signal_context: &SignalContext<'_>,
+        payload: &str,
+    ) -> zbus::Result<()>;
+
+    /// Emitido a cada ciclo do atualizador com o mesmo payload de `FastMetricsJson`.
+    #[zbus(signal)]
+    async fn fast_metrics_updated(
+        signal_context: &SignalContext<'_>,
+        payload: &str,
+    ) -> zbus::Result<()>;
+
+    /// Emitido quando sensores, GPUs ou processos são efetivamente atualizados.
+    #[zbus(signal)]
+    async fn slow_metrics_updated(
+        signal_context: &SignalContext<'_>,
+        payload: &str,
+    ) -> zbus::Result<()>;
+
+    /// Emitido a cada transição de estado ou fase do speed test.
+    #[zbus(signal)]
+    async fn speed_test_status_changed(
+        signal_context: &SignalContext<'_>,
+        payload: &str,
+    ) -> zbus::Result<()>;
+}
+
+pub async fn run_dbus_service() -> Result<(), Box<dyn Error>> {
+    let config_path = config_path();
+    let config = load_config(&config_path).unwrap_or_else(|error| {
+        eprintln!("monitor-tray: usando configuração padrão: {error}");
+        MonitorConfig::default()
+    });
+
+    let connection = Connection::session().await?;
+    let backend = MetricsBackend::with_config(config, config_path.clone());
+    if let Err(error) = watch_config(config_path, backend.config_sender()) {
+        eprintln!("monitor-tray: recarga de configuração desativada: {error}");
+    }
+    let typed_backend = backend.typed_backend();
+    let monitor = backend.monitor.clone();
+    backend.spawn_background_tasks(SignalContext::new(&connection, DBUS_OBJECT_PATH)?.into_owned());
+
+    connection
+        .object_server()
+        .at(DBUS_OBJECT_PATH, backend)
+        .await?;
+    connection
+        .object_server()
+        .at(DBUS_OBJECT_PATH, typed_backend)
+        .await?;
+    connection.request_name(DBUS_SERVICE_NAME).await?;
+
+    // Encerramento pelo systemd (SIGTERM) ou pelo terminal (SIGINT) grava o histórico antes de sair.
+    let mut terminate = signal(SignalKind::terminate())?;
+    tokio::select! {
+        _ = terminate.recv() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    checkpoint_history(&monitor).await;
+    Ok(())
+}
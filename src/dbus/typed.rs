@@ -0,0 +1,983 @@
+//! Interface DBus tipada `com.monitortray.Backend2`.
+//!
+//! Espelha os modelos de `monitor::models` em tipos com assinatura DBus real:
+//! structs sem campos opcionais viram `(…)` e structs com `Option` viram
+//! dicionários `a{sv}`, em que campos ausentes são simplesmente omitidos.
+//! Modelos cujos campos já têm tipo DBus derivam `Type` em `models.rs` e são
+//! usados diretamente; aqui ficam só as cópias com forma diferente (`usize` →
+//! `u32`, enums → string, `Option` → `a{sv}`).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, RwLock};
+use zbus::fdo::Properties;
+use zbus::names::InterfaceName;
+use zbus::zvariant::{DeserializeDict, SerializeDict, Type, Value};
+use zbus::{interface, SignalContext};
+
+use crate::monitor::{self, SystemMonitor};
+use crate::monitor::{DiskMetrics, MemoryMetrics, NetworkInterface, PressureStall, ProcessInfo};
+use crate::speedtest::NetworkSpeedTestManager;
+use crate::DBUS_TYPED_INTERFACE_NAME;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CpuMetrics {
+    pub usage_percent: f64,
+    pub user_percent: f64,
+    pub system_percent: f64,
+    pub idle_percent: f64,
+    pub steal_percent: f64,
+    pub iowait_percent: f64,
+    pub irq_percent: f64,
+    pub softirq_percent: f64,
+    pub nice_percent: f64,
+    pub guest_percent: f64,
+    pub core_count: u32,
+    pub per_core_usage: Vec<f64>,
+    pub frequency: u64,
+    pub name: String,
+    pub cores: Vec<CpuCoreMetrics>,
+    pub core_classes: Vec<CpuCoreClassMetrics>,
+    pub frequency_policy: CpuFrequencyPolicy,
+    pub throttle: CpuThrottleMetrics,
+}
+
+#[derive(Debug, Clone, SerializeDict, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct CpuFrequencyPolicy {
+    pub governor: Option<String>,
+    pub energy_performance_preference: Option<String>,
+    pub scaling_min_mhz: Option<u64>,
+    pub scaling_max_mhz: Option<u64>,
+    pub boost_enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CpuThrottleMetrics {
+    pub throttling: bool,
+    pub throttled_cpus: Vec<u32>,
+    pub core_throttle_count: u64,
+    pub package_throttle_count: u64,
+}
+
+#[derive(Debug, Clone, SerializeDict, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct CpuCoreMetrics {
+    pub id: u32,
+    pub usage_percent: f64,
+    pub user_percent: f64,
+    pub system_percent: f64,
+    pub iowait_percent: f64,
+    pub steal_percent: f64,
+    pub nice_percent: f64,
+    pub idle_percent: f64,
+    pub irq_percent: f64,
+    pub softirq_percent: f64,
+    pub guest_percent: f64,
+    pub frequency_mhz: u64,
+    pub core_type: Option<String>,
+    pub core_id: Option<u32>,
+    pub package_id: Option<u32>,
+    pub cluster_id: Option<u32>,
+    pub smt_siblings: Vec<u32>,
+    pub core_throttle_count: Option<u64>,
+    pub package_throttle_count: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct CpuCoreClassMetrics {
+    pub core_type: String,
+    pub cpu_count: u32,
+    pub physical_core_count: u32,
+    pub usage_percent: f64,
+    pub frequency_mhz: u64,
+}
+
+#[derive(Debug, Clone, SerializeDict, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct NetworkMetrics {
+    pub interfaces: HashMap<String, NetworkInterface>,
+    pub total_bytes_received: u64,
+    pub total_bytes_transmitted: u64,
+    pub gateway_ip: Option<String>,
+    pub gateway_latency_ms: Option<f64>,
+    pub latency_target: Option<String>,
+}
+
+#[derive(Debug, Clone, SerializeDict, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct TemperatureSensor {
+    pub label: String,
+    pub chip: String,
+    pub temperature_celsius: f64,
+    pub max_celsius: Option<f64>,
+    pub critical_celsius: Option<f64>,
+}
+
+#[derive(Debug, Clone, SerializeDict, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct FanSensor {
+    pub label: String,
+    pub chip: String,
+    pub rpm: u64,
+    pub duty_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, SerializeDict, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct SensorMetrics {
+    pub temperatures: Vec<TemperatureSensor>,
+    pub average_temperature_celsius: Option<f64>,
+    pub hottest_temperature_celsius: Option<f64>,
+    pub hottest_label: String,
+    pub hottest_cpu_celsius: Option<f64>,
+    pub hottest_cpu_label: String,
+    pub hottest_gpu_celsius: Option<f64>,
+    pub hottest_gpu_label: String,
+    pub fans: Vec<FanSensor>,
+    /// Rótulo → volts.
+    pub voltages: HashMap<String, f64>,
+    /// Rótulo → ampères.
+    pub currents: HashMap<String, f64>,
+    /// Rótulo → watts.
+    pub powers: HashMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SystemInfo {
+    pub hostname: String,
+    pub os_name: String,
+    pub os_version: String,
+    pub kernel_version: String,
+    pub architecture: String,
+    pub process_count: u32,
+}
+
+#[derive(Debug, Clone, SerializeDict, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct GpuInfo {
+    pub name: String,
+    /// `amd`, `nvidia`, `intel` ou `unknown`, como no payload JSON.
+    pub vendor: String,
+    pub usage_percent: Option<f64>,
+    pub vram_used_gb: Option<f64>,
+    pub vram_total_gb: Option<f64>,
+    pub vram_usage_percent: Option<f64>,
+    pub shader_clock_mhz: Option<u64>,
+    pub memory_clock_mhz: Option<u64>,
+    pub temperature_celsius: Option<f64>,
+    pub power_watts: Option<f64>,
+    pub fan_rpm: Option<u64>,
+    pub fan_duty_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, SerializeDict, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct NetworkSpeedTestStatus {
+    pub state: String,
+    pub phase: String,
+    pub tool: Option<String>,
+    pub ping_ms: Option<f64>,
+    pub download_mbps: Option<f64>,
+    pub upload_mbps: Option<f64>,
+    pub server_name: Option<String>,
+    pub server_location: Option<String>,
+    pub started_at_unix_ms: Option<u64>,
+    pub finished_at_unix_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, SerializeDict, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct PressureResource {
+    pub some: PressureStall,
+    pub full: Option<PressureStall>,
+}
+
+#[derive(Debug, Clone, SerializeDict, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct CgroupPressureMetrics {
+    pub path: String,
+    pub cpu: Option<PressureResource>,
+    pub memory: Option<PressureResource>,
+    pub io: Option<PressureResource>,
+}
+
+#[derive(Debug, Clone, SerializeDict, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct PressureMetrics {
+    pub cpu: Option<PressureResource>,
+    pub memory: Option<PressureResource>,
+    pub io: Option<PressureResource>,
+    pub cgroup: Option<CgroupPressureMetrics>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct FastMetrics {
+    pub cpu: CpuMetrics,
+    pub memory: MemoryMetrics,
+    pub disk: DiskMetrics,
+    pub network: NetworkMetrics,
+    pub uptime: u64,
+    pub load_average: (f64, f64, f64),
+    pub pressure: PressureMetrics,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SlowMetrics {
+    pub sensors: SensorMetrics,
+    pub gpus: Vec<GpuInfo>,
+    pub top_processes: Vec<ProcessInfo>,
+    pub system_info: SystemInfo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct HistorySeries {
+    pub buffer: Vec<f64>,
+    pub start: u32,
+    pub count: u32,
+    pub sample_interval_ms: u64,
+}
+
+fn option_f64(value: Option<f32>) -> Option<f64> {
+    value.map(f64::from)
+}
+
+/// Serializa enums `snake_case` dos modelos exatamente como no payload JSON.
+fn enum_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+impl From<&monitor::CpuMetrics> for CpuMetrics {
+    fn from(cpu: &monitor::CpuMetrics) -> Self {
+        Self {
+            usage_percent: cpu.usage_percent.into(),
+            user_percent: cpu.user_percent.into(),
+            system_percent: cpu.system_percent.into(),
+            idle_percent: cpu.idle_percent.into(),
+            steal_percent: cpu.steal_percent.into(),
+            iowait_percent: cpu.iowait_percent.into(),
+            irq_percent: cpu.irq_percent.into(),
+            softirq_percent: cpu.softirq_percent.into(),
+            nice_percent: cpu.nice_percent.into(),
+            guest_percent: cpu.guest_percent.into(),
+            core_count: cpu.core_count as u32,
+            per_core_usage: cpu.per_core_usage.iter().map(|&v| v.into()).collect(),
+            frequency: cpu.frequency,
+            name: cpu.name.clone(),
+            cores: cpu.cores.iter().map(CpuCoreMetrics::from).collect(),
+            core_classes: cpu
+                .core_classes
+                .iter()
+                .map(CpuCoreClassMetrics::from)
+                .collect(),
+            frequency_policy: CpuFrequencyPolicy {
+                governor: cpu.frequency_policy.governor.clone(),
+                energy_performance_preference: cpu
+                    .frequency_policy
+                    .energy_performance_preference
+                    .clone(),
+                scaling_min_mhz: cpu.frequency_policy.scaling_min_mhz,
+                scaling_max_mhz: cpu.frequency_policy.scaling_max_mhz,
+                boost_enabled: cpu.frequency_policy.boost_enabled,
+            },
+            throttle: CpuThrottleMetrics {
+                throttling: cpu.throttle.throttling,
+                throttled_cpus: cpu
+                    .throttle
+                    .throttled_cpus
+                    .iter()
+                    .map(|&cpu| cpu as u32)
+                    .collect(),
+                core_throttle_count: cpu.throttle.core_throttle_count,
+                package_throttle_count: cpu.throttle.package_throttle_count,
+            },
+        }
+    }
+}
+
+impl From<&monitor::CpuCoreMetrics> for CpuCoreMetrics {
+    fn from(core: &monitor::CpuCoreMetrics) -> Self {
+        Self {
+            id: core.id as u32,
+            usage_percent: core.usage_percent.into(),
+            user_percent: core.user_percent.into(),
+            system_percent: core.system_percent.into(),
+            iowait_percent: core.iowait_percent.into(),
+            steal_percent: core.steal_percent.into(),
+            nice_percent: core.nice_percent.into(),
+            idle_percent: core.idle_percent.into(),
+            irq_percent: core.irq_percent.into(),
+            softirq_percent: core.softirq_percent.into(),
+            guest_percent: core.guest_percent.into(),
+            frequency_mhz: core.frequency_mhz,
+            core_type: core.core_type.as_ref().map(enum_name),
+            core_id: core.core_id,
+            package_id: core.package_id,
+            cluster_id: core.cluster_id,
+            smt_siblings: core.smt_siblings.iter().map(|&cpu| cpu as u32).collect(),
+            core_throttle_count: core.core_throttle_count,
+            package_throttle_count: core.package_throttle_count,
+        }
+    }
+}
+
+impl From<&monitor::CpuCoreClassMetrics> for CpuCoreClassMetrics {
+    fn from(class: &monitor::CpuCoreClassMetrics) -> Self {
+        Self {
+            core_type: enum_name(&class.core_type),
+            cpu_count: class.cpu_count as u32,
+            physical_core_count: class.physical_core_count as u32,
+            usage_percent: class.usage_percent.into(),
+            frequency_mhz: class.frequency_mhz,
+        }
+    }
+}
+
+impl From<&monitor::NetworkMetrics> for NetworkMetrics {
+    fn from(network: &monitor::NetworkMetrics) -> Self {
+        Self {
+            interfaces: network.interfaces.clone(),
+            total_bytes_received: network.total_bytes_received,
+            total_bytes_transmitted: network.total_bytes_transmitted,
+            gateway_ip: network.gateway_ip.clone(),
+            gateway_latency_ms: option_f64(network.gateway_latency_ms),
+            latency_target: network.latency_target.clone(),
+        }
+    }
+}
+
+impl From<&monitor::SensorMetrics> for SensorMetrics {
+    fn from(sensors: &monitor::SensorMetrics) -> Self {
+        Self {
+            temperatures: sensors
+                .temperatures
+                .iter()
+                .map(|sensor| TemperatureSensor {
+                    label: sensor.label.clone(),
+                    chip: sensor.chip.clone(),
+                    temperature_celsius: sensor.temperature_celsius.into(),
+                    max_celsius: option_f64(sensor.max_celsius),
+                    critical_celsius: option_f64(sensor.critical_celsius),
+                })
+                .collect(),
+            average_temperature_celsius: option_f64(sensors.average_temperature_celsius),
+            hottest_temperature_celsius: option_f64(sensors.hottest_temperature_celsius),
+            hottest_label: sensors.hottest_label.clone(),
+            hottest_cpu_celsius: option_f64(sensors.hottest_cpu_celsius),
+            hottest_cpu_label: sensors.hottest_cpu_label.clone(),
+            hottest_gpu_celsius: option_f64(sensors.hottest_gpu_celsius),
+            hottest_gpu_label: sensors.hottest_gpu_label.clone(),
+            fans: sensors
+                .fans
+                .iter()
+                .map(|fan| FanSensor {
+                    label: fan.label.clone(),
+                    chip: fan.chip.clone(),
+                    rpm: fan.rpm,
+                    duty_percent: option_f64(fan.duty_percent),
+                })
+                .collect(),
+            voltages: sensors
+                .voltages
+                .iter()
+                .map(|sensor| (sensor.label.clone(), sensor.volts.into()))
+                .collect(),
+            currents: sensors
+                .currents
+                .iter()
+                .map(|sensor| (sensor.label.clone(), sensor.amps.into()))
+                .collect(),
+            powers: sensors
+                .powers
+                .iter()
+                .map(|sensor| (sensor.label.clone(), sensor.watts.into()))
+                .collect(),
+        }
+    }
+}
+
+impl From<&monitor::SystemInfo> for SystemInfo {
+    fn from(info: &monitor::SystemInfo) -> Self {
+        Self {
+            hostname: info.hostname.clone(),
+            os_name: info.os_name.clone(),
+            os_version: info.os_version.clone(),
+            kernel_version: info.kernel_version.clone(),
+            architecture: info.architecture.clone(),
+            process_count: info.process_count as u32,
+        }
+    }
+}
+
+impl From<&monitor::GpuInfo> for GpuInfo {
+    fn from(gpu: &monitor::GpuInfo) -> Self {
+        Self {
+            name: gpu.name.clone(),
+            vendor: gpu.vendor.as_str().to_string(),
+            usage_percent: option_f64(gpu.usage_percent),
+            vram_used_gb: gpu.vram_used_gb,
+            vram_total_gb: gpu.vram_total_gb,
+            vram_usage_percent: option_f64(gpu.vram_usage_percent),
+            shader_clock_mhz: gpu.shader_clock_mhz,
+            memory_clock_mhz: gpu.memory_clock_mhz,
+            temperature_celsius: option_f64(gpu.temperature_celsius),
+            power_watts: option_f64(gpu.power_watts),
+            fan_rpm: gpu.fan_rpm,
+            fan_duty_percent: option_f64(gpu.fan_duty_percent),
+        }
+    }
+}
+
+impl From<&monitor::NetworkSpeedTestStatus> for NetworkSpeedTestStatus {
+    fn from(status: &monitor::NetworkSpeedTestStatus) -> Self {
+        Self {
+            state: enum_name(&status.state),
+            phase: enum_name(&status.phase),
+            tool: status.tool.clone(),
+            ping_ms: option_f64(status.ping_ms),
+            download_mbps: option_f64(status.download_mbps),
+            upload_mbps: option_f64(status.upload_mbps),
+            server_name: status.server_name.clone(),
+            server_location: status.server_location.clone(),
+            started_at_unix_ms: status.started_at_unix_ms,
+            finished_at_unix_ms: status.finished_at_unix_ms,
+            error: status.error.clone(),
+        }
+    }
+}
+
+impl From<&monitor::PressureResource> for PressureResource {
+    fn from(resource: &monitor::PressureResource) -> Self {
+        Self {
+            some: resource.some.clone(),
+            full: resource.full.clone(),
+        }
+    }
+}
+
+impl From<&monitor::CgroupPressureMetrics> for CgroupPressureMetrics {
+    fn from(cgroup: &monitor::CgroupPressureMetrics) -> Self {
+        Self {
+            path: cgroup.path.clone(),
+            cpu: cgroup.cpu.as_ref().map(PressureResource::from),
+            memory: cgroup.memory.as_ref().map(PressureResource::from),
+            io: cgroup.io.as_ref().map(PressureResource::from),
+        }
+    }
+}
+
+impl From<&monitor::PressureMetrics> for PressureMetrics {
+    fn from(pressure: &monitor::PressureMetrics) -> Self {
+        Self {
+            cpu: pressure.cpu.as_ref().map(PressureResource::from),
+            memory: pressure.memory.as_ref().map(PressureResource::from),
+            io: pressure.io.as_ref().map(PressureResource::from),
+            cgroup: pressure.cgroup.as_ref().map(CgroupPressureMetrics::from),
+        }
+    }
+}
+
+impl From<&monitor::FastMetrics> for FastMetrics {
+    fn from(fast: &monitor::FastMetrics) -> Self {
+        Self {
+            cpu: (&fast.cpu).into(),
+            memory: fast.memory.clone(),
+            disk: fast.disk.clone(),
+            network: (&fast.network).into(),
+            uptime: fast.uptime,
+            load_average: fast.load_average,
+            pressure: (&fast.pressure).into(),
+        }
+    }
+}
+
+impl From<&monitor::SlowMetrics> for SlowMetrics {
+    fn from(slow: &monitor::SlowMetrics) -> Self {
+        Self {
+            sensors: (&slow.sensors).into(),
+            gpus: slow.gpus.iter().map(GpuInfo::from).collect(),
+            top_processes: slow.top_processes.clone(),
+            system_info: (&slow.system_info).into(),
+        }
+    }
+}
+
+impl From<&monitor::HistorySeries> for HistorySeries {
+    fn from(series: &monitor::HistorySeries) -> Self {
+        Self {
+            buffer: series.buffer.clone(),
+            start: series.start as u32,
+            count: series.count as u32,
+            sample_interval_ms: series.sample_interval_ms,
+        }
+    }
+}
+
+/// Converte o histórico em um dicionário `nome da série → série`.
+fn history_series_map(history: &monitor::HistoryMetrics) -> HashMap<String, HistorySeries> {
+    history
+        .series()
+        .into_iter()
+        .map(|(name, series)| (name.to_string(), series.into()))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Propriedades escalares
+// ---------------------------------------------------------------------------
+
+/// Valores escalares expostos como propriedades DBus.
+/// Valores ausentes (ex.: sem sensor de temperatura) são publicados como `NaN`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct KeyScalars {
+    cpu_usage_percent: f64,
+    memory_usage_percent: f64,
+    hottest_temperature_celsius: f64,
+    hottest_cpu_celsius: f64,
+    hottest_gpu_celsius: f64,
+    gateway_latency_ms: f64,
+}
+
+impl Default for KeyScalars {
+    fn default() -> Self {
+        Self {
+            cpu_usage_percent: 0.0,
+            memory_usage_percent: 0.0,
+            hottest_temperature_celsius: f64::NAN,
+            hottest_cpu_celsius: f64::NAN,
+            hottest_gpu_celsius: f64::NAN,
+            gateway_latency_ms: f64::NAN,
+        }
+    }
+}
+
+fn option_or_nan(value: Option<f32>) -> f64 {
+    value.map(f64::from).unwrap_or(f64::NAN)
+}
+
+/// Compara dois escalares tratando `NaN == NaN` para não emitir sinais espúrios.
+fn same_scalar(left: f64, right: f64) -> bool {
+    left == right || (left.is_nan() && right.is_nan())
+}
+
+impl KeyScalars {
+    pub(crate) fn from_monitor(monitor: &SystemMonitor) -> Self {
+        let sensors = monitor.get_sensor_metrics();
+        Self {
+            cpu_usage_percent: monitor.get_cpu_metrics().usage_percent.into(),
+            memory_usage_percent: monitor.get_memory_metrics().usage_percent.into(),
+            hottest_temperature_celsius: option_or_nan(sensors.hottest_temperature_celsius),
+            hottest_cpu_celsius: option_or_nan(sensors.hottest_cpu_celsius),
+            hottest_gpu_celsius: option_or_nan(sensors.hottest_gpu_celsius),
+            gateway_latency_ms: option_or_nan(monitor.cached_gateway_latency_ms),
+        }
+    }
+
+    fn entries(&self) -> [(&'static str, f64); 6] {
+        [
+            ("CpuUsagePercent", self.cpu_usage_percent),
+            ("MemoryUsagePercent", self.memory_usage_percent),
+            (
+                "HottestTemperatureCelsius",
+                self.hottest_temperature_celsius,
+            ),
+            ("HottestCpuCelsius", self.hottest_cpu_celsius),
+            ("HottestGpuCelsius", self.hottest_gpu_celsius),
+            ("GatewayLatencyMs", self.gateway_latency_ms),
+        ]
+    }
+}
+
+/// Atualiza os escalares publicados e emite `PropertiesChanged` apenas com
+/// as propriedades que mudaram desde o ciclo anterior.
+pub(crate) async fn publish_key_scalars(
+    cache: &RwLock<KeyScalars>,
+    current: KeyScalars,
+    signal_context: &SignalContext<'_>,
+) -> zbus::Result<()> {
+    let previous = std::mem::replace(&mut *cache.write().await, current);
+
+    let values: Vec<(&str, Value<'_>)> = previous
+        .entries()
+        .into_iter()
+        .zip(current.entries())
+        .filter(|((_, old), (_, new))| !same_scalar(*old, *new))
+        .map(|(_, (name, new))| (name, Value::from(new)))
+        .collect();
+    if values.is_empty() {
+        return Ok(());
+    }
+
+    let changed: HashMap<&str, &Value<'_>> =
+        values.iter().map(|(name, value)| (*name, value)).collect();
+    Properties::properties_changed(
+        signal_context,
+        InterfaceName::from_static_str_unchecked(DBUS_TYPED_INTERFACE_NAME),
+        &changed,
+        &[],
+    )
+    .await
+}
+
+// ---------------------------------------------------------------------------
+// Interface
+// ---------------------------------------------------------------------------
+
+pub struct TypedMetricsBackend {
+    pub(crate) monitor: Arc<Mutex<SystemMonitor>>,
+    pub(crate) key_scalars: Arc<RwLock<KeyScalars>>,
+    pub(crate) speed_test: NetworkSpeedTestManager,
+}
+
+#[interface(name = "com.monitortray.Backend2")]
+impl TypedMetricsBackend {
+    async fn get_cpu_metrics(&self) -> CpuMetrics {
+        (&self.monitor.lock().await.get_cpu_metrics()).into()
+    }
+
+    async fn get_memory_metrics(&self) -> MemoryMetrics {
+        self.monitor.lock().await.get_memory_metrics()
+    }
+
+    async fn get_disk_metrics(&self) -> DiskMetrics {
+        self.monitor.lock().await.get_disk_metrics()
+    }
+
+    async fn get_network_metrics(&self) -> NetworkMetrics {
+        (&self.monitor.lock().await.get_network_metrics()).into()
+    }
+
+    async fn get_sensor_metrics(&self) -> SensorMetrics {
+        (&self.monitor.lock().await.get_sensor_metrics()).into()
+    }
+
+    async fn get_gpus(&self) -> Vec<GpuInfo> {
+        let monitor = self.monitor.lock().await;
+        monitor.cached_gpus.iter().map(GpuInfo::from).collect()
+    }
+
+    async fn get_top_processes(&self) -> Vec<ProcessInfo> {
+        let monitor = self.monitor.lock().await;
+        monitor.get_top_processes().to_vec()
+    }
+
+    async fn get_system_info(&self) -> SystemInfo {
+        (&self.monitor.lock().await.get_slow_metrics().system_info).into()
+    }
+
+    async fn get_fast_metrics(&self) -> FastMetrics {
+        (&self.monitor.lock().await.get_fast_metrics()).into()
+    }
+
+    async fn get_slow_metrics(&self) -> SlowMetrics {
+        (&self.monitor.lock().await.get_slow_metrics()).into()
+    }
+
+    async fn get_history(&self) -> HashMap<String, HistorySeries> {
+        history_series_map(&self.monitor.lock().await.get_history_metrics())
+    }
+
+    async fn start_network_speed_test(&self) -> bool {
+        self.speed_test.start().await
+    }
+
+    async fn cancel_network_speed_test(&self) -> bool {
+        self.speed_test.cancel().await
+    }
+
+    async fn get_network_speed_test_status(&self) -> NetworkSpeedTestStatus {
+        (&self.speed_test.get_status().await).into()
+    }
+
+    #[zbus(property)]
+    async fn cpu_usage_percent(&self) -> f64 {
+        self.key_scalars.read().await.cpu_usage_percent
+    }
+
+    #[zbus(property)]
+    async fn memory_usage_percent(&self) -> f64 {
+        self.key_scalars.read().await.memory_usage_percent
+    }
+
+    #[zbus(property)]
+    async fn hottest_temperature_celsius(&self) -> f64 {
+        self.key_scalars.read().await.hottest_temperature_celsius
+    }
+
+    #[zbus(property)]
+    async fn hottest_cpu_celsius(&self) -> f64 {
+        self.key_scalars.read().await.hottest_cpu_celsius
+    }
+
+    #[zbus(property)]
+    async fn hottest_gpu_celsius(&self) -> f64 {
+        self.key_scalars.read().await.hottest_gpu_celsius
+    }
+
+    #[zbus(property)]
+    async fn gateway_latency_ms(&self) -> f64 {
+        self.key_scalars.read().await.gateway_latency_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zbus::zvariant::{serialized::Context, to_bytes, OwnedValue, LE};
+
+    #[test]
+    fn test_gpu_info_omits_missing_fields_from_dict() {
+        let gpu = monitor::GpuInfo {
+            name: "Intel GPU (card0)".to_string(),
+            vendor: monitor::GpuVendor::Intel,
+            usage_percent: Some(12.5),
+            vram_used_gb: None,
+            vram_total_gb: None,
+            vram_usage_percent: None,
+            shader_clock_mhz: Some(1300),
+            memory_clock_mhz: None,
+            temperature_celsius: None,
+            power_watts: None,
+            fan_rpm: None,
+            fan_duty_percent: None,
+        };
+
+        let typed = GpuInfo::from(&gpu);
+        let encoded = to_bytes(Context::new_dbus(LE, 0), &typed).expect("gpu should encode");
+        let (dict, _): (HashMap<String, OwnedValue>, _) =
+            encoded.deserialize().expect("gpu should decode as a dict");
+
+        assert_eq!(GpuInfo::signature(), "a{sv}");
+        assert_eq!(typed.vendor, "intel");
+        assert!(dict.contains_key("usage_percent"));
+        assert!(dict.contains_key("shader_clock_mhz"));
+        assert!(!dict.contains_key("vram_total_gb"));
+    }
+
+    #[test]
+    fn test_cpu_metrics_is_a_plain_struct() {
+        assert_eq!(
+            CpuMetrics::signature(),
+            "(dddddddddduadtsaa{sv}a(suudt)a{sv}(bautt))"
+        );
+    }
+
+    #[test]
+    fn test_fast_metrics_carries_pressure_dict() {
+        let stall = monitor::PressureStall {
+            avg10: 1.5,
+            avg60: 1.0,
+            avg300: 0.5,
+            total_us: 1_000,
+            stall_percent: 2.0,
+        };
+        let pressure = monitor::PressureMetrics {
+            cpu: Some(monitor::PressureResource {
+                some: stall.clone(),
+                full: None,
+            }),
+            memory: None,
+            io: Some(monitor::PressureResource {
+                some: stall.clone(),
+                full: Some(stall),
+            }),
+            cgroup: None,
+        };
+
+        let typed = PressureMetrics::from(&pressure);
+        let encoded = to_bytes(Context::new_dbus(LE, 0), &typed).expect("pressure should encode");
+        let (dict, _): (HashMap<String, OwnedValue>, _) = encoded
+            .deserialize()
+            .expect("pressure should decode as a dict");
+
+        assert_eq!(PressureStall::signature(), "(dddtd)");
+        assert_eq!(PressureResource::signature(), "a{sv}");
+        assert_eq!(CgroupPressureMetrics::signature(), "a{sv}");
+        assert!(FastMetrics::signature().ends_with("t(ddd)a{sv})"));
+        assert!(dict.contains_key("cpu"));
+        assert!(dict.contains_key("io"));
+        assert!(!dict.contains_key("memory"));
+        assert!(!dict.contains_key("cgroup"));
+        assert!(typed.cpu.is_some_and(|cpu| cpu.full.is_none()));
+    }
+
+    /// Campos do JSON do modelo sem chave correspondente no tipo DBus. Valores
+    /// dentro de `a{sv}` são desembrulhados; coleções com formas diferentes
+    /// (lista de sensores → dicionário rótulo → valor) não são comparadas.
+    fn missing_typed_fields(
+        model: &serde_json::Value,
+        typed: &serde_json::Value,
+        path: &str,
+        missing: &mut Vec<String>,
+    ) {
+        let typed = typed.get("zvariant::Value::Value").unwrap_or(typed);
+        match (model, typed) {
+            (serde_json::Value::Object(model), serde_json::Value::Object(typed)) => {
+                for (key, value) in model {
+                    let field = format!("{path}.{key}");
+                    match typed.get(key) {
+                        Some(typed_value) => {
+                            missing_typed_fields(value, typed_value, &field, missing)
+                        }
+                        None => missing.push(field),
+                    }
+                }
+            }
+            (serde_json::Value::Array(model), serde_json::Value::Array(typed)) => {
+                if let (Some(model), Some(typed)) = (model.first(), typed.first()) {
+                    missing_typed_fields(model, typed, &format!("{path}[0]"), missing);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn assert_typed_covers<M: Serialize, T: Serialize>(name: &str, model: &M, typed: &T) {
+        let model = serde_json::to_value(model).expect("model should serialize");
+        let typed = serde_json::to_value(typed).expect("typed value should serialize");
+        let mut missing = Vec::new();
+        missing_typed_fields(&model, &typed, name, &mut missing);
+        assert!(
+            missing.is_empty(),
+            "campos sem equivalente tipado (ou None na amostra): {missing:?}"
+        );
+    }
+
+    fn full_pressure_resource() -> monitor::PressureResource {
+        let stall = monitor::PressureStall {
+            avg10: 1.0,
+            avg60: 2.0,
+            avg300: 3.0,
+            total_us: 4,
+            stall_percent: 5.0,
+        };
+        monitor::PressureResource {
+            some: stall.clone(),
+            full: Some(stall),
+        }
+    }
+
+    #[test]
+    fn test_typed_structs_cover_every_model_field() {
+        // Todos os `Option` da amostra são `Some`: campos ausentes somem do `a{sv}`.
+        let monitor = SystemMonitor::new_empty();
+        let mut fast = monitor.get_fast_metrics();
+        fast.cpu.cores = vec![monitor::CpuCoreMetrics {
+            core_type: Some(monitor::CpuCoreType::Performance),
+            core_id: Some(0),
+            package_id: Some(0),
+            cluster_id: Some(0),
+            smt_siblings: vec![1],
+            core_throttle_count: Some(1),
+            package_throttle_count: Some(1),
+            ..Default::default()
+        }];
+        fast.cpu.core_classes = vec![monitor::CpuCoreClassMetrics {
+            core_type: monitor::CpuCoreType::Performance,
+            cpu_count: 2,
+            physical_core_count: 1,
+            usage_percent: 10.0,
+            frequency_mhz: 3000,
+        }];
+        fast.cpu.frequency_policy = monitor::CpuFrequencyPolicy {
+            governor: Some("powersave".to_string()),
+            energy_performance_preference: Some("balance_performance".to_string()),
+            scaling_min_mhz: Some(400),
+            scaling_max_mhz: Some(4800),
+            boost_enabled: Some(true),
+        };
+        fast.network.gateway_ip = Some("192.168.0.1".to_string());
+        fast.network.gateway_latency_ms = Some(1.5);
+        fast.network.latency_target = Some("1.1.1.1".to_string());
+        fast.pressure = monitor::PressureMetrics {
+            cpu: Some(full_pressure_resource()),
+            memory: Some(full_pressure_resource()),
+            io: Some(full_pressure_resource()),
+            cgroup: Some(monitor::CgroupPressureMetrics {
+                path: "user.slice".to_string(),
+                cpu: Some(full_pressure_resource()),
+                memory: Some(full_pressure_resource()),
+                io: Some(full_pressure_resource()),
+            }),
+        };
+
+        let mut slow = monitor.get_slow_metrics();
+        slow.sensors.temperatures = vec![monitor::TemperatureSensor {
+            label: "Package id 0".to_string(),
+            chip: "coretemp".to_string(),
+            temperature_celsius: 50.0,
+            max_celsius: Some(90.0),
+            critical_celsius: Some(100.0),
+        }];
+        slow.sensors.average_temperature_celsius = Some(50.0);
+        slow.sensors.hottest_temperature_celsius = Some(50.0);
+        slow.sensors.hottest_cpu_celsius = Some(50.0);
+        slow.sensors.hottest_gpu_celsius = Some(50.0);
+        slow.sensors.fans = vec![monitor::FanSensor {
+            label: "nct6775: fan1".to_string(),
+            chip: "nct6775".to_string(),
+            rpm: 1200,
+            duty_percent: Some(40.0),
+        }];
+        slow.gpus = vec![monitor::GpuInfo {
+            name: "Radeon".to_string(),
+            vendor: monitor::GpuVendor::Amd,
+            usage_percent: Some(1.0),
+            vram_used_gb: Some(1.0),
+            vram_total_gb: Some(8.0),
+            vram_usage_percent: Some(12.5),
+            shader_clock_mhz: Some(2000),
+            memory_clock_mhz: Some(1000),
+            temperature_celsius: Some(45.0),
+            power_watts: Some(30.0),
+            fan_rpm: Some(900),
+            fan_duty_percent: Some(30.0),
+        }];
+        slow.top_processes = vec![monitor::ProcessInfo {
+            pid: 1,
+            name: "init".to_string(),
+            cpu_percent: 0.5,
+            memory_mb: 12.0,
+        }];
+
+        let speed_test = monitor::NetworkSpeedTestStatus {
+            tool: Some("speedtest".to_string()),
+            ping_ms: Some(10.0),
+            download_mbps: Some(100.0),
+            upload_mbps: Some(50.0),
+            server_name: Some("server".to_string()),
+            server_location: Some("city".to_string()),
+            started_at_unix_ms: Some(1),
+            finished_at_unix_ms: Some(2),
+            error: Some("erro".to_string()),
+            ..Default::default()
+        };
+        let history = monitor.get_history_metrics();
+
+        assert_typed_covers("FastMetrics", &fast, &FastMetrics::from(&fast));
+        assert_typed_covers("SlowMetrics", &slow, &SlowMetrics::from(&slow));
+        assert_typed_covers(
+            "NetworkSpeedTestStatus",
+            &speed_test,
+            &NetworkSpeedTestStatus::from(&speed_test),
+        );
+        assert_typed_covers(
+            "HistorySeries",
+            &history.cpu_usage,
+            &HistorySeries::from(&history.cpu_usage),
+        );
+    }
+
+    #[test]
+    fn test_same_scalar_treats_nan_as_unchanged() {
+        assert!(same_scalar(f64::NAN, f64::NAN));
+        assert!(same_scalar(42.0, 42.0));
+        assert!(!same_scalar(42.0, f64::NAN));
+    }
+}
@@ -0,0 +1,9 @@
+//! Exportação das métricas para sistemas de monitoramento externos.
+
+pub mod mqtt;
+pub mod prometheus;
+pub mod push;
+pub mod textfile;
+
+/// Modelos guardam memória, disco e VRAM em GiB; os exportadores publicam bytes.
+const BYTES_PER_GIB: f64 = 1024.0 * 1024.0 * 1024.0;
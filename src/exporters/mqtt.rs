@@ -0,0 +1,587 @@
+//! Publicação MQTT para o Home Assistant: um tópico de estado JSON por host, payloads de
+//! MQTT discovery para cada sensor e um tópico de disponibilidade com last will.
+
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::str::FromStr;
+use std::time::Duration;
+
+use rumqttc::{
+    AsyncClient, ConnectionError, Event, EventLoop, LastWill, MqttOptions, Outgoing, Packet, QoS,
+};
+use serde_json::{json, Map, Value};
+use sysinfo::System;
+use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
+
+use crate::monitor::{SlowSubsystems, SystemMonitor};
+
+pub const DEFAULT_TOPIC_PREFIX: &str = "monitor-tray";
+pub const DEFAULT_DISCOVERY_PREFIX: &str = "homeassistant";
+pub const PASSWORD_ENV: &str = "MONITOR_TRAY_MQTT_PASSWORD";
+
+const DEFAULT_PORT: u16 = 1883;
+const ONLINE: &str = "online";
+const OFFLINE: &str = "offline";
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+/// Mensagens aguardando o event loop; com o broker fora, as excedentes são descartadas.
+const CLIENT_CAPACITY: usize = 64;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Grupos de campos publicados.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MqttGroup {
+    Cpu,
+    Memory,
+    Sensors,
+    Gpu,
+}
+
+impl MqttGroup {
+    pub const ALL: [MqttGroup; 4] = [
+        MqttGroup::Cpu,
+        MqttGroup::Memory,
+        MqttGroup::Sensors,
+        MqttGroup::Gpu,
+    ];
+}
+
+/// Broker no formato `mqtt://host[:porta]` ou `host[:porta]`; a porta padrão é 1883.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttBroker {
+    pub host: String,
+    pub port: u16,
+}
+
+impl FromStr for MqttBroker {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let address = value.strip_prefix("mqtt://").unwrap_or(value);
+        let (host, port) = match address.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .map_err(|_| format!("porta inválida em {value}"))?,
+            ),
+            None => (address, DEFAULT_PORT),
+        };
+        if host.is_empty() || address.contains("://") {
+            return Err(format!("broker inválido: {value} (use mqtt://host:porta)"));
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
+pub struct MqttSettings {
+    pub broker: MqttBroker,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub topic_prefix: String,
+    pub discovery_prefix: String,
+    pub groups: Vec<MqttGroup>,
+}
+
+/// Um sensor do Home Assistant: chave no JSON de estado e metadados do discovery.
+#[derive(Debug, Clone, PartialEq)]
+struct Entity {
+    key: String,
+    name: String,
+    value: f64,
+    unit: &'static str,
+    device_class: Option<&'static str>,
+    icon: Option<&'static str>,
+}
+
+impl Entity {
+    fn new(key: String, name: String, value: f64, unit: &'static str) -> Self {
+        Self {
+            key,
+            name,
+            value,
+            unit,
+            device_class: None,
+            icon: None,
+        }
+    }
+
+    fn device_class(mut self, device_class: &'static str) -> Self {
+        self.device_class = Some(device_class);
+        self
+    }
+
+    fn icon(mut self, icon: &'static str) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    fn temperature(key: String, name: String, celsius: f32) -> Self {
+        Self::new(key, name, celsius as f64, "°C").device_class("temperature")
+    }
+}
+
+/// Reduz a `[a-z0-9_]`, formato aceito em `object_id`, `node_id` e nos tópicos.
+fn slug(value: &str) -> String {
+    let mut slug = String::with_capacity(value.len());
+    for character in value.chars().flat_map(char::to_lowercase) {
+        if character.is_ascii_alphanumeric() {
+            slug.push(character);
+        } else if !slug.is_empty() && !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    while slug.ends_with('_') {
+        slug.pop();
+    }
+    slug
+}
+
+/// Monta os sensores dos grupos pedidos. Chaves repetidas (dois sensores com o mesmo
+/// chip e rótulo, por exemplo) mantêm só a primeira ocorrência.
+fn entities(monitor: &SystemMonitor, groups: &[MqttGroup]) -> Vec<Entity> {
+    let mut entities = Vec::new();
+    if groups.contains(&MqttGroup::Cpu) {
+        let cpu = monitor.get_cpu_metrics();
+        entities.push(
+            Entity::new(
+                "cpu_usage".to_string(),
+                "CPU".to_string(),
+                cpu.usage_percent as f64,
+                "%",
+            )
+            .icon("mdi:cpu-64-bit"),
+        );
+        entities.push(
+            Entity::new(
+                "cpu_frequency".to_string(),
+                "Frequência da CPU".to_string(),
+                cpu.frequency as f64,
+                "MHz",
+            )
+            .device_class("frequency"),
+        );
+    }
+    if groups.contains(&MqttGroup::Memory) {
+        let memory = monitor.get_memory_metrics();
+        entities.push(
+            Entity::new(
+                "memory_usage".to_string(),
+                "Memória".to_string(),
+                memory.usage_percent as f64,
+                "%",
+            )
+            .icon("mdi:memory"),
+        );
+        entities.push(
+            Entity::new(
+                "memory_used".to_string(),
+                "Memória em uso".to_string(),
+                memory.used_memory,
+                "GiB",
+            )
+            .device_class("data_size"),
+        );
+        entities.push(
+            Entity::new(
+                "swap_used".to_string(),
+                "Swap em uso".to_string(),
+                memory.used_swap,
+                "GiB",
+            )
+            .device_class("data_size"),
+        );
+    }
+    if groups.contains(&MqttGroup::Sensors) {
+        let sensors = monitor.get_sensor_metrics();
+        for sensor in &sensors.temperatures {
+            entities.push(Entity::temperature(
+                format!(
+                    "temperature_{}",
+                    slug(&format!("{}_{}", sensor.chip, sensor.label))
+                ),
+                format!("{} {}", sensor.chip, sensor.label),
+                sensor.temperature_celsius,
+            ));
+        }
+        for (key, name, value) in [
+            (
+                "temperature_hottest_cpu",
+                "Temperatura da CPU",
+                sensors.hottest_cpu_celsius,
+            ),
+            (
+                "temperature_hottest_gpu",
+                "Temperatura da GPU",
+                sensors.hottest_gpu_celsius,
+            ),
+        ] {
+            if let Some(celsius) = value {
+                entities.push(Entity::temperature(
+                    key.to_string(),
+                    name.to_string(),
+                    celsius,
+                ));
+            }
+        }
+        for fan in &sensors.fans {
+            entities.push(
+                Entity::new(
+                    format!("fan_{}", slug(&fan.label)),
+                    format!("Ventoinha {}", fan.label),
+                    fan.rpm as f64,
+                    "RPM",
+                )
+                .icon("mdi:fan"),
+            );
+        }
+    }
+    if groups.contains(&MqttGroup::Gpu) {
+        for (index, gpu) in monitor.get_gpus().iter().enumerate() {
+            let key = |field: &str| format!("gpu{index}_{field}");
+            let name = |field: &str| format!("{} {field}", gpu.name);
+            if let Some(usage) = gpu.usage_percent {
+                entities.push(
+                    Entity::new(key("usage"), name("uso"), usage as f64, "%")
+                        .icon("mdi:expansion-card"),
+                );
+            }
+            if let Some(usage) = gpu.vram_usage_percent {
+                entities.push(
+                    Entity::new(key("vram_usage"), name("VRAM"), usage as f64, "%")
+                        .icon("mdi:memory"),
+                );
+            }
+            if let Some(celsius) = gpu.temperature_celsius {
+                entities.push(Entity::temperature(
+                    key("temperature"),
+                    name("temperatura"),
+                    celsius,
+                ));
+            }
+            if let Some(watts) = gpu.power_watts {
+                entities.push(
+                    Entity::new(key("power"), name("consumo"), watts as f64, "W")
+                        .device_class("power"),
+                );
+            }
+        }
+    }
+
+    let mut seen = BTreeSet::new();
+    entities.retain(|entity| seen.insert(entity.key.clone()));
+    entities
+}
+
+/// Tópicos de um host: `<prefixo>/<node_id>/state` e `<prefixo>/<node_id>/availability`.
+struct Topics {
+    node_id: String,
+    host: String,
+    state: String,
+    availability: String,
+    discovery_prefix: String,
+}
+
+impl Topics {
+    fn new(topic_prefix: &str, discovery_prefix: &str, host: &str) -> Self {
+        let node_id = match slug(host) {
+            node_id if node_id.is_empty() => "unknown".to_string(),
+            node_id => node_id,
+        };
+        let base = format!("{}/{node_id}", topic_prefix.trim_end_matches('/'));
+        Self {
+            state: format!("{base}/state"),
+            availability: format!("{base}/availability"),
+            discovery_prefix: discovery_prefix.trim_end_matches('/').to_string(),
+            host: host.to_string(),
+            node_id,
+        }
+    }
+
+    fn discovery(&self, entity: &Entity) -> String {
+        format!(
+            "{}/sensor/{}/{}/config",
+            self.discovery_prefix, self.node_id, entity.key
+        )
+    }
+}
+
+/// Valores arredondados a duas casas; o Home Assistant não precisa do ruído do `f32`.
+fn state_payload(entities: &[Entity]) -> String {
+    let state: Map<String, Value> = entities
+        .iter()
+        .filter(|entity| entity.value.is_finite())
+        .map(|entity| {
+            let rounded = (entity.value * 100.0).round() / 100.0;
+            (entity.key.clone(), json!(rounded))
+        })
+        .collect();
+    Value::Object(state).to_string()
+}
+
+fn discovery_payload(topics: &Topics, entity: &Entity) -> String {
+    let mut config = json!({
+        "name": entity.name,
+        "unique_id": format!("{}_{}", topics.node_id, entity.key),
+        "object_id": format!("{}_{}", topics.node_id, entity.key),
+        "state_topic": topics.state,
+        "value_template": format!("{{{{ value_json.{} }}}}", entity.key),
+        "unit_of_measurement": entity.unit,
+        "state_class": "measurement",
+        "availability_topic": topics.availability,
+        "device": {
+            "identifiers": [format!("monitor_tray_{}", topics.node_id)],
+            "name": topics.host,
+            "model": "monitor-tray",
+            "sw_version": env!("CARGO_PKG_VERSION"),
+        },
+    });
+    if let Some(device_class) = entity.device_class {
+        config["device_class"] = json!(device_class);
+    }
+    if let Some(icon) = entity.icon {
+        config["icon"] = json!(icon);
+    }
+    config.to_string()
+}
+
+/// Mantém a conexão com o broker. O rumqttc reconecta no próximo `poll` após um erro;
+/// aqui só se espera um backoff exponencial entre as tentativas. Cada `ConnAck` é
+/// repassado para que disponibilidade e discovery sejam republicados.
+async fn drive_event_loop(mut event_loop: EventLoop, connected: mpsc::UnboundedSender<()>) {
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+        match event_loop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                backoff = INITIAL_BACKOFF;
+                if connected.send(()).is_err() {
+                    return;
+                }
+            }
+            Ok(Event::Outgoing(Outgoing::Disconnect)) | Err(ConnectionError::RequestsDone) => {
+                return
+            }
+            Ok(_) => {}
+            Err(error) => {
+                eprintln!(
+                    "monitor-tray: falha na conexão MQTT: {error}; nova tentativa em {}s",
+                    backoff.as_secs()
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+/// Envia o discovery das entidades ainda não anunciadas. Com a fila cheia a mensagem é
+/// descartada, então só conta como anunciado o que `publish` aceitou; o resto é tentado
+/// de novo no próximo ciclo.
+fn announce(
+    entities: &[Entity],
+    announced: &mut BTreeSet<String>,
+    mut publish: impl FnMut(&Entity) -> bool,
+) {
+    for entity in entities {
+        if !announced.contains(&entity.key) && publish(entity) {
+            announced.insert(entity.key.clone());
+        }
+    }
+}
+
+/// Publica sem bloquear: com o broker fora e a fila cheia, a mensagem é descartada e o
+/// próximo ciclo traz valores novos. Retorna se a mensagem entrou na fila.
+fn publish(client: &AsyncClient, topic: &str, retain: bool, payload: String) -> bool {
+    client
+        .try_publish(topic, QoS::AtLeastOnce, retain, payload)
+        .is_ok()
+}
+
+/// Publica o estado a cada `interval` até `Ctrl+C`; ao sair, marca o host como `offline`.
+pub async fn run_mqtt(
+    mut monitor: SystemMonitor,
+    settings: MqttSettings,
+    interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    let host = System::host_name().unwrap_or_else(|| "unknown".to_string());
+    let topics = Topics::new(&settings.topic_prefix, &settings.discovery_prefix, &host);
+
+    let mut options = MqttOptions::new(
+        format!("monitor-tray-{}", topics.node_id),
+        settings.broker.host.clone(),
+        settings.broker.port,
+    );
+    options.set_keep_alive(KEEP_ALIVE);
+    options.set_last_will(LastWill::new(
+        &topics.availability,
+        OFFLINE,
+        QoS::AtLeastOnce,
+        true,
+    ));
+    if let Some(username) = settings.username {
+        options.set_credentials(username, settings.password.unwrap_or_default());
+    }
+    let (client, event_loop) = AsyncClient::new(options, CLIENT_CAPACITY);
+    let (connected_sender, mut connected) = mpsc::unbounded_channel();
+    let events = tokio::spawn(drive_event_loop(event_loop, connected_sender));
+
+    let subsystems = SlowSubsystems {
+        sensors: settings.groups.contains(&MqttGroup::Sensors),
+        gpus: settings.groups.contains(&MqttGroup::Gpu),
+        processes: false,
+    };
+    eprintln!(
+        "monitor-tray: publicando em mqtt://{}:{} ({})",
+        settings.broker.host, settings.broker.port, topics.state
+    );
+
+    let mut announced = BTreeSet::new();
+    let mut reconnecting = false;
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            Some(()) = connected.recv() => {
+                publish(&client, &topics.availability, true, ONLINE.to_string());
+                // Numa reconexão o discovery pode ter se perdido junto com a fila; repete tudo.
+                if reconnecting {
+                    announced.clear();
+                }
+                reconnecting = true;
+                continue;
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+
+        monitor.update_fast_metrics().await;
+        if subsystems != SlowSubsystems::NONE {
+            monitor.refresh_slow_subsystems(false, subsystems).await;
+        }
+        let entities = entities(&monitor, &settings.groups);
+        announce(&entities, &mut announced, |entity| {
+            publish(
+                &client,
+                &topics.discovery(entity),
+                true,
+                discovery_payload(&topics, entity),
+            )
+        });
+        publish(&client, &topics.state, false, state_payload(&entities));
+    }
+
+    publish(&client, &topics.availability, true, OFFLINE.to_string());
+    let _ = client.disconnect().await;
+    let _ = tokio::time::timeout(SHUTDOWN_TIMEOUT, events).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_broker_accepts_scheme_and_default_port() {
+        assert_eq!(
+            "mqtt://broker.lan".parse(),
+            Ok(MqttBroker {
+                host: "broker.lan".to_string(),
+                port: 1883
+            })
+        );
+        assert_eq!(
+            "127.0.0.1:1884".parse(),
+            Ok(MqttBroker {
+                host: "127.0.0.1".to_string(),
+                port: 1884
+            })
+        );
+        assert!("tcp://broker.lan".parse::<MqttBroker>().is_err());
+        assert!("broker.lan:porta".parse::<MqttBroker>().is_err());
+    }
+
+    #[test]
+    fn test_announce_retries_discovery_dropped_by_full_queue() {
+        let entities: Vec<Entity> = ["cpu", "memory", "swap"]
+            .into_iter()
+            .map(|key| Entity::new(key.to_string(), key.to_string(), 0.0, "%"))
+            .collect();
+        let mut announced = BTreeSet::new();
+
+        // Fila com espaço para só uma mensagem no primeiro ciclo.
+        let mut slots = 1;
+        announce(&entities, &mut announced, |_| {
+            let accepted = slots > 0;
+            slots -= i32::from(accepted);
+            accepted
+        });
+        assert_eq!(announced.len(), 1);
+
+        let mut sent = Vec::new();
+        announce(&entities, &mut announced, |entity| {
+            sent.push(entity.key.clone());
+            true
+        });
+        assert_eq!(sent, vec!["memory", "swap"]);
+        assert_eq!(announced.len(), 3);
+    }
+
+    #[test]
+    fn test_discovery_payload_points_to_host_topics() {
+        let topics = Topics::new("monitor-tray/", "homeassistant", "Lab-PC.local");
+        let entity = Entity::temperature(
+            format!("temperature_{}", slug("k10temp Tctl")),
+            "k10temp Tctl".to_string(),
+            61.25,
+        );
+
+        assert_eq!(topics.state, "monitor-tray/lab_pc_local/state");
+        assert_eq!(
+            topics.discovery(&entity),
+            "homeassistant/sensor/lab_pc_local/temperature_k10temp_tctl/config"
+        );
+        let config: Value = serde_json::from_str(&discovery_payload(&topics, &entity)).unwrap();
+        assert_eq!(config["unique_id"], "lab_pc_local_temperature_k10temp_tctl");
+        assert_eq!(config["device_class"], "temperature");
+        assert_eq!(config["unit_of_measurement"], "°C");
+        assert_eq!(
+            config["value_template"],
+            "{{ value_json.temperature_k10temp_tctl }}"
+        );
+        assert_eq!(
+            config["availability_topic"],
+            "monitor-tray/lab_pc_local/availability"
+        );
+        assert_eq!(config["device"]["name"], "Lab-PC.local");
+        assert_eq!(
+            state_payload(&[entity]),
+            r#"{"temperature_k10temp_tctl":61.25}"#
+        );
+    }
+
+    #[test]
+    fn test_entities_follow_selected_groups() {
+        let monitor = SystemMonitor::new_empty();
+
+        let keys: Vec<String> = entities(&monitor, &[MqttGroup::Memory, MqttGroup::Cpu])
+            .into_iter()
+            .map(|entity| entity.key)
+            .collect();
+
+        assert_eq!(
+            keys,
+            [
+                "cpu_usage",
+                "cpu_frequency",
+                "memory_usage",
+                "memory_used",
+                "swap_used"
+            ]
+        );
+        assert!(entities(&monitor, &[MqttGroup::Gpu]).is_empty());
+    }
+}
@@ -0,0 +1,1003 @@
+//! Exportador Prometheus: converte `SystemMetrics` no formato texto de exposição
+//! (`text/plain; version=0.0.4`) e o serve em `/metrics` num socket TCP de loopback
+//! ou num socket Unix.
+
+use std::error::Error;
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, UnixListener};
+use tokio::sync::RwLock;
+use tokio::time::MissedTickBehavior;
+
+use super::BYTES_PER_GIB;
+use crate::collect_metrics;
+use crate::monitor::{
+    CpuMetrics, DiskMetrics, GpuInfo, MemoryMetrics, NetworkMetrics, NetworkSpeedTestState,
+    NetworkSpeedTestStatus, PressureMetrics, PressureResource, ProcessInfo, SensorMetrics,
+    SystemMetrics, SystemMonitor,
+};
+
+pub const DEFAULT_LISTEN_ADDRESS: &str = "127.0.0.1:9882";
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+const METRIC_PREFIX: &str = "monitor_tray";
+const BYTES_PER_MIB: f64 = 1024.0 * 1024.0;
+/// Limite do cabeçalho da requisição; scrapers enviam bem menos que isso.
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MetricKind {
+    Gauge,
+    Counter,
+}
+
+impl MetricKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            MetricKind::Gauge => "gauge",
+            MetricKind::Counter => "counter",
+        }
+    }
+}
+
+type Labels = Vec<(&'static str, String)>;
+
+struct MetricFamily {
+    name: String,
+    help: &'static str,
+    kind: MetricKind,
+    samples: Vec<(Labels, f64)>,
+}
+
+/// Conjunto de famílias na ordem em que foram declaradas.
+#[derive(Default)]
+pub struct Exposition {
+    families: Vec<MetricFamily>,
+}
+
+impl Exposition {
+    fn family(&mut self, name: &str, help: &'static str, kind: MetricKind) -> &mut MetricFamily {
+        let name = format!("{METRIC_PREFIX}_{name}");
+        let index = match self.families.iter().position(|family| family.name == name) {
+            Some(index) => index,
+            None => {
+                self.families.push(MetricFamily {
+                    name,
+                    help,
+                    kind,
+                    samples: Vec::new(),
+                });
+                self.families.len() - 1
+            }
+        };
+        &mut self.families[index]
+    }
+
+    fn gauge(&mut self, name: &str, help: &'static str, labels: Labels, value: f64) {
+        self.family(name, help, MetricKind::Gauge)
+            .push(labels, value);
+    }
+
+    fn counter(&mut self, name: &str, help: &'static str, labels: Labels, value: f64) {
+        self.family(name, help, MetricKind::Counter)
+            .push(labels, value);
+    }
+
+    /// Como [`Exposition::gauge`], omitindo a amostra quando o valor não está disponível.
+    fn optional_gauge(
+        &mut self,
+        name: &str,
+        help: &'static str,
+        labels: Labels,
+        value: Option<f64>,
+    ) {
+        if let Some(value) = value {
+            self.gauge(name, help, labels, value);
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for family in self
+            .families
+            .iter()
+            .filter(|family| !family.samples.is_empty())
+        {
+            let _ = writeln!(out, "# HELP {} {}", family.name, family.help);
+            let _ = writeln!(out, "# TYPE {} {}", family.name, family.kind.as_str());
+            for (labels, value) in &family.samples {
+                out.push_str(&family.name);
+                if !labels.is_empty() {
+                    let labels: Vec<String> = labels
+                        .iter()
+                        .map(|(name, value)| format!("{name}=\"{}\"", escape_label(value)))
+                        .collect();
+                    let _ = write!(out, "{{{}}}", labels.join(","));
+                }
+                let _ = writeln!(out, " {}", format_value(*value));
+            }
+        }
+        out
+    }
+}
+
+impl MetricFamily {
+    /// Séries repetidas (por exemplo dois `fan1` de chips diferentes) invalidam o scrape
+    /// inteiro; mantém só a primeira.
+    fn push(&mut self, labels: Labels, value: f64) {
+        if !self.samples.iter().any(|(existing, _)| *existing == labels) {
+            self.samples.push((labels, value));
+        }
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn encode_cpu(exposition: &mut Exposition, cpu: &CpuMetrics) {
+    exposition.gauge(
+        "cpu_usage_percent",
+        "Uso total de CPU.",
+        vec![],
+        cpu.usage_percent as f64,
+    );
+    for (mode, value) in [
+        ("user", cpu.user_percent),
+        ("nice", cpu.nice_percent),
+        ("system", cpu.system_percent),
+        ("idle", cpu.idle_percent),
+        ("iowait", cpu.iowait_percent),
+        ("irq", cpu.irq_percent),
+        ("softirq", cpu.softirq_percent),
+        ("steal", cpu.steal_percent),
+        ("guest", cpu.guest_percent),
+    ] {
+        exposition.gauge(
+            "cpu_mode_percent",
+            "Tempo de CPU por modo.",
+            vec![("mode", mode.to_string())],
+            value as f64,
+        );
+    }
+    for (core, usage) in cpu.per_core_usage.iter().enumerate() {
+        exposition.gauge(
+            "cpu_core_usage_percent",
+            "Uso de CPU por núcleo lógico.",
+            vec![("core", core.to_string())],
+            *usage as f64,
+        );
+    }
+    exposition.gauge(
+        "cpu_frequency_mhz",
+        "Frequência do primeiro núcleo.",
+        vec![],
+        cpu.frequency as f64,
+    );
+
+    let policy = &cpu.frequency_policy;
+    if let Some(governor) = &policy.governor {
+        exposition.gauge(
+            "cpu_frequency_policy_info",
+            "Governor e preferência de energia do cpufreq.",
+            vec![
+                ("governor", governor.clone()),
+                (
+                    "energy_performance_preference",
+                    policy
+                        .energy_performance_preference
+                        .clone()
+                        .unwrap_or_default(),
+                ),
+            ],
+            1.0,
+        );
+    }
+    for (limit, value) in [
+        ("min", policy.scaling_min_mhz),
+        ("max", policy.scaling_max_mhz),
+    ] {
+        exposition.optional_gauge(
+            "cpu_scaling_limit_mhz",
+            "Limites de frequência do cpufreq.",
+            vec![("limit", limit.to_string())],
+            value.map(|value| value as f64),
+        );
+    }
+    exposition.optional_gauge(
+        "cpu_boost_enabled",
+        "Turbo/boost habilitado (1) ou não (0).",
+        vec![],
+        policy
+            .boost_enabled
+            .map(|boost| if boost { 1.0 } else { 0.0 }),
+    );
+    if cpu
+        .cores
+        .iter()
+        .any(|core| core.core_throttle_count.is_some())
+    {
+        exposition.gauge(
+            "cpu_throttling",
+            "Contadores de throttling térmico subiram na última atualização.",
+            vec![],
+            if cpu.throttle.throttling { 1.0 } else { 0.0 },
+        );
+        for (scope, count) in [
+            ("core", cpu.throttle.core_throttle_count),
+            ("package", cpu.throttle.package_throttle_count),
+        ] {
+            exposition.counter(
+                "cpu_thermal_throttle_events_total",
+                "Eventos de throttling térmico desde o boot.",
+                vec![("scope", scope.to_string())],
+                count as f64,
+            );
+        }
+    }
+}
+
+fn encode_memory(exposition: &mut Exposition, memory: &MemoryMetrics) {
+    for (name, help, value) in [
+        (
+            "memory_total_bytes",
+            "Memória física total.",
+            memory.total_memory,
+        ),
+        (
+            "memory_used_bytes",
+            "Memória física em uso.",
+            memory.used_memory,
+        ),
+        (
+            "memory_available_bytes",
+            "Memória disponível para novos processos.",
+            memory.available_memory,
+        ),
+        ("swap_total_bytes", "Swap total.", memory.total_swap),
+        ("swap_used_bytes", "Swap em uso.", memory.used_swap),
+    ] {
+        exposition.gauge(name, help, vec![], value * BYTES_PER_GIB);
+    }
+}
+
+fn encode_disks(exposition: &mut Exposition, disk: &DiskMetrics) {
+    for disk in &disk.disks {
+        let labels = || {
+            vec![
+                ("device", disk.name.clone()),
+                ("mountpoint", disk.mount_point.clone()),
+            ]
+        };
+        exposition.gauge(
+            "disk_total_bytes",
+            "Tamanho do sistema de arquivos.",
+            labels(),
+            disk.total_space * BYTES_PER_GIB,
+        );
+        exposition.gauge(
+            "disk_used_bytes",
+            "Espaço usado no sistema de arquivos.",
+            labels(),
+            disk.used_space * BYTES_PER_GIB,
+        );
+        exposition.gauge(
+            "disk_available_bytes",
+            "Espaço livre no sistema de arquivos.",
+            labels(),
+            disk.available_space * BYTES_PER_GIB,
+        );
+        exposition.gauge(
+            "disk_read_bytes_per_second",
+            "Taxa de leitura do dispositivo.",
+            labels(),
+            disk.read_bytes_per_sec as f64,
+        );
+        exposition.gauge(
+            "disk_write_bytes_per_second",
+            "Taxa de escrita do dispositivo.",
+            labels(),
+            disk.write_bytes_per_sec as f64,
+        );
+    }
+}
+
+fn encode_network(exposition: &mut Exposition, network: &NetworkMetrics) {
+    let mut interfaces: Vec<_> = network.interfaces.iter().collect();
+    interfaces.sort_by_key(|(name, _)| *name);
+    for (name, interface) in interfaces {
+        let labels = || vec![("interface", name.clone())];
+        exposition.gauge(
+            "network_up",
+            "1 quando a interface está operacional.",
+            labels(),
+            if interface.is_up { 1.0 } else { 0.0 },
+        );
+        for (metric, help, value) in [
+            (
+                "network_receive_bytes_total",
+                "Bytes recebidos.",
+                interface.bytes_received,
+            ),
+            (
+                "network_transmit_bytes_total",
+                "Bytes enviados.",
+                interface.bytes_transmitted,
+            ),
+            (
+                "network_receive_packets_total",
+                "Pacotes recebidos.",
+                interface.packets_received,
+            ),
+            (
+                "network_transmit_packets_total",
+                "Pacotes enviados.",
+                interface.packets_transmitted,
+            ),
+            (
+                "network_receive_errors_total",
+                "Erros de recepção.",
+                interface.errors_received,
+            ),
+            (
+                "network_transmit_errors_total",
+                "Erros de transmissão.",
+                interface.errors_transmitted,
+            ),
+        ] {
+            exposition.counter(metric, help, labels(), value as f64);
+        }
+    }
+}
+
+pub(crate) fn encode_latency(exposition: &mut Exposition, network: &NetworkMetrics) {
+    exposition.optional_gauge(
+        "gateway_latency_seconds",
+        "Latência do ping ao gateway ou ao latency_target configurado.",
+        vec![(
+            "target",
+            network
+                .latency_target
+                .clone()
+                .or_else(|| network.gateway_ip.clone())
+                .unwrap_or_default(),
+        )],
+        network.gateway_latency_ms.map(|ms| ms as f64 / 1000.0),
+    );
+}
+
+pub(crate) fn encode_sensors(exposition: &mut Exposition, sensors: &SensorMetrics) {
+    for sensor in &sensors.temperatures {
+        let labels = || {
+            vec![
+                ("chip", sensor.chip.clone()),
+                ("label", sensor.label.clone()),
+            ]
+        };
+        exposition.gauge(
+            "temperature_celsius",
+            "Temperatura do sensor hwmon.",
+            labels(),
+            sensor.temperature_celsius as f64,
+        );
+        exposition.optional_gauge(
+            "temperature_max_celsius",
+            "Limite máximo informado pelo sensor.",
+            labels(),
+            sensor.max_celsius.map(f64::from),
+        );
+        exposition.optional_gauge(
+            "temperature_critical_celsius",
+            "Limite crítico informado pelo sensor.",
+            labels(),
+            sensor.critical_celsius.map(f64::from),
+        );
+    }
+    exposition.optional_gauge(
+        "temperature_average_celsius",
+        "Média das temperaturas visíveis.",
+        vec![],
+        sensors.average_temperature_celsius.map(f64::from),
+    );
+    exposition.optional_gauge(
+        "temperature_hottest_celsius",
+        "Temperatura mais alta entre todos os sensores.",
+        vec![("label", sensors.hottest_label.clone())],
+        sensors.hottest_temperature_celsius.map(f64::from),
+    );
+    exposition.optional_gauge(
+        "temperature_hottest_cpu_celsius",
+        "Temperatura mais alta dos chips de CPU.",
+        vec![("label", sensors.hottest_cpu_label.clone())],
+        sensors.hottest_cpu_celsius.map(f64::from),
+    );
+    exposition.optional_gauge(
+        "temperature_hottest_gpu_celsius",
+        "Temperatura mais alta dos chips de GPU.",
+        vec![("label", sensors.hottest_gpu_label.clone())],
+        sensors.hottest_gpu_celsius.map(f64::from),
+    );
+    for fan in &sensors.fans {
+        exposition.gauge(
+            "fan_rpm",
+            "Rotação da ventoinha.",
+            vec![("label", fan.label.clone())],
+            fan.rpm as f64,
+        );
+        exposition.optional_gauge(
+            "fan_duty_percent",
+            "Duty cycle PWM da ventoinha.",
+            vec![("label", fan.label.clone())],
+            fan.duty_percent.map(f64::from),
+        );
+    }
+    for sensor in &sensors.voltages {
+        exposition.gauge(
+            "voltage_volts",
+            "Tensão medida pelo sensor.",
+            vec![("label", sensor.label.clone())],
+            sensor.volts as f64,
+        );
+    }
+    for sensor in &sensors.currents {
+        exposition.gauge(
+            "current_amperes",
+            "Corrente medida pelo sensor.",
+            vec![("label", sensor.label.clone())],
+            sensor.amps as f64,
+        );
+    }
+    for sensor in &sensors.powers {
+        exposition.gauge(
+            "power_watts",
+            "Potência medida pelo sensor.",
+            vec![("label", sensor.label.clone())],
+            sensor.watts as f64,
+        );
+    }
+}
+
+pub(crate) fn encode_gpus(exposition: &mut Exposition, gpus: &[GpuInfo]) {
+    for gpu in gpus {
+        let labels = || {
+            vec![
+                ("name", gpu.name.clone()),
+                ("vendor", gpu.vendor.as_str().to_string()),
+            ]
+        };
+        for (name, help, value) in [
+            (
+                "gpu_usage_percent",
+                "Uso da GPU.",
+                gpu.usage_percent.map(f64::from),
+            ),
+            (
+                "gpu_vram_used_bytes",
+                "VRAM em uso.",
+                gpu.vram_used_gb.map(|gb| gb * BYTES_PER_GIB),
+            ),
+            (
+                "gpu_vram_total_bytes",
+                "VRAM total.",
+                gpu.vram_total_gb.map(|gb| gb * BYTES_PER_GIB),
+            ),
+            (
+                "gpu_vram_usage_percent",
+                "Uso de VRAM.",
+                gpu.vram_usage_percent.map(f64::from),
+            ),
+            (
+                "gpu_shader_clock_mhz",
+                "Clock do núcleo da GPU.",
+                gpu.shader_clock_mhz.map(|mhz| mhz as f64),
+            ),
+            (
+                "gpu_memory_clock_mhz",
+                "Clock da memória da GPU.",
+                gpu.memory_clock_mhz.map(|mhz| mhz as f64),
+            ),
+            (
+                "gpu_temperature_celsius",
+                "Temperatura da GPU.",
+                gpu.temperature_celsius.map(f64::from),
+            ),
+            (
+                "gpu_power_watts",
+                "Consumo da GPU.",
+                gpu.power_watts.map(f64::from),
+            ),
+            (
+                "gpu_fan_rpm",
+                "Rotação da ventoinha da GPU.",
+                gpu.fan_rpm.map(|rpm| rpm as f64),
+            ),
+            (
+                "gpu_fan_duty_percent",
+                "Duty cycle da ventoinha da GPU.",
+                gpu.fan_duty_percent.map(f64::from),
+            ),
+        ] {
+            exposition.optional_gauge(name, help, labels(), value);
+        }
+    }
+}
+
+fn encode_processes(exposition: &mut Exposition, processes: &[ProcessInfo]) {
+    for process in processes {
+        let labels = || {
+            vec![
+                ("pid", process.pid.to_string()),
+                ("name", process.name.clone()),
+            ]
+        };
+        exposition.gauge(
+            "process_cpu_percent",
+            "Uso de CPU do processo, normalizado pelo número de núcleos.",
+            labels(),
+            process.cpu_percent as f64,
+        );
+        exposition.gauge(
+            "process_resident_memory_bytes",
+            "Memória residente do processo.",
+            labels(),
+            process.memory_mb * BYTES_PER_MIB,
+        );
+    }
+}
+
+fn encode_system(exposition: &mut Exposition, metrics: &SystemMetrics) {
+    let info = &metrics.system_info;
+    exposition.gauge(
+        "system_info",
+        "Identificação do host; o valor é sempre 1.",
+        vec![
+            ("hostname", info.hostname.clone()),
+            ("os_name", info.os_name.clone()),
+            ("os_version", info.os_version.clone()),
+            ("kernel_version", info.kernel_version.clone()),
+            ("architecture", info.architecture.clone()),
+        ],
+        1.0,
+    );
+    exposition.gauge(
+        "processes",
+        "Quantidade de processos.",
+        vec![],
+        info.process_count as f64,
+    );
+    exposition.gauge(
+        "uptime_seconds",
+        "Tempo desde o boot.",
+        vec![],
+        metrics.uptime as f64,
+    );
+    let (one, five, fifteen) = metrics.load_average;
+    for (period, value) in [("1m", one), ("5m", five), ("15m", fifteen)] {
+        exposition.gauge(
+            "load_average",
+            "Load average do sistema.",
+            vec![("period", period.to_string())],
+            value,
+        );
+    }
+}
+
+/// PSI do sistema com `cgroup="/"` e do cgroup configurado com o caminho dele.
+fn encode_pressure(exposition: &mut Exposition, pressure: &PressureMetrics) {
+    let mut encode = |cgroup: &str, resource: &str, value: &Option<PressureResource>| {
+        let Some(value) = value else {
+            return;
+        };
+        for (kind, stall) in std::iter::once(("some", &value.some))
+            .chain(value.full.as_ref().map(|full| ("full", full)))
+        {
+            let labels = || {
+                vec![
+                    ("cgroup", cgroup.to_string()),
+                    ("resource", resource.to_string()),
+                    ("kind", kind.to_string()),
+                ]
+            };
+            for (window, average) in [
+                ("10s", stall.avg10),
+                ("60s", stall.avg60),
+                ("300s", stall.avg300),
+            ] {
+                let mut labels = labels();
+                labels.push(("window", window.to_string()));
+                exposition.gauge(
+                    "pressure_average_percent",
+                    "Média móvel do kernel do tempo com tarefas travadas (PSI).",
+                    labels,
+                    average as f64,
+                );
+            }
+            exposition.counter(
+                "pressure_stalled_seconds_total",
+                "Tempo acumulado com tarefas travadas esperando o recurso (PSI).",
+                labels(),
+                stall.total_us as f64 / 1_000_000.0,
+            );
+        }
+    };
+    encode("/", "cpu", &pressure.cpu);
+    encode("/", "memory", &pressure.memory);
+    encode("/", "io", &pressure.io);
+    if let Some(cgroup) = &pressure.cgroup {
+        encode(&cgroup.path, "cpu", &cgroup.cpu);
+        encode(&cgroup.path, "memory", &cgroup.memory);
+        encode(&cgroup.path, "io", &cgroup.io);
+    }
+}
+
+/// Resultado do último teste de velocidade; vazio enquanto nenhum foi executado.
+pub(crate) fn encode_speed_test(exposition: &mut Exposition, status: &NetworkSpeedTestStatus) {
+    let finished = match status.state {
+        NetworkSpeedTestState::Idle => return,
+        NetworkSpeedTestState::Running => false,
+        NetworkSpeedTestState::Success
+        | NetworkSpeedTestState::Error
+        | NetworkSpeedTestState::Cancelled => true,
+    };
+    exposition.gauge(
+        "speedtest_running",
+        "1 enquanto um teste de velocidade está em andamento.",
+        vec![],
+        if finished { 0.0 } else { 1.0 },
+    );
+    if !finished {
+        return;
+    }
+
+    let success = matches!(status.state, NetworkSpeedTestState::Success);
+    exposition.gauge(
+        "speedtest_success",
+        "1 quando o último teste de velocidade terminou com sucesso.",
+        vec![],
+        if success { 1.0 } else { 0.0 },
+    );
+    exposition.optional_gauge(
+        "speedtest_finished_timestamp_seconds",
+        "Horário Unix em que o último teste terminou.",
+        vec![],
+        status.finished_at_unix_ms.map(|ms| ms as f64 / 1000.0),
+    );
+    if !success {
+        return;
+    }
+    exposition.optional_gauge(
+        "speedtest_download_bits_per_second",
+        "Download medido no último teste.",
+        vec![],
+        status.download_mbps.map(|mbps| mbps as f64 * 1_000_000.0),
+    );
+    exposition.optional_gauge(
+        "speedtest_upload_bits_per_second",
+        "Upload medido no último teste.",
+        vec![],
+        status.upload_mbps.map(|mbps| mbps as f64 * 1_000_000.0),
+    );
+    exposition.optional_gauge(
+        "speedtest_ping_seconds",
+        "Ping medido no último teste.",
+        vec![],
+        status.ping_ms.map(|ms| ms as f64 / 1000.0),
+    );
+}
+
+/// Preenche `exposition` com todas as famílias derivadas de `metrics`.
+pub fn encode_system_metrics(exposition: &mut Exposition, metrics: &SystemMetrics) {
+    encode_cpu(exposition, &metrics.cpu);
+    encode_memory(exposition, &metrics.memory);
+    encode_disks(exposition, &metrics.disk);
+    encode_network(exposition, &metrics.network);
+    encode_latency(exposition, &metrics.network);
+    encode_sensors(exposition, &metrics.sensors);
+    encode_gpus(exposition, &metrics.gpus);
+    encode_processes(exposition, &metrics.top_processes);
+    encode_system(exposition, metrics);
+    encode_pressure(exposition, &metrics.pressure);
+}
+
+/// Endereço do exportador: TCP restrito a loopback ou `unix:/caminho`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenAddress {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl FromStr for ListenAddress {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = value.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err("caminho do socket Unix vazio".to_string());
+            }
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+
+        let address: SocketAddr = value.parse().map_err(|_| {
+            format!("endereço inválido: {value:?} (use 127.0.0.1:PORTA ou unix:/caminho)")
+        })?;
+        if !address.ip().is_loopback() {
+            return Err(format!(
+                "{address} não é loopback; o exportador só escuta em 127.0.0.1, ::1 ou socket Unix"
+            ));
+        }
+        Ok(Self::Tcp(address))
+    }
+}
+
+impl std::fmt::Display for ListenAddress {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(formatter, "http://{address}/metrics"),
+            Self::Unix(path) => write!(formatter, "unix:{}", path.display()),
+        }
+    }
+}
+
+fn response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Atende uma única requisição HTTP/1.1: `GET /metrics` devolve `body`, o resto recebe 404/405.
+async fn handle_connection<S>(mut stream: S, body: &RwLock<String>) -> std::io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut head = Vec::with_capacity(512);
+    let mut buffer = [0u8; 512];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST_HEAD {
+            break;
+        }
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+
+    let request_line = String::from_utf8_lossy(&head);
+    let mut parts = request_line.lines().next().unwrap_or_default().split(' ');
+    let (method, target) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+    );
+    let path = target.split('?').next().unwrap_or_default();
+
+    let reply = match (method, path) {
+        ("GET", "/metrics") => response("200 OK", CONTENT_TYPE, &body.read().await),
+        ("GET", "/") => response(
+            "200 OK",
+            "text/plain; charset=utf-8",
+            "monitor-tray exporter: métricas em /metrics\n",
+        ),
+        ("GET", _) => response("404 Not Found", "text/plain; charset=utf-8", "not found\n"),
+        _ => response(
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "method not allowed\n",
+        ),
+    };
+    stream.write_all(reply.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+fn spawn_handler<S>(stream: S, body: Arc<RwLock<String>>)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        // Clientes lentos ou quebrados não devem segurar a task indefinidamente.
+        let _ = tokio::time::timeout(REQUEST_TIMEOUT, handle_connection(stream, &body)).await;
+    });
+}
+
+async fn serve(address: &ListenAddress, body: Arc<RwLock<String>>) -> std::io::Result<()> {
+    match address {
+        ListenAddress::Tcp(address) => {
+            let listener = TcpListener::bind(address).await?;
+            loop {
+                let (stream, _) = listener.accept().await?;
+                spawn_handler(stream, body.clone());
+            }
+        }
+        ListenAddress::Unix(path) => {
+            // Socket deixado por uma execução anterior que não terminou de forma limpa.
+            if std::fs::symlink_metadata(path).is_ok_and(|metadata| {
+                std::os::unix::fs::FileTypeExt::is_socket(&metadata.file_type())
+            }) {
+                std::fs::remove_file(path)?;
+            }
+            let listener = UnixListener::bind(path)?;
+            loop {
+                let (stream, _) = listener.accept().await?;
+                spawn_handler(stream, body.clone());
+            }
+        }
+    }
+}
+
+/// Coleta a cada `interval` e serve o último snapshot em `address` até `Ctrl+C`.
+pub async fn run_exporter(
+    mut monitor: SystemMonitor,
+    address: ListenAddress,
+    interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    let body = Arc::new(RwLock::new(String::new()));
+    let refresher = {
+        let body = body.clone();
+        async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let mut exposition = Exposition::default();
+                encode_system_metrics(&mut exposition, &collect_metrics(&mut monitor).await);
+                *body.write().await = exposition.render();
+            }
+        }
+    };
+
+    eprintln!("monitor-tray: exportando métricas em {address}");
+    let result = tokio::select! {
+        result = serve(&address, body) => result.map_err(Into::into),
+        _ = refresher => Ok(()),
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    };
+    if let ListenAddress::Unix(path) = &address {
+        let _ = std::fs::remove_file(path);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::{FanSensor, GpuInfo, GpuVendor};
+
+    fn sample_metrics() -> SystemMetrics {
+        let mut metrics = SystemMonitor::new_empty().get_all_metrics();
+        metrics.gpus = vec![GpuInfo {
+            name: "Radeon \"RX\" 7800".to_string(),
+            vendor: GpuVendor::Amd,
+            usage_percent: Some(42.5),
+            vram_used_gb: Some(2.0),
+            vram_total_gb: Some(16.0),
+            vram_usage_percent: Some(12.5),
+            shader_clock_mhz: None,
+            memory_clock_mhz: None,
+            temperature_celsius: Some(55.0),
+            power_watts: None,
+            fan_rpm: None,
+            fan_duty_percent: None,
+        }];
+        metrics.sensors.fans = vec![
+            FanSensor {
+                label: "fan1".to_string(),
+                chip: String::new(),
+                rpm: 1200,
+                duty_percent: None,
+            },
+            FanSensor {
+                label: "fan1".to_string(),
+                chip: String::new(),
+                rpm: 900,
+                duty_percent: None,
+            },
+        ];
+        metrics
+    }
+
+    fn render(metrics: &SystemMetrics) -> String {
+        let mut exposition = Exposition::default();
+        encode_system_metrics(&mut exposition, metrics);
+        exposition.render()
+    }
+
+    #[test]
+    fn test_gpu_metrics_are_labelled_and_escaped() {
+        let output = render(&sample_metrics());
+
+        assert!(output.contains("# TYPE monitor_tray_gpu_usage_percent gauge\n"));
+        assert!(output.contains(
+            "monitor_tray_gpu_usage_percent{name=\"Radeon \\\"RX\\\" 7800\",vendor=\"amd\"} 42.5\n"
+        ));
+        assert!(output.contains(
+            "monitor_tray_gpu_vram_total_bytes{name=\"Radeon \\\"RX\\\" 7800\",vendor=\"amd\"} 17179869184\n"
+        ));
+        // Valores ausentes não geram amostra nem família vazia.
+        assert!(!output.contains("monitor_tray_gpu_power_watts"));
+    }
+
+    #[test]
+    fn test_duplicate_series_keep_first_sample() {
+        let output = render(&sample_metrics());
+
+        assert!(output.contains("monitor_tray_fan_rpm{label=\"fan1\"} 1200\n"));
+        assert!(!output.contains("monitor_tray_fan_rpm{label=\"fan1\"} 900\n"));
+        assert_eq!(output.matches("# HELP monitor_tray_fan_rpm ").count(), 1);
+    }
+
+    #[test]
+    fn test_format_value_uses_prometheus_special_values() {
+        assert_eq!(format_value(f64::NAN), "NaN");
+        assert_eq!(format_value(f64::INFINITY), "+Inf");
+        assert_eq!(format_value(f64::NEG_INFINITY), "-Inf");
+        assert_eq!(format_value(0.25), "0.25");
+        assert_eq!(escape_label("a\\b\n"), "a\\\\b\\n");
+    }
+
+    #[test]
+    fn test_listen_address_only_accepts_loopback_or_unix() {
+        assert_eq!(
+            "127.0.0.1:9882".parse::<ListenAddress>(),
+            Ok(ListenAddress::Tcp("127.0.0.1:9882".parse().unwrap()))
+        );
+        assert!("[::1]:9882".parse::<ListenAddress>().is_ok());
+        assert_eq!(
+            "unix:/run/user/1000/monitor-tray.sock".parse::<ListenAddress>(),
+            Ok(ListenAddress::Unix(PathBuf::from(
+                "/run/user/1000/monitor-tray.sock"
+            )))
+        );
+        assert!("0.0.0.0:9882".parse::<ListenAddress>().is_err());
+        assert!("192.168.0.10:9882".parse::<ListenAddress>().is_err());
+        assert!("unix:".parse::<ListenAddress>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_connection_serves_metrics_and_rejects_other_paths() {
+        let body = RwLock::new("monitor_tray_processes 3\n".to_string());
+
+        for (request, expected_status) in [
+            (
+                "GET /metrics HTTP/1.1\r\nHost: x\r\n\r\n",
+                "HTTP/1.1 200 OK",
+            ),
+            ("GET /other HTTP/1.1\r\n\r\n", "HTTP/1.1 404 Not Found"),
+            (
+                "POST /metrics HTTP/1.1\r\n\r\n",
+                "HTTP/1.1 405 Method Not Allowed",
+            ),
+        ] {
+            let (mut client, server) = tokio::io::duplex(4096);
+            client.write_all(request.as_bytes()).await.unwrap();
+            handle_connection(server, &body).await.unwrap();
+
+            let mut reply = String::new();
+            client.read_to_string(&mut reply).await.unwrap();
+            assert!(reply.starts_with(expected_status), "{reply}");
+            if expected_status.ends_with("200 OK") {
+                assert!(reply.contains(&format!("Content-Type: {CONTENT_TYPE}\r\n")));
+                assert!(reply.ends_with("\r\n\r\nmonitor_tray_processes 3\n"));
+            }
+        }
+    }
+}
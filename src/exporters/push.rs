@@ -0,0 +1,691 @@
+//! Envio ativo de métricas: serializa cada refresh de `FastMetrics`/`SlowMetrics` em
+//! line protocol do InfluxDB ou no formato plaintext do Graphite e envia por UDP ou TCP,
+//! acumulando as linhas em lotes e reconectando com backoff exponencial.
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use sysinfo::System;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::time::MissedTickBehavior;
+
+use super::BYTES_PER_GIB;
+use crate::monitor::{FastMetrics, SlowMetrics, SystemMonitor};
+use crate::unix_time::unix_now;
+
+pub const DEFAULT_PREFIX: &str = "monitor_tray";
+pub const DEFAULT_BATCH_SIZE: usize = 1000;
+
+/// Tamanho máximo de cada datagrama UDP, abaixo do MTU típico de 1500 bytes.
+const MAX_DATAGRAM: usize = 1400;
+/// Limite de linhas retidas enquanto o destino está fora; as mais antigas são descartadas.
+const MAX_BUFFERED_LINES: usize = 100_000;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PushFormat {
+    /// Line protocol do InfluxDB (também aceito pelo Telegraf e VictoriaMetrics)
+    Influx,
+    /// Plaintext do Graphite/Carbon
+    Graphite,
+}
+
+/// Destino no formato `udp://host:porta` ou `tcp://host:porta`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushEndpoint {
+    Udp(String),
+    Tcp(String),
+}
+
+impl FromStr for PushEndpoint {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (build, address): (fn(String) -> Self, &str) =
+            if let Some(address) = value.strip_prefix("udp://") {
+                (Self::Udp, address)
+            } else if let Some(address) = value.strip_prefix("tcp://") {
+                (Self::Tcp, address)
+            } else {
+                return Err(format!(
+                    "destino inválido: {value} (use udp://host:porta ou tcp://host:porta)"
+                ));
+            };
+        match address.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => {
+                Ok(build(address.to_string()))
+            }
+            _ => Err(format!("destino sem host ou porta válidos: {value}")),
+        }
+    }
+}
+
+impl std::fmt::Display for PushEndpoint {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Udp(address) => write!(formatter, "udp://{address}"),
+            Self::Tcp(address) => write!(formatter, "tcp://{address}"),
+        }
+    }
+}
+
+type Tags = Vec<(&'static str, String)>;
+
+/// Uma medição com seus tags e campos, independente do formato de saída.
+#[derive(Debug, Clone, PartialEq)]
+struct Point {
+    measurement: &'static str,
+    tags: Tags,
+    fields: Vec<(&'static str, f64)>,
+}
+
+impl Point {
+    fn new(measurement: &'static str, tags: Tags) -> Self {
+        Self {
+            measurement,
+            tags,
+            fields: Vec::new(),
+        }
+    }
+
+    fn field(mut self, name: &'static str, value: f64) -> Self {
+        self.fields.push((name, value));
+        self
+    }
+
+    fn optional_field(self, name: &'static str, value: Option<f64>) -> Self {
+        match value {
+            Some(value) => self.field(name, value),
+            None => self,
+        }
+    }
+}
+
+fn fast_points(metrics: &FastMetrics) -> Vec<Point> {
+    let cpu = &metrics.cpu;
+    let memory = &metrics.memory;
+    let (load1, load5, load15) = metrics.load_average;
+    let mut points = vec![
+        Point::new("cpu", vec![])
+            .field("usage_percent", cpu.usage_percent as f64)
+            .field("user_percent", cpu.user_percent as f64)
+            .field("system_percent", cpu.system_percent as f64)
+            .field("idle_percent", cpu.idle_percent as f64)
+            .field("steal_percent", cpu.steal_percent as f64)
+            .field("iowait_percent", cpu.iowait_percent as f64)
+            .field("irq_percent", cpu.irq_percent as f64)
+            .field("softirq_percent", cpu.softirq_percent as f64)
+            .field("nice_percent", cpu.nice_percent as f64)
+            .field("guest_percent", cpu.guest_percent as f64)
+            .field("frequency_mhz", cpu.frequency as f64),
+        Point::new("memory", vec![])
+            .field("total_bytes", memory.total_memory * BYTES_PER_GIB)
+            .field("used_bytes", memory.used_memory * BYTES_PER_GIB)
+            .field("available_bytes", memory.available_memory * BYTES_PER_GIB)
+            .field("usage_percent", memory.usage_percent as f64)
+            .field("swap_total_bytes", memory.total_swap * BYTES_PER_GIB)
+            .field("swap_used_bytes", memory.used_swap * BYTES_PER_GIB),
+        Point::new("system", vec![])
+            .field("uptime_seconds", metrics.uptime as f64)
+            .field("load1", load1)
+            .field("load5", load5)
+            .field("load15", load15),
+    ];
+    for (core, usage) in cpu.per_core_usage.iter().enumerate() {
+        points.push(
+            Point::new("cpu_core", vec![("core", core.to_string())])
+                .field("usage_percent", *usage as f64),
+        );
+    }
+    for disk in &metrics.disk.disks {
+        points.push(
+            Point::new(
+                "disk",
+                vec![
+                    ("device", disk.name.clone()),
+                    ("mountpoint", disk.mount_point.clone()),
+                ],
+            )
+            .field("total_bytes", disk.total_space * BYTES_PER_GIB)
+            .field("used_bytes", disk.used_space * BYTES_PER_GIB)
+            .field("available_bytes", disk.available_space * BYTES_PER_GIB)
+            .field("usage_percent", disk.usage_percent as f64)
+            .field("read_bytes_per_second", disk.read_bytes_per_sec as f64)
+            .field("write_bytes_per_second", disk.write_bytes_per_sec as f64),
+        );
+    }
+    let mut interfaces: Vec<_> = metrics.network.interfaces.iter().collect();
+    interfaces.sort_by_key(|(name, _)| *name);
+    for (name, interface) in interfaces {
+        points.push(
+            Point::new("network", vec![("interface", name.clone())])
+                .field("up", if interface.is_up { 1.0 } else { 0.0 })
+                .field("bytes_received", interface.bytes_received as f64)
+                .field("bytes_transmitted", interface.bytes_transmitted as f64)
+                .field("packets_received", interface.packets_received as f64)
+                .field("packets_transmitted", interface.packets_transmitted as f64)
+                .field("errors_received", interface.errors_received as f64)
+                .field("errors_transmitted", interface.errors_transmitted as f64),
+        );
+    }
+    let network = &metrics.network;
+    if let Some(latency) = network.gateway_latency_ms {
+        let target = network
+            .latency_target
+            .clone()
+            .or_else(|| network.gateway_ip.clone())
+            .unwrap_or_default();
+        points.push(Point::new("latency", vec![("target", target)]).field("ms", latency as f64));
+    }
+    points
+}
+
+/// Os processos do top não são enviados: o par pid/nome geraria uma série nova a cada processo.
+fn slow_points(metrics: &SlowMetrics) -> Vec<Point> {
+    let sensors = &metrics.sensors;
+    let mut points =
+        vec![Point::new("system", vec![])
+            .field("processes", metrics.system_info.process_count as f64)];
+    for sensor in &sensors.temperatures {
+        points.push(
+            Point::new(
+                "temperature",
+                vec![
+                    ("chip", sensor.chip.clone()),
+                    ("label", sensor.label.clone()),
+                ],
+            )
+            .field("celsius", sensor.temperature_celsius as f64)
+            .optional_field("max_celsius", sensor.max_celsius.map(f64::from))
+            .optional_field("critical_celsius", sensor.critical_celsius.map(f64::from)),
+        );
+    }
+    for fan in &sensors.fans {
+        points.push(
+            Point::new("fan", vec![("label", fan.label.clone())])
+                .field("rpm", fan.rpm as f64)
+                .optional_field("duty_percent", fan.duty_percent.map(f64::from)),
+        );
+    }
+    for sensor in &sensors.voltages {
+        points.push(
+            Point::new("voltage", vec![("label", sensor.label.clone())])
+                .field("volts", sensor.volts as f64),
+        );
+    }
+    for sensor in &sensors.currents {
+        points.push(
+            Point::new("current", vec![("label", sensor.label.clone())])
+                .field("amps", sensor.amps as f64),
+        );
+    }
+    for sensor in &sensors.powers {
+        points.push(
+            Point::new("power", vec![("label", sensor.label.clone())])
+                .field("watts", sensor.watts as f64),
+        );
+    }
+    for gpu in &metrics.gpus {
+        points.push(
+            Point::new(
+                "gpu",
+                vec![
+                    ("name", gpu.name.clone()),
+                    ("vendor", gpu.vendor.as_str().to_string()),
+                ],
+            )
+            .optional_field("usage_percent", gpu.usage_percent.map(f64::from))
+            .optional_field(
+                "vram_used_bytes",
+                gpu.vram_used_gb.map(|gb| gb * BYTES_PER_GIB),
+            )
+            .optional_field(
+                "vram_total_bytes",
+                gpu.vram_total_gb.map(|gb| gb * BYTES_PER_GIB),
+            )
+            .optional_field("vram_usage_percent", gpu.vram_usage_percent.map(f64::from))
+            .optional_field(
+                "shader_clock_mhz",
+                gpu.shader_clock_mhz.map(|mhz| mhz as f64),
+            )
+            .optional_field(
+                "memory_clock_mhz",
+                gpu.memory_clock_mhz.map(|mhz| mhz as f64),
+            )
+            .optional_field(
+                "temperature_celsius",
+                gpu.temperature_celsius.map(f64::from),
+            )
+            .optional_field("power_watts", gpu.power_watts.map(f64::from))
+            .optional_field("fan_rpm", gpu.fan_rpm.map(|rpm| rpm as f64))
+            .optional_field("fan_duty_percent", gpu.fan_duty_percent.map(f64::from)),
+        );
+    }
+    points
+}
+
+/// Escapa vírgulas, espaços e, em chaves e valores de tag, o sinal de igual.
+fn escape_influx(value: &str, escape_equals: bool) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for character in value.chars() {
+        if character == ',' || character == ' ' || (escape_equals && character == '=') {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
+}
+
+/// Uma linha por ponto. Campos não finitos são omitidos (o InfluxDB rejeita NaN) e tags
+/// vazios também, já que o line protocol não aceita valor de tag vazio.
+fn encode_influx(point: &Point, host: &str, timestamp: Duration) -> Option<String> {
+    let fields: Vec<String> = point
+        .fields
+        .iter()
+        .filter(|(_, value)| value.is_finite())
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect();
+    if fields.is_empty() {
+        return None;
+    }
+    let mut line = escape_influx(point.measurement, false);
+    for (key, value) in std::iter::once(&("host", host.to_string())).chain(&point.tags) {
+        if !value.is_empty() {
+            let _ = write!(line, ",{key}={}", escape_influx(value, true));
+        }
+    }
+    let _ = write!(line, " {} {}", fields.join(","), timestamp.as_nanos());
+    Some(line)
+}
+
+/// Troca tudo que não for alfanumérico, `-` ou `_` por `_`, já que `.` separa os níveis.
+fn graphite_segment(value: &str) -> String {
+    let segment: String = value
+        .chars()
+        .map(|character| {
+            if character.is_ascii_alphanumeric() || character == '-' || character == '_' {
+                character
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if segment.is_empty() {
+        "_".to_string()
+    } else {
+        segment
+    }
+}
+
+/// Uma linha por campo, no caminho `prefixo.host.medição.<valores dos tags>.campo`.
+fn encode_graphite(point: &Point, prefix: &str, host: &str, timestamp: Duration) -> Vec<String> {
+    let mut path = String::new();
+    if !prefix.is_empty() {
+        path.push_str(prefix);
+        path.push('.');
+    }
+    path.push_str(&graphite_segment(host));
+    path.push('.');
+    path.push_str(point.measurement);
+    for (_, value) in &point.tags {
+        path.push('.');
+        path.push_str(&graphite_segment(value));
+    }
+    point
+        .fields
+        .iter()
+        .filter(|(_, value)| value.is_finite())
+        .map(|(name, value)| format!("{path}.{name} {value} {}", timestamp.as_secs()))
+        .collect()
+}
+
+enum Connection {
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+}
+
+/// Destino das linhas: serializa, guarda em buffer e envia em lotes de `batch_size`.
+pub struct PushSink {
+    endpoint: PushEndpoint,
+    format: PushFormat,
+    prefix: String,
+    host: String,
+    batch_size: usize,
+    buffer: VecDeque<String>,
+    connection: Option<Connection>,
+    backoff: Duration,
+    retry_at: Option<Instant>,
+    dropped_lines: u64,
+}
+
+impl PushSink {
+    pub fn new(
+        endpoint: PushEndpoint,
+        format: PushFormat,
+        prefix: String,
+        batch_size: usize,
+    ) -> Self {
+        Self {
+            endpoint,
+            format,
+            prefix: prefix.trim_matches('.').to_string(),
+            host: System::host_name().unwrap_or_else(|| "unknown".to_string()),
+            batch_size: batch_size.max(1),
+            buffer: VecDeque::new(),
+            connection: None,
+            backoff: INITIAL_BACKOFF,
+            retry_at: None,
+            dropped_lines: 0,
+        }
+    }
+
+    pub fn buffered_lines(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Indica se já há linhas suficientes para um lote completo.
+    pub fn batch_ready(&self) -> bool {
+        self.buffer.len() >= self.batch_size
+    }
+
+    fn push_points(&mut self, points: &[Point], timestamp: Duration) {
+        for point in points {
+            match self.format {
+                PushFormat::Influx => {
+                    if let Some(line) = encode_influx(point, &self.host, timestamp) {
+                        self.buffer.push_back(line);
+                    }
+                }
+                PushFormat::Graphite => {
+                    self.buffer
+                        .extend(encode_graphite(point, &self.prefix, &self.host, timestamp))
+                }
+            }
+        }
+        let excess = self.buffer.len().saturating_sub(MAX_BUFFERED_LINES);
+        if excess > 0 {
+            if self.dropped_lines == 0 {
+                eprintln!(
+                    "monitor-tray: {} indisponível, descartando as linhas mais antigas",
+                    self.endpoint
+                );
+            }
+            self.buffer.drain(..excess);
+            self.dropped_lines += excess as u64;
+        }
+    }
+
+    pub fn push_fast(&mut self, metrics: &FastMetrics, timestamp: Duration) {
+        self.push_points(&fast_points(metrics), timestamp);
+    }
+
+    pub fn push_slow(&mut self, metrics: &SlowMetrics, timestamp: Duration) {
+        self.push_points(&slow_points(metrics), timestamp);
+    }
+
+    async fn connect(&self) -> std::io::Result<Connection> {
+        match &self.endpoint {
+            PushEndpoint::Udp(address) => {
+                let target = tokio::net::lookup_host(address.as_str())
+                    .await?
+                    .next()
+                    .ok_or_else(|| std::io::Error::other(format!("{address} não resolve")))?;
+                let local = if target.is_ipv4() {
+                    "0.0.0.0:0"
+                } else {
+                    "[::]:0"
+                };
+                let socket = UdpSocket::bind(local).await?;
+                socket.connect(target).await?;
+                Ok(Connection::Udp(socket))
+            }
+            PushEndpoint::Tcp(address) => {
+                let stream =
+                    tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(address.as_str()))
+                        .await
+                        .map_err(|_| std::io::Error::from(std::io::ErrorKind::TimedOut))??;
+                stream.set_nodelay(true)?;
+                Ok(Connection::Tcp(stream))
+            }
+        }
+    }
+
+    async fn send_batch(&mut self, lines: &[String]) -> std::io::Result<()> {
+        if self.connection.is_none() {
+            self.connection = Some(self.connect().await?);
+        }
+        match self.connection.as_mut() {
+            Some(Connection::Tcp(stream)) => {
+                let mut payload = lines.join("\n");
+                payload.push('\n');
+                stream.write_all(payload.as_bytes()).await
+            }
+            Some(Connection::Udp(socket)) => {
+                // Agrupa linhas inteiras em datagramas; uma linha maior que o limite vai sozinha.
+                let mut datagram = String::new();
+                for line in lines {
+                    if !datagram.is_empty() && datagram.len() + line.len() + 1 > MAX_DATAGRAM {
+                        socket.send(datagram.as_bytes()).await?;
+                        datagram.clear();
+                    }
+                    datagram.push_str(line);
+                    datagram.push('\n');
+                }
+                if !datagram.is_empty() {
+                    socket.send(datagram.as_bytes()).await?;
+                }
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Envia o buffer em lotes. Em caso de falha, descarta a conexão, mantém as linhas
+    /// pendentes e só tenta de novo depois do backoff, que dobra a cada falha até 60 s.
+    pub async fn flush(&mut self) -> std::io::Result<()> {
+        if self
+            .retry_at
+            .is_some_and(|retry_at| Instant::now() < retry_at)
+        {
+            return Ok(());
+        }
+        while !self.buffer.is_empty() {
+            let count = self.buffer.len().min(self.batch_size);
+            let batch: Vec<String> = self.buffer.range(..count).cloned().collect();
+            if let Err(error) = self.send_batch(&batch).await {
+                self.connection = None;
+                self.retry_at = Some(Instant::now() + self.backoff);
+                self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+                return Err(error);
+            }
+            self.buffer.drain(..count);
+        }
+        if self.retry_at.take().is_some() {
+            eprintln!("monitor-tray: conexão com {} restabelecida", self.endpoint);
+        }
+        self.backoff = INITIAL_BACKOFF;
+        self.dropped_lines = 0;
+        Ok(())
+    }
+}
+
+/// Coleta a cada `interval` e envia as linhas acumuladas a cada `flush_interval`, ou antes
+/// quando um lote enche, até `Ctrl+C`. As métricas lentas só entram quando foram de fato
+/// atualizadas no ciclo.
+pub async fn run_push(
+    mut monitor: SystemMonitor,
+    mut sink: PushSink,
+    interval: Duration,
+    flush_interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    eprintln!("monitor-tray: enviando métricas para {}", sink.endpoint);
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut last_flush = Instant::now();
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+
+        monitor.update_fast_metrics().await;
+        let slow_refreshed = monitor.refresh_slow_metrics(false).await;
+        let timestamp = unix_now();
+        sink.push_fast(&monitor.get_fast_metrics(), timestamp);
+        if slow_refreshed {
+            sink.push_slow(&monitor.get_slow_metrics(), timestamp);
+        }
+
+        if sink.batch_ready() || last_flush.elapsed() >= flush_interval {
+            last_flush = Instant::now();
+            if let Err(error) = sink.flush().await {
+                eprintln!(
+                    "monitor-tray: falha ao enviar para {}: {error} ({} linhas pendentes)",
+                    sink.endpoint,
+                    sink.buffered_lines()
+                );
+            }
+        }
+    }
+
+    // Última tentativa ao encerrar; o que não for enviado agora é perdido.
+    sink.retry_at = None;
+    sink.flush().await.map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    const TIMESTAMP: Duration = Duration::from_secs(1_700_000_000);
+
+    fn sink(endpoint: PushEndpoint, format: PushFormat) -> PushSink {
+        let mut sink = PushSink::new(endpoint, format, DEFAULT_PREFIX.to_string(), 2);
+        sink.host = "lab-01".to_string();
+        sink
+    }
+
+    #[test]
+    fn test_influx_line_escapes_tags_and_skips_non_finite_fields() {
+        let point = Point::new(
+            "disk",
+            vec![
+                ("device", "My Disk,1".to_string()),
+                ("mountpoint", "/a=b".to_string()),
+                ("empty", String::new()),
+            ],
+        )
+        .field("used_bytes", 1024.0)
+        .field("usage_percent", f64::NAN);
+
+        assert_eq!(
+            encode_influx(&point, "lab-01", TIMESTAMP).as_deref(),
+            Some(
+                "disk,host=lab-01,device=My\\ Disk\\,1,mountpoint=/a\\=b \
+                 used_bytes=1024 1700000000000000000"
+            )
+        );
+        let empty = Point::new("cpu", vec![]).field("usage_percent", f64::INFINITY);
+        assert_eq!(encode_influx(&empty, "lab-01", TIMESTAMP), None);
+    }
+
+    #[test]
+    fn test_graphite_lines_use_sanitized_tag_values_as_path() {
+        let point = Point::new(
+            "temperature",
+            vec![("chip", "k10temp-pci-00c3".to_string())],
+        )
+        .field("celsius", 54.5)
+        .optional_field("max_celsius", None);
+
+        assert_eq!(
+            encode_graphite(&point, "monitor_tray", "lab.01", TIMESTAMP),
+            vec!["monitor_tray.lab_01.temperature.k10temp-pci-00c3.celsius 54.5 1700000000"]
+        );
+        assert_eq!(graphite_segment("/home"), "_home");
+        assert_eq!(graphite_segment(""), "_");
+    }
+
+    #[test]
+    fn test_endpoint_requires_scheme_host_and_port() {
+        assert_eq!(
+            "udp://127.0.0.1:8089".parse(),
+            Ok(PushEndpoint::Udp("127.0.0.1:8089".to_string()))
+        );
+        assert_eq!(
+            "tcp://carbon.lan:2003".parse(),
+            Ok(PushEndpoint::Tcp("carbon.lan:2003".to_string()))
+        );
+        assert!("carbon.lan:2003".parse::<PushEndpoint>().is_err());
+        assert!("tcp://carbon.lan".parse::<PushEndpoint>().is_err());
+        assert!("udp://:8089".parse::<PushEndpoint>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tcp_sink_keeps_lines_until_endpoint_comes_back() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+
+        let mut sink = sink(PushEndpoint::Tcp(address.to_string()), PushFormat::Influx);
+        sink.push_fast(&SystemMonitor::new_empty().get_fast_metrics(), TIMESTAMP);
+        let pending = sink.buffered_lines();
+        assert!(sink.batch_ready());
+
+        assert!(sink.flush().await.is_err());
+        assert_eq!(sink.buffered_lines(), pending);
+        assert_eq!(sink.backoff, INITIAL_BACKOFF * 2);
+        // Dentro do backoff, o flush não tenta conectar.
+        assert!(sink.flush().await.is_ok());
+        assert_eq!(sink.buffered_lines(), pending);
+
+        let listener = TcpListener::bind(address).await.unwrap();
+        sink.retry_at = Some(Instant::now());
+        sink.flush().await.expect("flush after reconnect");
+        assert_eq!(sink.buffered_lines(), 0);
+        assert_eq!(sink.backoff, INITIAL_BACKOFF);
+
+        let (mut stream, _) = listener.accept().await.unwrap();
+        drop(sink);
+        let mut received = String::new();
+        stream.read_to_string(&mut received).await.unwrap();
+        assert_eq!(received.lines().count(), pending);
+        assert!(received.starts_with("cpu,host=lab-01 usage_percent="));
+    }
+
+    #[tokio::test]
+    async fn test_udp_sink_splits_batches_into_datagrams() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let mut sink = sink(
+            PushEndpoint::Udp(receiver.local_addr().unwrap().to_string()),
+            PushFormat::Graphite,
+        );
+        let long_tag = "x".repeat(MAX_DATAGRAM / 2);
+        let points: Vec<Point> = (0..3)
+            .map(|_| Point::new("fan", vec![("label", long_tag.clone())]).field("rpm", 900.0))
+            .collect();
+        sink.push_points(&points, TIMESTAMP);
+
+        sink.flush().await.expect("udp flush");
+
+        let mut datagram = vec![0; 2 * MAX_DATAGRAM];
+        let mut lines = 0;
+        while lines < 3 {
+            let size = receiver.recv(&mut datagram).await.unwrap();
+            assert!(size <= MAX_DATAGRAM);
+            let text = std::str::from_utf8(&datagram[..size]).unwrap();
+            assert!(text.ends_with(" 900 1700000000\n"));
+            lines += text.lines().count();
+        }
+        assert_eq!(sink.buffered_lines(), 0);
+    }
+}
@@ -0,0 +1,191 @@
+//! Saída para o textfile collector do node_exporter: grava periodicamente um `.prom`
+//! só com o que o node_exporter não coleta sozinho (GPU, sensores, latência e speed test).
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use tokio::time::MissedTickBehavior;
+
+use crate::collect_metrics;
+use crate::dbus::client::{connect_backend, BackendProxy};
+use crate::monitor::{NetworkSpeedTestStatus, SystemMetrics, SystemMonitor};
+use crate::speedtest::NetworkSpeedTestManager;
+
+use super::prometheus::{
+    encode_gpus, encode_latency, encode_sensors, encode_speed_test, Exposition,
+};
+
+pub const DEFAULT_FILE_NAME: &str = "monitor_tray.prom";
+
+/// Origem do resultado de speed test publicado no arquivo.
+enum SpeedTestSource {
+    /// Executa um teste local a cada `interval`.
+    Local {
+        manager: NetworkSpeedTestManager,
+        interval: Duration,
+        last_started: Option<Instant>,
+    },
+    /// Repassa o último resultado do serviço DBus em execução.
+    Service(BackendProxy<'static>),
+    None,
+}
+
+impl SpeedTestSource {
+    async fn status(&mut self) -> Option<NetworkSpeedTestStatus> {
+        match self {
+            Self::Local {
+                manager,
+                interval,
+                last_started,
+            } => {
+                if last_started.is_none_or(|started| started.elapsed() >= *interval) {
+                    *last_started = Some(Instant::now());
+                    manager.start().await;
+                }
+                Some(manager.get_status().await)
+            }
+            Self::Service(proxy) => {
+                let payload = proxy.get_network_speed_test_status_json().await.ok()?;
+                serde_json::from_str(&payload).ok()
+            }
+            Self::None => None,
+        }
+    }
+}
+
+pub fn encode_textfile(
+    metrics: &SystemMetrics,
+    speed_test: Option<&NetworkSpeedTestStatus>,
+) -> String {
+    let mut exposition = Exposition::default();
+    encode_gpus(&mut exposition, &metrics.gpus);
+    encode_sensors(&mut exposition, &metrics.sensors);
+    encode_latency(&mut exposition, &metrics.network);
+    if let Some(status) = speed_test {
+        encode_speed_test(&mut exposition, status);
+    }
+    exposition.render()
+}
+
+/// Grava `content` em `path` via arquivo temporário + rename no mesmo diretório, para que
+/// o node_exporter nunca leia um arquivo pela metade. O temporário não termina em `.prom`
+/// e por isso é ignorado pelo coletor.
+pub fn write_atomically(path: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+    let temp_path = path.with_extension("prom.tmp");
+    std::fs::write(&temp_path, content)?;
+    std::fs::rename(&temp_path, path)
+}
+
+/// Regrava `directory/monitor_tray.prom` a cada `interval` até `Ctrl+C`, ou uma única vez com `once`.
+/// Com `speedtest_interval`, roda testes de velocidade locais; sem ele, publica o último
+/// resultado do serviço DBus quando houver um em execução.
+pub async fn run_textfile(
+    mut monitor: SystemMonitor,
+    directory: PathBuf,
+    interval: Duration,
+    once: bool,
+    speedtest_interval: Option<Duration>,
+) -> Result<(), Box<dyn Error>> {
+    let path = directory.join(DEFAULT_FILE_NAME);
+    let mut speed_test = match speedtest_interval {
+        Some(interval) => SpeedTestSource::Local {
+            manager: NetworkSpeedTestManager::new(),
+            interval,
+            last_started: None,
+        },
+        None => connect_backend()
+            .await
+            .map_or(SpeedTestSource::None, SpeedTestSource::Service),
+    };
+
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+
+        let metrics = collect_metrics(&mut monitor).await;
+        let status = speed_test.status().await;
+        write_atomically(&path, &encode_textfile(&metrics, status.as_ref()))
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+        if once {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::{NetworkSpeedTestPhase, NetworkSpeedTestState};
+    use crate::test_support::temp_fixture_dir;
+
+    #[test]
+    fn test_encode_textfile_skips_metrics_node_exporter_already_has() {
+        let mut metrics = SystemMonitor::new_empty().get_all_metrics();
+        metrics.network.gateway_latency_ms = Some(12.5);
+        metrics.network.gateway_ip = Some("192.168.0.1".to_string());
+        let status = NetworkSpeedTestStatus {
+            state: NetworkSpeedTestState::Success,
+            phase: NetworkSpeedTestPhase::Done,
+            download_mbps: Some(250.0),
+            upload_mbps: Some(40.0),
+            ping_ms: Some(8.0),
+            finished_at_unix_ms: Some(1_700_000_000_000),
+            ..NetworkSpeedTestStatus::default()
+        };
+
+        let output = encode_textfile(&metrics, Some(&status));
+
+        assert!(output
+            .contains("monitor_tray_gateway_latency_seconds{target=\"192.168.0.1\"} 0.0125\n"));
+        assert!(output.contains("monitor_tray_speedtest_download_bits_per_second 250000000\n"));
+        assert!(output.contains("monitor_tray_speedtest_upload_bits_per_second 40000000\n"));
+        assert!(output.contains("monitor_tray_speedtest_finished_timestamp_seconds 1700000000\n"));
+        assert!(!output.contains("monitor_tray_cpu_usage_percent"));
+        assert!(!output.contains("monitor_tray_network_receive_bytes_total"));
+    }
+
+    #[test]
+    fn test_failed_speed_test_reports_status_without_results() {
+        let metrics = SystemMonitor::new_empty().get_all_metrics();
+        let status = NetworkSpeedTestStatus {
+            state: NetworkSpeedTestState::Error,
+            download_mbps: Some(1.0),
+            ..NetworkSpeedTestStatus::default()
+        };
+
+        let output = encode_textfile(&metrics, Some(&status));
+
+        assert!(output.contains("monitor_tray_speedtest_success 0\n"));
+        assert!(output.contains("monitor_tray_speedtest_running 0\n"));
+        assert!(!output.contains("download"));
+        assert!(
+            !encode_textfile(&metrics, Some(&NetworkSpeedTestStatus::default()))
+                .contains("speedtest")
+        );
+    }
+
+    #[test]
+    fn test_write_atomically_replaces_file_without_leaving_temp() {
+        let directory = temp_fixture_dir();
+        let path = directory.join(DEFAULT_FILE_NAME);
+
+        write_atomically(&path, "a 1\n").expect("first write");
+        write_atomically(&path, "a 2\n").expect("second write");
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a 2\n");
+        let entries: Vec<_> = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, vec![std::ffi::OsString::from(DEFAULT_FILE_NAME)]);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,6 +1,17 @@
+pub mod alerts;
+pub mod cli;
+pub mod config;
 pub mod dbus;
+pub mod exporters;
 pub mod monitor;
+pub mod recorder;
 pub mod speedtest;
+mod subprocess;
+#[cfg(test)]
+mod test_support;
+pub mod tray;
+pub mod tui;
+mod unix_time;
 
 use std::error::Error;
 
@@ -12,6 +23,7 @@ use speedtest::NetworkSpeedTestManager;
 pub const DBUS_SERVICE_NAME: &str = "com.monitortray.Backend";
 pub const DBUS_OBJECT_PATH: &str = "/com/monitortray/Backend";
 pub const DBUS_INTERFACE_NAME: &str = "com.monitortray.Backend";
+pub const DBUS_TYPED_INTERFACE_NAME: &str = "com.monitortray.Backend2";
 
 pub async fn collect_metrics(monitor: &mut SystemMonitor) -> SystemMetrics {
     monitor.update_metrics().await;
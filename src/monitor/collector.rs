@@ -1,39 +1,35 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::path::Path;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 use sysinfo::{
     Components, DiskRefreshKind, Disks, Networks, ProcessRefreshKind, ProcessesToUpdate, System,
 };
 
+use super::cpufreq::{
+    read_core_frequencies_mhz, read_frequency_policy, read_throttle_counters, summarize_throttle,
+    throttled_cpus, ThrottleCounters, CPU_SYSFS_PATH,
+};
+use super::history_csv::history_csv;
+use super::history_store::{restore_history, save_history, unix_time_ms};
 use super::hwmon::{collect_hwmon_metrics_from_path, HWMON_BASE_PATH};
+use super::pressure::{collect_pressure, CGROUP_ROOT_PATH, PROC_PRESSURE_PATH};
+use super::rollup::HistoryRollups;
+use super::topology::{aggregate_core_classes, read_cpu_topology, CpuTopology, DEVICES_SYSFS_PATH};
 use super::{
-    CpuMetrics, DiskInfo, DiskMetrics, FastMetrics, GpuInfo, HistoryMetrics, HistorySeries,
-    MemoryMetrics, NetworkInterface, NetworkMetrics, ProcessInfo, SensorMetrics, SlowMetrics,
-    SystemInfo, SystemMetrics, TemperatureSensor,
+    CpuCoreMetrics, CpuFrequencyPolicy, CpuMetrics, CpuThrottleMetrics, DiskInfo, DiskMetrics,
+    FastMetrics, GpuInfo, HistoryMetrics, HistoryRange, HistoryRangeMetrics, HistorySeries,
+    MemoryMetrics, NetworkInterface, NetworkMetrics, PressureMetrics, PressureResource,
+    ProcessInfo, SensorMetrics, SlowMetrics, SystemInfo, SystemMetrics, TemperatureSensor,
 };
+use crate::config::MonitorConfig;
 
 const BYTES_TO_GB: f64 = 1024.0 * 1024.0 * 1024.0;
 const SECTOR_BYTES: f64 = 512.0;
-const TOP_PROCESSES: usize = 15;
-const HISTORY_DURATION_MS: u64 = 5 * 60 * 1000;
 const FAST_HISTORY_SAMPLE_INTERVAL_MS: u64 = 1000;
 const SENSOR_HISTORY_SAMPLE_INTERVAL_MS: u64 = 3000;
 const GPU_HISTORY_SAMPLE_INTERVAL_MS: u64 = 4500;
-/// Mede latência a cada N ciclos (~10s com sampleInterval de 1500ms).
-const LATENCY_INTERVAL_CYCLES: u32 = 7;
-/// Atualiza GPU com menor frequência para evitar scan de DRM e `nvidia-smi` em todo ciclo.
-const GPU_INTERVAL_CYCLES: u32 = 3;
-const GPU_MAX_AGE: Duration = Duration::from_millis(GPU_HISTORY_SAMPLE_INTERVAL_MS);
-/// Atualiza sensores com menor frequência, sem perder responsividade percebida.
-const SENSOR_INTERVAL_CYCLES: u32 = 2;
-const SENSOR_MAX_AGE: Duration = Duration::from_millis(SENSOR_HISTORY_SAMPLE_INTERVAL_MS);
-/// Atualiza processos com menor frequência, reduzindo custo de `/proc/<pid>`.
-const PROCESS_INTERVAL_CYCLES: u32 = 2;
-const PROCESS_MAX_AGE: Duration = Duration::from_millis(SENSOR_HISTORY_SAMPLE_INTERVAL_MS);
-/// Frequência muda pouco; não precisa ser atualizada em todo tick.
-const CPU_FREQUENCY_INTERVAL_CYCLES: u32 = 10;
 
 pub(crate) fn bytes_to_gb(bytes: u64) -> f64 {
     bytes as f64 / BYTES_TO_GB
@@ -53,6 +49,10 @@ pub(crate) struct CpuStatRaw {
     pub(crate) irq: u64,
     pub(crate) softirq: u64,
     pub(crate) steal: u64,
+    /// Já contado em `user`; o kernel soma o tempo de convidados nos dois campos.
+    pub(crate) guest: u64,
+    /// Já contado em `nice`.
+    pub(crate) guest_nice: u64,
 }
 
 impl CpuStatRaw {
@@ -65,48 +65,143 @@ impl CpuStatRaw {
     fn total_user(&self) -> u64 {
         self.user + self.nice
     }
+    fn total(&self) -> u64 {
+        self.total_user() + self.total_system() + self.total_idle() + self.steal
+    }
+
+    fn parse(fields: &[&str]) -> Option<Self> {
+        if fields.len() < 4 {
+            return None;
+        }
+        let field = |index: usize| {
+            fields
+                .get(index)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0)
+        };
+        Some(CpuStatRaw {
+            user: field(0),
+            nice: field(1),
+            system: field(2),
+            idle: field(3),
+            iowait: field(4),
+            irq: field(5),
+            softirq: field(6),
+            steal: field(7),
+            guest: field(8),
+            guest_nice: field(9),
+        })
+    }
 }
 
-fn read_cpu_stat_raw() -> Option<CpuStatRaw> {
-    let content = std::fs::read_to_string("/proc/stat").ok()?;
+/// Contadores de `/proc/stat`: a linha agregada `cpu` e uma por CPU lógica online (`cpuN`).
+#[derive(Clone)]
+pub(crate) struct CpuStatSnapshot {
+    pub(crate) total: CpuStatRaw,
+    pub(crate) cores: Vec<(usize, CpuStatRaw)>,
+}
+
+fn parse_cpu_stat(content: &str) -> Option<CpuStatSnapshot> {
+    let mut total = None;
+    let mut cores = Vec::new();
     for line in content.lines() {
-        if !line.starts_with("cpu ") {
+        let mut fields = line.split_whitespace();
+        let Some(label) = fields.next().and_then(|label| label.strip_prefix("cpu")) else {
             continue;
+        };
+        let fields: Vec<&str> = fields.collect();
+        if label.is_empty() {
+            total = CpuStatRaw::parse(&fields);
+        } else if let (Ok(id), Some(raw)) = (label.parse::<usize>(), CpuStatRaw::parse(&fields)) {
+            cores.push((id, raw));
         }
-        let p: Vec<&str> = line.split_whitespace().collect();
-        if p.len() < 5 {
-            return None;
-        }
-        return Some(CpuStatRaw {
-            user: p.get(1).and_then(|v| v.parse().ok()).unwrap_or(0),
-            nice: p.get(2).and_then(|v| v.parse().ok()).unwrap_or(0),
-            system: p.get(3).and_then(|v| v.parse().ok()).unwrap_or(0),
-            idle: p.get(4).and_then(|v| v.parse().ok()).unwrap_or(0),
-            iowait: p.get(5).and_then(|v| v.parse().ok()).unwrap_or(0),
-            irq: p.get(6).and_then(|v| v.parse().ok()).unwrap_or(0),
-            softirq: p.get(7).and_then(|v| v.parse().ok()).unwrap_or(0),
-            steal: p.get(8).and_then(|v| v.parse().ok()).unwrap_or(0),
-        });
     }
-    None
+    Some(CpuStatSnapshot {
+        total: total?,
+        cores,
+    })
+}
+
+fn read_cpu_stat_raw() -> Option<CpuStatSnapshot> {
+    parse_cpu_stat(&std::fs::read_to_string("/proc/stat").ok()?)
+}
+
+/// Fração do tempo de CPU em cada campo de `/proc/stat`. Os campos somam 100%:
+/// `user` e `nice` descontam o tempo de convidados, que aparece só em `guest`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct CpuTimePercents {
+    pub(crate) user: f32,
+    pub(crate) nice: f32,
+    pub(crate) system: f32,
+    pub(crate) idle: f32,
+    pub(crate) iowait: f32,
+    pub(crate) irq: f32,
+    pub(crate) softirq: f32,
+    pub(crate) steal: f32,
+    pub(crate) guest: f32,
 }
 
-fn compute_cpu_percents(prev: &CpuStatRaw, curr: &CpuStatRaw) -> (f32, f32, f32, f32) {
-    let d_user = curr.total_user().saturating_sub(prev.total_user());
-    let d_system = curr.total_system().saturating_sub(prev.total_system());
-    let d_idle = curr.total_idle().saturating_sub(prev.total_idle());
-    let d_steal = curr.steal.saturating_sub(prev.steal);
-    let d_total = d_user + d_system + d_idle + d_steal;
+fn compute_cpu_percents(prev: &CpuStatRaw, curr: &CpuStatRaw) -> CpuTimePercents {
+    let d_total = curr.total().saturating_sub(prev.total());
     if d_total == 0 {
-        return (0.0, 0.0, 100.0, 0.0);
+        return CpuTimePercents {
+            idle: 100.0,
+            ..CpuTimePercents::default()
+        };
     }
     let s = 100.0 / d_total as f32;
-    (
-        (d_user as f32 * s).min(100.0),
-        (d_system as f32 * s).min(100.0),
-        (d_idle as f32 * s).min(100.0),
-        (d_steal as f32 * s).min(100.0),
-    )
+    let percent =
+        |current: u64, previous: u64| (current.saturating_sub(previous) as f32 * s).min(100.0);
+    let d_guest = curr.guest.saturating_sub(prev.guest);
+    let d_guest_nice = curr.guest_nice.saturating_sub(prev.guest_nice);
+    CpuTimePercents {
+        user: percent(curr.user, prev.user + d_guest),
+        nice: percent(curr.nice, prev.nice + d_guest_nice),
+        system: percent(curr.system, prev.system),
+        idle: percent(curr.idle, prev.idle),
+        iowait: percent(curr.iowait, prev.iowait),
+        irq: percent(curr.irq, prev.irq),
+        softirq: percent(curr.softirq, prev.softirq),
+        steal: percent(curr.steal, prev.steal),
+        guest: ((d_guest + d_guest_nice) as f32 * s).min(100.0),
+    }
+}
+
+/// `hidden_sensors` para fans e sensores elétricos, cujo `label` já vem como `"chip: rótulo"`:
+/// aceita tanto o rótulo sozinho quanto a forma com o chip, como nas temperaturas.
+fn is_hwmon_sensor_hidden(config: &MonitorConfig, chip: &str, label: &str) -> bool {
+    let bare_label = label
+        .strip_prefix(chip)
+        .and_then(|rest| rest.strip_prefix(": "))
+        .unwrap_or(label);
+    config.is_sensor_hidden(Some(chip), bare_label)
+}
+
+/// Uso por CPU lógica entre dois snapshots; CPUs que ficaram online no meio são ignoradas.
+/// Os campos seguem a mesma divisão de [`compute_cpu_percents`] usada no agregado, e o uso
+/// é tudo que não é `idle` nem `iowait`.
+fn compute_core_metrics(prev: &CpuStatSnapshot, curr: &CpuStatSnapshot) -> Vec<CpuCoreMetrics> {
+    curr.cores
+        .iter()
+        .filter_map(|(id, curr)| {
+            let (_, prev) = prev.cores.iter().find(|(prev_id, _)| prev_id == id)?;
+            let times = compute_cpu_percents(prev, curr);
+            Some(CpuCoreMetrics {
+                id: *id,
+                usage_percent: (100.0 - times.idle - times.iowait).clamp(0.0, 100.0),
+                user_percent: times.user,
+                nice_percent: times.nice,
+                system_percent: times.system,
+                idle_percent: times.idle,
+                iowait_percent: times.iowait,
+                irq_percent: times.irq,
+                softirq_percent: times.softirq,
+                steal_percent: times.steal,
+                guest_percent: times.guest,
+                ..CpuCoreMetrics::default()
+            })
+        })
+        .collect()
 }
 
 // ---------------------------------------------------------------------------
@@ -240,13 +335,15 @@ async fn ping_host(host: &str) -> Option<f32> {
     None
 }
 
-async fn measure_gateway_latency() -> (Option<String>, Option<f32>) {
-    if let Some(gw) = read_default_gateway() {
-        let latency = ping_host(&gw).await;
-        (Some(gw), latency)
-    } else {
-        (None, None)
-    }
+/// Mede a latência até `target` ou, sem alvo configurado, até o gateway padrão.
+/// O IP do gateway é sempre reportado quando existe rota default.
+async fn measure_gateway_latency(target: Option<String>) -> (Option<String>, Option<f32>) {
+    let gateway = read_default_gateway();
+    let latency = match target.as_deref().or(gateway.as_deref()) {
+        Some(host) => ping_host(host).await,
+        None => None,
+    };
+    (gateway, latency)
 }
 
 fn process_refresh_kind_before() -> ProcessRefreshKind {
@@ -279,20 +376,54 @@ fn refresh_due_by_age(last_refresh: Option<Instant>, max_age: Duration) -> bool
         .unwrap_or(true)
 }
 
-fn history_capacity(sample_interval_ms: u64) -> usize {
+/// Um subsistema lento vence a cada `interval_cycles` ciclos ou, quando o ciclo atrasa
+/// (chamadas fora do atualizador), ao passar o mesmo tempo: `interval_cycles × refresh_interval`.
+fn slow_refresh_due(
+    counter: &mut u32,
+    interval_cycles: u32,
+    refresh_interval: Duration,
+    last_refresh: Option<Instant>,
+) -> bool {
+    if refresh_due_by_age(last_refresh, refresh_interval * interval_cycles.max(1)) {
+        *counter = 0;
+        return true;
+    }
+    should_refresh_every(counter, interval_cycles)
+}
+
+fn history_capacity(history_duration_ms: u64, sample_interval_ms: u64) -> usize {
     let interval = sample_interval_ms.max(1);
-    (((HISTORY_DURATION_MS + interval - 1) / interval).max(2)) as usize
+    history_duration_ms.div_ceil(interval).max(2) as usize
 }
 
-fn create_history_series(sample_interval_ms: u64) -> HistorySeries {
+fn create_history_series(history_duration_ms: u64, sample_interval_ms: u64) -> HistorySeries {
     HistorySeries {
-        buffer: vec![0.0; history_capacity(sample_interval_ms)],
+        buffer: vec![0.0; history_capacity(history_duration_ms, sample_interval_ms)],
         start: 0,
         count: 0,
         sample_interval_ms,
     }
 }
 
+/// Redimensiona o buffer circular para a nova janela, preservando as amostras mais recentes.
+fn resize_history_series(series: &mut HistorySeries, history_duration_ms: u64) {
+    let capacity = history_capacity(history_duration_ms, series.sample_interval_ms);
+    if capacity == series.buffer.len() {
+        return;
+    }
+
+    let ordered: Vec<f64> = (0..series.count)
+        .map(|offset| series.buffer[(series.start + offset) % series.buffer.len()])
+        .collect();
+    let kept = &ordered[ordered.len().saturating_sub(capacity)..];
+
+    let mut buffer = vec![0.0; capacity];
+    buffer[..kept.len()].copy_from_slice(kept);
+    series.buffer = buffer;
+    series.start = 0;
+    series.count = kept.len();
+}
+
 fn append_history_sample(series: &mut HistorySeries, value: f64) {
     if series.buffer.is_empty() {
         return;
@@ -309,10 +440,20 @@ fn append_history_sample(series: &mut HistorySeries, value: f64) {
     }
 }
 
-fn create_history_metrics() -> HistoryMetrics {
+fn create_history_metrics(history_duration_ms: u64) -> HistoryMetrics {
+    let create_history_series =
+        |sample_interval_ms| create_history_series(history_duration_ms, sample_interval_ms);
     HistoryMetrics {
-        history_duration_ms: HISTORY_DURATION_MS,
+        history_duration_ms,
         cpu_usage: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
+        cpu_user: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
+        cpu_nice: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
+        cpu_system: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
+        cpu_iowait: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
+        cpu_irq: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
+        cpu_softirq: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
+        cpu_steal: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
+        cpu_guest: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
         memory_usage: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
         gpu_usage: create_history_series(GPU_HISTORY_SAMPLE_INTERVAL_MS),
         disk_read: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
@@ -329,6 +470,11 @@ fn create_history_metrics() -> HistoryMetrics {
         system_load_5: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
         system_load_15: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
         system_process_count: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
+        pressure_cpu_some: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
+        pressure_memory_some: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
+        pressure_memory_full: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
+        pressure_io_some: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
+        pressure_io_full: create_history_series(FAST_HISTORY_SAMPLE_INTERVAL_MS),
     }
 }
 
@@ -336,21 +482,50 @@ fn create_history_metrics() -> HistoryMetrics {
 // SystemMonitor
 // ---------------------------------------------------------------------------
 
+/// Subsistemas do caminho lento que podem ser atualizados de forma independente.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlowSubsystems {
+    pub sensors: bool,
+    pub gpus: bool,
+    pub processes: bool,
+}
+
+impl SlowSubsystems {
+    pub const ALL: Self = Self {
+        sensors: true,
+        gpus: true,
+        processes: true,
+    };
+    pub const NONE: Self = Self {
+        sensors: false,
+        gpus: false,
+        processes: false,
+    };
+}
+
 pub struct SystemMonitor {
+    pub(crate) config: MonitorConfig,
     pub(crate) system: System,
     pub(crate) disks: Disks,
     pub(crate) networks: Networks,
     pub(crate) components: Components,
-    pub(crate) cpu_user_percent: f32,
-    pub(crate) cpu_system_percent: f32,
-    pub(crate) cpu_idle_percent: f32,
-    pub(crate) cpu_steal_percent: f32,
+    pub(crate) cpu_times: CpuTimePercents,
+    pub(crate) cpu_cores: Vec<CpuCoreMetrics>,
+    pub(crate) cpu_core_frequencies: HashMap<usize, u64>,
+    pub(crate) cpu_topology: HashMap<usize, CpuTopology>,
+    pub(crate) cpu_frequency_policy: CpuFrequencyPolicy,
+    pub(crate) cpu_throttle_counters: HashMap<usize, ThrottleCounters>,
+    pub(crate) throttled_cpus: Vec<usize>,
+    pub(crate) throttling_started: bool,
+    pub(crate) pressure: PressureMetrics,
+    pub(crate) last_pressure_at: Option<Instant>,
     pub(crate) disk_read_rates: HashMap<String, u64>,
     pub(crate) disk_write_rates: HashMap<String, u64>,
     pub(crate) network_download_rate: u64,
     pub(crate) network_upload_rate: u64,
     pub(crate) last_network_totals: Option<(u64, u64, Instant)>,
     pub(crate) history: HistoryMetrics,
+    pub(crate) rollups: HistoryRollups,
     pub(crate) cached_gpus: Vec<GpuInfo>,
     pub(crate) cached_sensors: Option<SensorMetrics>,
     pub(crate) cached_top_processes: Option<Vec<ProcessInfo>>,
@@ -375,21 +550,30 @@ impl Default for SystemMonitor {
 #[cfg(test)]
 impl SystemMonitor {
     pub(crate) fn new_empty() -> Self {
+        let config = MonitorConfig::default();
         Self {
+            history: create_history_metrics(config.history_duration_ms),
+            rollups: HistoryRollups::default(),
+            config,
             system: System::new(),
             disks: Disks::new_with_refreshed_list(),
             networks: Networks::new_with_refreshed_list(),
             components: Components::new_with_refreshed_list(),
-            cpu_user_percent: 0.0,
-            cpu_system_percent: 0.0,
-            cpu_idle_percent: 0.0,
-            cpu_steal_percent: 0.0,
+            cpu_times: CpuTimePercents::default(),
+            cpu_cores: Vec::new(),
+            cpu_core_frequencies: HashMap::new(),
+            cpu_topology: HashMap::new(),
+            cpu_frequency_policy: CpuFrequencyPolicy::default(),
+            cpu_throttle_counters: HashMap::new(),
+            throttled_cpus: Vec::new(),
+            throttling_started: false,
+            pressure: PressureMetrics::default(),
+            last_pressure_at: None,
             disk_read_rates: HashMap::new(),
             disk_write_rates: HashMap::new(),
             network_download_rate: 0,
             network_upload_rate: 0,
             last_network_totals: None,
-            history: create_history_metrics(),
             cached_gpus: vec![],
             cached_sensors: None,
             cached_top_processes: None,
@@ -409,23 +593,38 @@ impl SystemMonitor {
 
 impl SystemMonitor {
     pub fn new() -> Self {
+        Self::with_config(MonitorConfig::default())
+    }
+
+    pub fn with_config(config: MonitorConfig) -> Self {
         let mut system = System::new_all();
         system.refresh_all();
         Self {
+            history: create_history_metrics(config.history_duration_ms),
+            rollups: HistoryRollups::default(),
+            config,
             system,
             disks: Disks::new_with_refreshed_list(),
             networks: Networks::new_with_refreshed_list(),
             components: Components::new_with_refreshed_list(),
-            cpu_user_percent: 0.0,
-            cpu_system_percent: 0.0,
-            cpu_idle_percent: 0.0,
-            cpu_steal_percent: 0.0,
+            cpu_times: CpuTimePercents::default(),
+            cpu_cores: Vec::new(),
+            cpu_core_frequencies: HashMap::new(),
+            cpu_topology: read_cpu_topology(
+                Path::new(CPU_SYSFS_PATH),
+                Path::new(DEVICES_SYSFS_PATH),
+            ),
+            cpu_frequency_policy: read_frequency_policy(Path::new(CPU_SYSFS_PATH)),
+            cpu_throttle_counters: read_throttle_counters(Path::new(CPU_SYSFS_PATH)),
+            throttled_cpus: Vec::new(),
+            throttling_started: false,
+            pressure: PressureMetrics::default(),
+            last_pressure_at: None,
             disk_read_rates: HashMap::new(),
             disk_write_rates: HashMap::new(),
             network_download_rate: 0,
             network_upload_rate: 0,
             last_network_totals: None,
-            history: create_history_metrics(),
             cached_gpus: vec![],
             cached_sensors: None,
             cached_top_processes: None,
@@ -452,6 +651,18 @@ impl SystemMonitor {
             &mut self.history.cpu_usage,
             cpu.usage_percent.clamp(0.0, 100.0) as f64,
         );
+        for (series, percent) in [
+            (&mut self.history.cpu_user, cpu.user_percent),
+            (&mut self.history.cpu_nice, cpu.nice_percent),
+            (&mut self.history.cpu_system, cpu.system_percent),
+            (&mut self.history.cpu_iowait, cpu.iowait_percent),
+            (&mut self.history.cpu_irq, cpu.irq_percent),
+            (&mut self.history.cpu_softirq, cpu.softirq_percent),
+            (&mut self.history.cpu_steal, cpu.steal_percent),
+            (&mut self.history.cpu_guest, cpu.guest_percent),
+        ] {
+            append_history_sample(series, percent.clamp(0.0, 100.0) as f64);
+        }
         append_history_sample(
             &mut self.history.memory_usage,
             memory.usage_percent.clamp(0.0, 100.0) as f64,
@@ -482,6 +693,36 @@ impl SystemMonitor {
             &mut self.history.system_process_count,
             self.system.processes().len() as f64,
         );
+        let some = |resource: &Option<PressureResource>| {
+            resource
+                .as_ref()
+                .map_or(0.0, |resource| resource.some.stall_percent)
+        };
+        let full = |resource: &Option<PressureResource>| {
+            resource
+                .as_ref()
+                .and_then(|resource| resource.full.as_ref())
+                .map_or(0.0, |full| full.stall_percent)
+        };
+        for (series, percent) in [
+            (
+                &mut self.history.pressure_cpu_some,
+                some(&self.pressure.cpu),
+            ),
+            (
+                &mut self.history.pressure_memory_some,
+                some(&self.pressure.memory),
+            ),
+            (
+                &mut self.history.pressure_memory_full,
+                full(&self.pressure.memory),
+            ),
+            (&mut self.history.pressure_io_some, some(&self.pressure.io)),
+            (&mut self.history.pressure_io_full, full(&self.pressure.io)),
+        ] {
+            append_history_sample(series, percent.clamp(0.0, 100.0) as f64);
+        }
+        self.roll_up_latest_samples(FAST_HISTORY_SAMPLE_INTERVAL_MS);
     }
 
     fn record_sensor_history(&mut self) {
@@ -522,6 +763,7 @@ impl SystemMonitor {
             &mut self.history.sensor_total_power_watts,
             total_power_watts,
         );
+        self.roll_up_latest_samples(SENSOR_HISTORY_SAMPLE_INTERVAL_MS);
     }
 
     fn record_gpu_history(&mut self) {
@@ -532,21 +774,98 @@ impl SystemMonitor {
             .unwrap_or(0.0)
             .clamp(0.0, 100.0);
         append_history_sample(&mut self.history.gpu_usage, usage_percent as f64);
+        self.roll_up_latest_samples(GPU_HISTORY_SAMPLE_INTERVAL_MS);
+    }
+
+    /// Repassa aos agregados de longo prazo a última amostra de cada série
+    /// amostrada em `sample_interval_ms`, isto é, as que acabaram de ser registradas.
+    fn roll_up_latest_samples(&mut self, sample_interval_ms: u64) {
+        let now_ms = unix_time_ms(SystemTime::now());
+        for (name, series) in self.history.series() {
+            if series.sample_interval_ms != sample_interval_ms || series.count == 0 {
+                continue;
+            }
+            let latest = series.buffer[(series.start + series.count - 1) % series.buffer.len()];
+            self.rollups.record(name, latest, now_ms);
+        }
+    }
+
+    pub fn config(&self) -> &MonitorConfig {
+        &self.config
+    }
+
+    /// Aplica uma nova configuração sem perder o histórico acumulado.
+    /// Os buffers são redimensionados quando a janela de histórico muda.
+    pub fn apply_config(&mut self, config: MonitorConfig) {
+        if config.hidden_sensors != self.config.hidden_sensors {
+            self.cached_sensors = None;
+        }
+        if config.top_processes != self.config.top_processes {
+            self.cached_top_processes = None;
+        }
+        if config.latency_target != self.config.latency_target {
+            self.latency_cycle = 0;
+            self.cached_gateway_latency_ms = None;
+        }
+        if config.history_duration_ms != self.history.history_duration_ms {
+            self.history.history_duration_ms = config.history_duration_ms;
+            for (_, series) in self.history.series_mut() {
+                resize_history_series(series, config.history_duration_ms);
+            }
+        }
+        self.config = config;
+    }
+
+    /// Grava um checkpoint do histórico em `path` para ser restaurado na próxima inicialização.
+    pub fn save_history(&self, path: &Path) -> std::io::Result<()> {
+        save_history(&self.history, &self.rollups, path)
+    }
+
+    /// Restaura o histórico gravado por [`SystemMonitor::save_history`] em `path`.
+    /// Arquivo ausente não é erro: o histórico continua vazio.
+    pub fn restore_history(&mut self, path: &Path) -> std::io::Result<()> {
+        restore_history(&mut self.history, &mut self.rollups, path)
+    }
+
+    /// Agregados min/média/máx de `series` na janela `range`;
+    /// `None` quando `series` não é uma série do histórico.
+    pub fn get_history_range(
+        &self,
+        series: &str,
+        range: HistoryRange,
+    ) -> Option<HistoryRangeMetrics> {
+        self.history
+            .series()
+            .iter()
+            .any(|(name, _)| *name == series)
+            .then(|| {
+                self.rollups
+                    .range(series, range, unix_time_ms(SystemTime::now()))
+            })
     }
 
     pub fn get_history_metrics(&self) -> HistoryMetrics {
         self.history.clone()
     }
 
+    /// CSV das séries `series` (todas quando vazio), com a amostra mais recente agora.
+    pub fn get_history_csv(&self, series: &[&str]) -> Result<String, String> {
+        history_csv(&self.history, series, unix_time_ms(SystemTime::now()))
+    }
+
     /// Atualiza apenas as métricas rápidas do ciclo quente.
     pub async fn update_fast_metrics(&mut self) {
         let refresh_latency =
-            should_refresh_every(&mut self.latency_cycle, LATENCY_INTERVAL_CYCLES);
-        let refresh_cpu_frequency =
-            should_refresh_every(&mut self.cpu_frequency_cycle, CPU_FREQUENCY_INTERVAL_CYCLES);
+            should_refresh_every(&mut self.latency_cycle, self.config.latency_interval_cycles);
+        let refresh_cpu_frequency = should_refresh_every(
+            &mut self.cpu_frequency_cycle,
+            self.config.cpu_frequency_interval_cycles,
+        );
 
         let ping_task = if refresh_latency {
-            Some(tokio::spawn(measure_gateway_latency()))
+            Some(tokio::spawn(measure_gateway_latency(
+                self.config.latency_target.clone(),
+            )))
         } else {
             None
         };
@@ -563,6 +882,11 @@ impl SystemMonitor {
         self.system.refresh_cpu_usage();
         if refresh_cpu_frequency {
             self.system.refresh_cpu_frequency();
+            self.cpu_core_frequencies = read_core_frequencies_mhz(Path::new(CPU_SYSFS_PATH));
+            // Relida junto com a frequência para acompanhar CPUs que entram ou saem de linha.
+            self.cpu_topology =
+                read_cpu_topology(Path::new(CPU_SYSFS_PATH), Path::new(DEVICES_SYSFS_PATH));
+            self.cpu_frequency_policy = read_frequency_policy(Path::new(CPU_SYSFS_PATH));
         }
         self.system.refresh_memory();
         self.disks
@@ -601,13 +925,29 @@ impl SystemMonitor {
         ));
 
         if let (Some(b), Some(a)) = (cpu_stat_before, read_cpu_stat_raw()) {
-            let (user, system, idle, steal) = compute_cpu_percents(&b, &a);
-            self.cpu_user_percent = user;
-            self.cpu_system_percent = system;
-            self.cpu_idle_percent = idle;
-            self.cpu_steal_percent = steal;
+            self.cpu_times = compute_cpu_percents(&b.total, &a.total);
+            self.cpu_cores = compute_core_metrics(&b, &a);
         }
 
+        let throttle_counters = read_throttle_counters(Path::new(CPU_SYSFS_PATH));
+        let was_throttling = !self.throttled_cpus.is_empty();
+        self.throttled_cpus = throttled_cpus(&self.cpu_throttle_counters, &throttle_counters);
+        self.cpu_throttle_counters = throttle_counters;
+        self.throttling_started |= !was_throttling && !self.throttled_cpus.is_empty();
+
+        let pressure_at = Instant::now();
+        let previous_pressure = self.last_pressure_at.map(|at| (&self.pressure, at));
+        self.pressure = collect_pressure(
+            Path::new(PROC_PRESSURE_PATH),
+            Path::new(CGROUP_ROOT_PATH),
+            self.config.pressure_cgroup.as_deref(),
+            previous_pressure.map(|(pressure, _)| pressure),
+            previous_pressure
+                .map(|(_, at)| pressure_at.duration_since(at))
+                .unwrap_or_default(),
+        );
+        self.last_pressure_at = Some(pressure_at);
+
         let (read_rates, write_rates) =
             compute_disk_io_rates(&disk_io_before, &read_diskstats(), elapsed_secs);
         self.disk_read_rates = read_rates;
@@ -623,19 +963,47 @@ impl SystemMonitor {
     }
 
     /// Atualiza métricas mais lentas, com opção de forçar refresh no caminho split do frontend.
-    pub async fn refresh_slow_metrics(&mut self, force: bool) {
-        let refresh_gpus = force
-            || self.cached_gpus.is_empty()
-            || refresh_due_by_age(self.last_gpu_refresh, GPU_MAX_AGE)
-            || should_refresh_every(&mut self.gpu_cycle, GPU_INTERVAL_CYCLES);
-        let refresh_sensors = force
-            || self.cached_sensors.is_none()
-            || refresh_due_by_age(self.last_sensor_refresh, SENSOR_MAX_AGE)
-            || should_refresh_every(&mut self.sensor_cycle, SENSOR_INTERVAL_CYCLES);
-        let refresh_processes = force
-            || self.cached_top_processes.is_none()
-            || refresh_due_by_age(self.last_process_refresh, PROCESS_MAX_AGE)
-            || should_refresh_every(&mut self.process_cycle, PROCESS_INTERVAL_CYCLES);
+    /// Retorna `true` quando algum subsistema lento foi de fato atualizado.
+    pub async fn refresh_slow_metrics(&mut self, force: bool) -> bool {
+        self.refresh_slow_subsystems(force, SlowSubsystems::ALL)
+            .await
+    }
+
+    /// Igual a [`SystemMonitor::refresh_slow_metrics`], mas restrito a `subsystems`;
+    /// os demais não são tocados, evitando por exemplo o probe de GPU quando só a CPU interessa.
+    pub async fn refresh_slow_subsystems(
+        &mut self,
+        force: bool,
+        subsystems: SlowSubsystems,
+    ) -> bool {
+        let refresh_interval = self.config.refresh_interval();
+        let refresh_gpus = subsystems.gpus
+            && (force
+                || self.cached_gpus.is_empty()
+                || slow_refresh_due(
+                    &mut self.gpu_cycle,
+                    self.config.gpu_interval_cycles,
+                    refresh_interval,
+                    self.last_gpu_refresh,
+                ));
+        let refresh_sensors = subsystems.sensors
+            && (force
+                || self.cached_sensors.is_none()
+                || slow_refresh_due(
+                    &mut self.sensor_cycle,
+                    self.config.sensor_interval_cycles,
+                    refresh_interval,
+                    self.last_sensor_refresh,
+                ));
+        let refresh_processes = subsystems.processes
+            && (force
+                || self.cached_top_processes.is_none()
+                || slow_refresh_due(
+                    &mut self.process_cycle,
+                    self.config.process_interval_cycles,
+                    refresh_interval,
+                    self.last_process_refresh,
+                ));
 
         if refresh_processes {
             self.system.refresh_processes_specifics(
@@ -665,6 +1033,8 @@ impl SystemMonitor {
             self.last_gpu_refresh = Some(Instant::now());
             self.record_gpu_history();
         }
+
+        refresh_processes || refresh_sensors || refresh_gpus
     }
 
     /// Atualiza todas as métricas.
@@ -684,12 +1054,49 @@ impl SystemMonitor {
         } else {
             0.0
         };
+        let cores: Vec<CpuCoreMetrics> = self
+            .cpu_cores
+            .iter()
+            .map(|core| {
+                let topology = self.cpu_topology.get(&core.id).cloned().unwrap_or_default();
+                let throttle = self.cpu_throttle_counters.get(&core.id);
+                // A lista do sysinfo omite CPUs offline; o índice nela não é o `N` de `cpuN`.
+                let name = format!("cpu{}", core.id);
+                CpuCoreMetrics {
+                    frequency_mhz: self
+                        .cpu_core_frequencies
+                        .get(&core.id)
+                        .copied()
+                        .or_else(|| {
+                            cpus.iter()
+                                .find(|cpu| cpu.name() == name)
+                                .map(|cpu| cpu.frequency())
+                        })
+                        .unwrap_or(0),
+                    core_type: topology.core_type,
+                    core_id: topology.core_id,
+                    package_id: topology.package_id,
+                    cluster_id: topology.cluster_id,
+                    smt_siblings: topology.smt_siblings,
+                    core_throttle_count: throttle.and_then(|counters| counters.core),
+                    package_throttle_count: throttle.and_then(|counters| counters.package),
+                    ..core.clone()
+                }
+            })
+            .collect();
+        let core_classes = aggregate_core_classes(&cores);
+        let throttle = summarize_throttle(&cores, &self.throttled_cpus);
         CpuMetrics {
             usage_percent: total_usage,
-            user_percent: self.cpu_user_percent,
-            system_percent: self.cpu_system_percent,
-            idle_percent: self.cpu_idle_percent,
-            steal_percent: self.cpu_steal_percent,
+            user_percent: self.cpu_times.user,
+            system_percent: self.cpu_times.system,
+            idle_percent: self.cpu_times.idle,
+            steal_percent: self.cpu_times.steal,
+            iowait_percent: self.cpu_times.iowait,
+            irq_percent: self.cpu_times.irq,
+            softirq_percent: self.cpu_times.softirq,
+            nice_percent: self.cpu_times.nice,
+            guest_percent: self.cpu_times.guest,
             core_count,
             per_core_usage,
             frequency: cpus.first().map(|c| c.frequency()).unwrap_or(0),
@@ -697,9 +1104,19 @@ impl SystemMonitor {
                 .first()
                 .map(|c| c.brand().to_string())
                 .unwrap_or_default(),
+            cores,
+            core_classes,
+            frequency_policy: self.cpu_frequency_policy.clone(),
+            throttle,
         }
     }
 
+    /// Throttling que começou desde a última chamada: os contadores de `thermal_throttle`
+    /// subiram numa atualização depois de outra em que estavam parados.
+    pub fn take_throttling_started(&mut self) -> Option<CpuThrottleMetrics> {
+        std::mem::take(&mut self.throttling_started).then(|| self.get_cpu_metrics().throttle)
+    }
+
     pub fn get_memory_metrics(&self) -> MemoryMetrics {
         let total_memory = bytes_to_gb(self.system.total_memory());
         let used_memory = bytes_to_gb(self.system.used_memory());
@@ -766,11 +1183,25 @@ impl SystemMonitor {
             total_bytes_transmitted,
             gateway_ip: self.cached_gateway_ip.clone(),
             gateway_latency_ms: self.cached_gateway_latency_ms,
+            latency_target: self.config.latency_target.clone(),
         }
     }
 
     fn collect_sensor_metrics(&self) -> SensorMetrics {
-        let hwmon = collect_hwmon_metrics_from_path(Path::new(HWMON_BASE_PATH));
+        let mut hwmon = collect_hwmon_metrics_from_path(Path::new(HWMON_BASE_PATH));
+        let config = &self.config;
+        hwmon
+            .fans
+            .retain(|sensor| !is_hwmon_sensor_hidden(config, &sensor.chip, &sensor.label));
+        hwmon
+            .voltages
+            .retain(|sensor| !is_hwmon_sensor_hidden(config, &sensor.chip, &sensor.label));
+        hwmon
+            .currents
+            .retain(|sensor| !is_hwmon_sensor_hidden(config, &sensor.chip, &sensor.label));
+        hwmon
+            .powers
+            .retain(|sensor| !is_hwmon_sensor_hidden(config, &sensor.chip, &sensor.label));
         let (hwmon_temps, fans, voltages, currents, powers) = (
             hwmon.temperatures,
             hwmon.fans,
@@ -804,6 +1235,10 @@ impl SystemMonitor {
                 })
                 .collect()
         };
+        let temperatures: Vec<TemperatureSensor> = temperatures
+            .into_iter()
+            .filter(|sensor| !config.is_sensor_hidden(Some(&sensor.chip), &sensor.label))
+            .collect();
 
         let average_temperature_celsius = if temperatures.is_empty() {
             None
@@ -890,11 +1325,11 @@ impl SystemMonitor {
                 .partial_cmp(&a.cpu_percent)
                 .unwrap_or(Ordering::Equal)
         });
-        procs.truncate(TOP_PROCESSES);
+        procs.truncate(self.config.top_processes);
         procs
     }
 
-    /// Retorna os TOP_PROCESSES processos com maior uso de CPU,
+    /// Retorna os `top_processes` processos com maior uso de CPU,
     /// normalizado pelo número de cores (0-100% do total do sistema).
     pub fn get_top_processes(&self) -> Vec<ProcessInfo> {
         self.cached_top_processes
@@ -902,7 +1337,7 @@ impl SystemMonitor {
             .unwrap_or_else(|| self.collect_top_processes())
     }
 
-    fn build_system_info(&self) -> SystemInfo {
+    pub fn get_system_info(&self) -> SystemInfo {
         SystemInfo {
             hostname: System::host_name().unwrap_or_else(|| "unknown".to_string()),
             os_name: System::name().unwrap_or_else(|| "Linux".to_string()),
@@ -913,6 +1348,15 @@ impl SystemMonitor {
         }
     }
 
+    /// GPUs da última atualização do caminho lento, sem disparar um novo probe.
+    pub fn get_gpus(&self) -> Vec<GpuInfo> {
+        self.cached_gpus.clone()
+    }
+
+    pub fn get_pressure_metrics(&self) -> PressureMetrics {
+        self.pressure.clone()
+    }
+
     pub fn get_fast_metrics(&self) -> FastMetrics {
         let load_average = System::load_average();
         FastMetrics {
@@ -922,33 +1366,21 @@ impl SystemMonitor {
             network: self.get_network_metrics(),
             uptime: System::uptime(),
             load_average: (load_average.one, load_average.five, load_average.fifteen),
+            pressure: self.get_pressure_metrics(),
         }
     }
 
     pub fn get_slow_metrics(&self) -> SlowMetrics {
         SlowMetrics {
             sensors: self.get_sensor_metrics(),
-            gpus: self.cached_gpus.clone(),
+            gpus: self.get_gpus(),
             top_processes: self.get_top_processes(),
-            system_info: self.build_system_info(),
+            system_info: self.get_system_info(),
         }
     }
 
     pub fn get_all_metrics(&self) -> SystemMetrics {
-        let fast = self.get_fast_metrics();
-        let slow = self.get_slow_metrics();
-        SystemMetrics {
-            cpu: fast.cpu,
-            memory: fast.memory,
-            disk: fast.disk,
-            network: fast.network,
-            sensors: slow.sensors,
-            gpus: slow.gpus,
-            top_processes: slow.top_processes,
-            system_info: slow.system_info,
-            uptime: fast.uptime,
-            load_average: fast.load_average,
-        }
+        SystemMetrics::from_parts(self.get_fast_metrics(), self.get_slow_metrics())
     }
 }
 
@@ -964,6 +1396,65 @@ mod tests {
         assert_eq!(device_basename(OsStr::new("")), "");
     }
 
+    #[test]
+    fn test_slow_refresh_due_respeita_intervalo_configurado() {
+        let refresh_interval = Duration::from_secs(2);
+        let mut counter = 0;
+        // 6 s desde a última coleta: passaria das idades fixas antigas (3 s e 4,5 s),
+        // mas ainda não cumpre 5 ciclos × 2 s.
+        let last_refresh = Instant::now().checked_sub(Duration::from_secs(6));
+        let due: Vec<bool> = (0..5)
+            .map(|_| slow_refresh_due(&mut counter, 5, refresh_interval, last_refresh))
+            .collect();
+        assert_eq!(due, vec![false, false, false, false, true]);
+
+        // Chamadas esparsas ainda atualizam quando a idade passa do intervalo configurado.
+        let stale = Instant::now().checked_sub(Duration::from_secs(11));
+        assert!(slow_refresh_due(&mut counter, 5, refresh_interval, stale));
+        assert!(slow_refresh_due(&mut counter, 5, refresh_interval, None));
+    }
+
+    #[test]
+    fn test_is_hwmon_sensor_hidden_aceita_rotulo_com_ou_sem_chip() {
+        let config = MonitorConfig {
+            hidden_sensors: vec!["Fan 2".to_string(), "nct6798: CPU Fan".to_string()],
+            ..MonitorConfig::default()
+        };
+
+        assert!(is_hwmon_sensor_hidden(&config, "nct6798", "nct6798: Fan 2"));
+        assert!(is_hwmon_sensor_hidden(
+            &config,
+            "nct6798",
+            "nct6798: CPU Fan"
+        ));
+        assert!(!is_hwmon_sensor_hidden(
+            &config,
+            "it8688",
+            "it8688: CPU Fan"
+        ));
+        assert!(!is_hwmon_sensor_hidden(
+            &config,
+            "nct6798",
+            "nct6798: Fan 1"
+        ));
+    }
+
+    #[test]
+    fn test_apply_config_descarta_top_processes_ao_mudar_quantidade() {
+        let mut monitor = SystemMonitor::new_empty();
+        monitor.cached_top_processes = Some(Vec::new());
+
+        monitor.apply_config(monitor.config().clone());
+        assert!(monitor.cached_top_processes.is_some());
+
+        let config = MonitorConfig {
+            top_processes: monitor.config().top_processes + 1,
+            ..monitor.config().clone()
+        };
+        monitor.apply_config(config);
+        assert!(monitor.cached_top_processes.is_none());
+    }
+
     #[test]
     fn test_compute_cpu_percents_distribui_corretamente() {
         let prev = CpuStatRaw {
@@ -975,6 +1466,8 @@ mod tests {
             irq: 0,
             softirq: 0,
             steal: 0,
+            guest: 0,
+            guest_nice: 0,
         };
         let curr = CpuStatRaw {
             user: 40,
@@ -985,12 +1478,15 @@ mod tests {
             irq: 0,
             softirq: 0,
             steal: 0,
+            guest: 0,
+            guest_nice: 0,
         };
-        let (user, system, idle, steal) = compute_cpu_percents(&prev, &curr);
-        assert!((user - 50.0).abs() < 0.1);
-        assert!((system - 20.0).abs() < 0.1);
-        assert!((idle - 30.0).abs() < 0.1);
-        assert!((steal - 0.0).abs() < 0.1);
+        let percents = compute_cpu_percents(&prev, &curr);
+        assert!((percents.user - 40.0).abs() < 0.1);
+        assert!((percents.nice - 10.0).abs() < 0.1);
+        assert!((percents.system - 20.0).abs() < 0.1);
+        assert!((percents.idle - 30.0).abs() < 0.1);
+        assert!((percents.steal - 0.0).abs() < 0.1);
     }
 
     #[test]
@@ -1004,10 +1500,61 @@ mod tests {
             irq: 0,
             softirq: 0,
             steal: 0,
+            guest: 0,
+            guest_nice: 0,
         };
-        let (_, _, idle, steal) = compute_cpu_percents(&snap, &snap);
-        assert_eq!(idle, 100.0);
-        assert_eq!(steal, 0.0);
+        let percents = compute_cpu_percents(&snap, &snap);
+        assert_eq!(percents.idle, 100.0);
+        assert_eq!(percents.steal, 0.0);
+    }
+
+    #[test]
+    fn test_parse_cpu_stat_le_linhas_por_cpu() {
+        let content = "cpu  10 0 5 100 2 0 0 0 0 0\n\
+                       cpu0 6 0 3 50 1 0 0 0 0 0\n\
+                       cpu2 4 0 2 50 1 0 0 0\n\
+                       intr 12345\n\
+                       cpufoo 1 2 3 4\n";
+        let snapshot = parse_cpu_stat(content).expect("linha agregada presente");
+        assert_eq!(snapshot.total.user, 10);
+        assert_eq!(snapshot.total.iowait, 2);
+        let ids: Vec<usize> = snapshot.cores.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![0, 2]);
+        assert_eq!(snapshot.cores[1].1.idle, 50);
+        assert!(parse_cpu_stat("cpu0 1 2 3 4\n").is_none());
+    }
+
+    #[test]
+    fn test_compute_core_metrics_separa_campos_como_no_agregado() {
+        let prev = parse_cpu_stat("cpu 0 0 0 0\ncpu0 0 0 0 0 0 0 0 0 0 0\ncpu1 0 0 0 0\n").unwrap();
+        let curr = parse_cpu_stat(
+            "cpu 0 0 0 0\ncpu0 30 10 10 20 10 5 5 10 10 0\ncpu1 0 0 0 100\ncpu3 50 0 0 50\n",
+        )
+        .unwrap();
+        let cores = compute_core_metrics(&prev, &curr);
+        assert_eq!(cores.len(), 2, "cpu3 sem amostra anterior é ignorada");
+
+        let cpu0 = &cores[0];
+        assert_eq!(cpu0.id, 0);
+        let fields = [
+            (cpu0.user_percent, 20.0),
+            (cpu0.nice_percent, 10.0),
+            (cpu0.system_percent, 10.0),
+            (cpu0.idle_percent, 20.0),
+            (cpu0.iowait_percent, 10.0),
+            (cpu0.irq_percent, 5.0),
+            (cpu0.softirq_percent, 5.0),
+            (cpu0.steal_percent, 10.0),
+            (cpu0.guest_percent, 10.0),
+        ];
+        for (value, expected) in fields {
+            assert!((value - expected).abs() < 0.01, "{value} != {expected}");
+        }
+        let sum: f32 = fields.iter().map(|(value, _)| value).sum();
+        assert!((sum - 100.0).abs() < 0.01);
+        assert!((cpu0.usage_percent - 70.0).abs() < 0.01);
+
+        assert_eq!(cores[1].usage_percent, 0.0);
     }
 
     #[test]
@@ -1021,6 +1568,8 @@ mod tests {
             irq: 0,
             softirq: 0,
             steal: 0,
+            guest: 0,
+            guest_nice: 0,
         };
         let curr = CpuStatRaw {
             user: 25,
@@ -1031,11 +1580,40 @@ mod tests {
             irq: 0,
             softirq: 0,
             steal: 25,
+            guest: 0,
+            guest_nice: 0,
         };
-        let (user, system, idle, steal) = compute_cpu_percents(&prev, &curr);
-        assert!((user - 25.0).abs() < 0.1);
-        assert!((steal - 25.0).abs() < 0.1);
-        let _ = (system, idle);
+        let percents = compute_cpu_percents(&prev, &curr);
+        assert!((percents.user - 25.0).abs() < 0.1);
+        assert!((percents.steal - 25.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_compute_cpu_percents_separa_iowait_irq_softirq_e_guest() {
+        let prev = parse_cpu_stat("cpu 100 20 50 500 10 5 5 0 40 10\n").unwrap();
+        let curr = parse_cpu_stat("cpu 150 30 60 520 20 10 15 0 60 15\n").unwrap();
+        let percents = compute_cpu_percents(&prev.total, &curr.total);
+
+        // Δtotal = 50 + 10 + 10 + 20 + 10 + 5 + 10 = 115; o guest já está em user/nice.
+        let expected = |ticks: f32| ticks * 100.0 / 115.0;
+        assert!((percents.user - expected(30.0)).abs() < 0.01);
+        assert!((percents.nice - expected(5.0)).abs() < 0.01);
+        assert!((percents.guest - expected(25.0)).abs() < 0.01);
+        assert!((percents.system - expected(10.0)).abs() < 0.01);
+        assert!((percents.idle - expected(20.0)).abs() < 0.01);
+        assert!((percents.iowait - expected(10.0)).abs() < 0.01);
+        assert!((percents.irq - expected(5.0)).abs() < 0.01);
+        assert!((percents.softirq - expected(10.0)).abs() < 0.01);
+        let sum = percents.user
+            + percents.nice
+            + percents.guest
+            + percents.system
+            + percents.idle
+            + percents.iowait
+            + percents.irq
+            + percents.softirq
+            + percents.steal;
+        assert!((sum - 100.0).abs() < 0.01);
     }
 
     #[test]
@@ -1060,7 +1638,7 @@ mod tests {
 
     #[test]
     fn test_history_series_append_rotaciona_buffer_circular() {
-        let mut series = create_history_series(1000);
+        let mut series = create_history_series(5 * 60 * 1000, 1000);
         series.buffer = vec![0.0; 3];
 
         append_history_sample(&mut series, 10.0);
@@ -1075,9 +1653,9 @@ mod tests {
 
     #[test]
     fn test_create_history_metrics_usa_janela_de_cinco_minutos() {
-        let history = create_history_metrics();
+        let history = create_history_metrics(MonitorConfig::default().history_duration_ms);
 
-        assert_eq!(history.history_duration_ms, HISTORY_DURATION_MS);
+        assert_eq!(history.history_duration_ms, 5 * 60 * 1000);
         assert_eq!(
             history.cpu_usage.sample_interval_ms,
             FAST_HISTORY_SAMPLE_INTERVAL_MS
@@ -1092,4 +1670,24 @@ mod tests {
         );
         assert!(history.cpu_usage.buffer.len() >= 2);
     }
+
+    #[test]
+    fn test_resize_history_series_preserva_amostras_mais_recentes() {
+        let mut series = create_history_series(5000, 1000);
+        for value in 1..=7 {
+            append_history_sample(&mut series, value as f64);
+        }
+
+        resize_history_series(&mut series, 3000);
+
+        assert_eq!(series.buffer, vec![5.0, 6.0, 7.0]);
+        assert_eq!(series.start, 0);
+        assert_eq!(series.count, 3);
+
+        resize_history_series(&mut series, 6000);
+
+        assert_eq!(series.buffer.len(), 6);
+        assert_eq!(series.count, 3);
+        assert_eq!(&series.buffer[..3], &[5.0, 6.0, 7.0]);
+    }
 }
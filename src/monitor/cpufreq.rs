@@ -0,0 +1,263 @@
+//! cpufreq e throttling térmico de `/sys/devices/system/cpu`: frequência atual,
+//! governor, EPP, limites de escala, boost e os contadores de `thermal_throttle`.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{CpuCoreMetrics, CpuFrequencyPolicy, CpuThrottleMetrics};
+
+pub(crate) const CPU_SYSFS_PATH: &str = "/sys/devices/system/cpu";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ThrottleCounters {
+    pub(crate) core: Option<u64>,
+    pub(crate) package: Option<u64>,
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn read_string(path: &Path) -> Option<String> {
+    let value = fs::read_to_string(path).ok()?.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+/// Diretórios `cpuN` em `base`, em ordem de `N`.
+pub(crate) fn cpu_dirs(base: &Path) -> Vec<(usize, PathBuf)> {
+    let Ok(entries) = fs::read_dir(base) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<(usize, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry
+                .file_name()
+                .to_str()?
+                .strip_prefix("cpu")?
+                .parse::<usize>()
+                .ok()?;
+            Some((id, entry.path()))
+        })
+        .collect();
+    dirs.sort_unstable_by_key(|(id, _)| *id);
+    dirs
+}
+
+/// `scaling_cur_freq` (kHz) de cada `cpuN` em `base`, convertido para MHz.
+pub(crate) fn read_core_frequencies_mhz(base: &Path) -> HashMap<usize, u64> {
+    cpu_dirs(base)
+        .into_iter()
+        .filter_map(|(id, dir)| {
+            let khz = read_u64(&dir.join("cpufreq/scaling_cur_freq"))?;
+            Some((id, khz / 1000))
+        })
+        .collect()
+}
+
+/// Governor e EPP da primeira CPU com cpufreq, limites extremos entre todas e o boost.
+pub(crate) fn read_frequency_policy(base: &Path) -> CpuFrequencyPolicy {
+    let mut policy = CpuFrequencyPolicy {
+        boost_enabled: read_u64(&base.join("cpufreq/boost"))
+            .map(|boost| boost != 0)
+            .or_else(|| {
+                read_u64(&base.join("intel_pstate/no_turbo")).map(|no_turbo| no_turbo == 0)
+            }),
+        ..CpuFrequencyPolicy::default()
+    };
+    for (_, dir) in cpu_dirs(base) {
+        let cpufreq = dir.join("cpufreq");
+        if policy.governor.is_none() {
+            policy.governor = read_string(&cpufreq.join("scaling_governor"));
+        }
+        if policy.energy_performance_preference.is_none() {
+            policy.energy_performance_preference =
+                read_string(&cpufreq.join("energy_performance_preference"));
+        }
+        if let Some(min) = read_u64(&cpufreq.join("scaling_min_freq")) {
+            let min = min / 1000;
+            policy.scaling_min_mhz = Some(
+                policy
+                    .scaling_min_mhz
+                    .map_or(min, |current| current.min(min)),
+            );
+        }
+        if let Some(max) = read_u64(&cpufreq.join("scaling_max_freq")) {
+            let max = max / 1000;
+            policy.scaling_max_mhz = Some(
+                policy
+                    .scaling_max_mhz
+                    .map_or(max, |current| current.max(max)),
+            );
+        }
+    }
+    policy
+}
+
+/// Contadores de `cpuN/thermal_throttle`; CPUs sem o diretório (AMD, VMs) ficam de fora.
+pub(crate) fn read_throttle_counters(base: &Path) -> HashMap<usize, ThrottleCounters> {
+    cpu_dirs(base)
+        .into_iter()
+        .filter_map(|(id, dir)| {
+            let throttle = dir.join("thermal_throttle");
+            let counters = ThrottleCounters {
+                core: read_u64(&throttle.join("core_throttle_count")),
+                package: read_u64(&throttle.join("package_throttle_count")),
+            };
+            (counters != ThrottleCounters::default()).then_some((id, counters))
+        })
+        .collect()
+}
+
+/// CPUs com algum contador maior que na leitura anterior, em ordem crescente.
+pub(crate) fn throttled_cpus(
+    previous: &HashMap<usize, ThrottleCounters>,
+    current: &HashMap<usize, ThrottleCounters>,
+) -> Vec<usize> {
+    let increased = |now: Option<u64>, before: Option<u64>| {
+        now.zip(before).is_some_and(|(now, before)| now > before)
+    };
+    let mut cpus: Vec<usize> = current
+        .iter()
+        .filter(|(id, now)| {
+            previous.get(id).is_some_and(|before| {
+                increased(now.core, before.core) || increased(now.package, before.package)
+            })
+        })
+        .map(|(id, _)| *id)
+        .collect();
+    cpus.sort_unstable();
+    cpus
+}
+
+/// Totais sem contar duas vezes irmãs SMT (mesmo núcleo) nem CPUs do mesmo pacote.
+pub(crate) fn summarize_throttle(
+    cores: &[CpuCoreMetrics],
+    throttled_cpus: &[usize],
+) -> CpuThrottleMetrics {
+    let mut seen_cores = HashSet::new();
+    let mut seen_packages = HashSet::new();
+    let mut summary = CpuThrottleMetrics {
+        throttling: !throttled_cpus.is_empty(),
+        throttled_cpus: throttled_cpus.to_vec(),
+        ..CpuThrottleMetrics::default()
+    };
+    for core in cores {
+        let physical_core = (
+            core.package_id,
+            core.core_id.map_or(core.id, |id| id as usize),
+        );
+        if let Some(count) = core
+            .core_throttle_count
+            .filter(|_| seen_cores.insert(physical_core))
+        {
+            summary.core_throttle_count += count;
+        }
+        if let Some(count) = core
+            .package_throttle_count
+            .filter(|_| seen_packages.insert(core.package_id))
+        {
+            summary.package_throttle_count += count;
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_fixture_dir;
+
+    #[test]
+    fn test_read_frequency_policy_reads_governor_limits_and_boost() {
+        let base = temp_fixture_dir();
+        for (cpu, min, max) in [(0, 800_000, 5_400_000), (1, 400_000, 3_800_000)] {
+            let cpufreq = base.join(format!("cpu{cpu}/cpufreq"));
+            fs::create_dir_all(&cpufreq).unwrap();
+            fs::write(cpufreq.join("scaling_governor"), "powersave\n").unwrap();
+            fs::write(
+                cpufreq.join("energy_performance_preference"),
+                "balance_performance\n",
+            )
+            .unwrap();
+            fs::write(cpufreq.join("scaling_min_freq"), format!("{min}\n")).unwrap();
+            fs::write(cpufreq.join("scaling_max_freq"), format!("{max}\n")).unwrap();
+            fs::write(cpufreq.join("scaling_cur_freq"), "2400000\n").unwrap();
+        }
+        fs::create_dir_all(base.join("intel_pstate")).unwrap();
+        fs::write(base.join("intel_pstate/no_turbo"), "1\n").unwrap();
+
+        assert_eq!(
+            read_frequency_policy(&base),
+            CpuFrequencyPolicy {
+                governor: Some("powersave".to_string()),
+                energy_performance_preference: Some("balance_performance".to_string()),
+                scaling_min_mhz: Some(400),
+                scaling_max_mhz: Some(5400),
+                boost_enabled: Some(false),
+            }
+        );
+        assert_eq!(read_core_frequencies_mhz(&base).get(&1), Some(&2400));
+
+        // `cpufreq/boost` tem precedência sobre o intel_pstate.
+        fs::create_dir_all(base.join("cpufreq")).unwrap();
+        fs::write(base.join("cpufreq/boost"), "1\n").unwrap();
+        assert_eq!(read_frequency_policy(&base).boost_enabled, Some(true));
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn test_throttle_counters_detect_increase_and_summarize_per_core_and_package() {
+        let base = temp_fixture_dir();
+        let write = |cpu: usize, core: u64, package: u64| {
+            let throttle = base.join(format!("cpu{cpu}/thermal_throttle"));
+            fs::create_dir_all(&throttle).unwrap();
+            fs::write(throttle.join("core_throttle_count"), format!("{core}\n")).unwrap();
+            fs::write(
+                throttle.join("package_throttle_count"),
+                format!("{package}\n"),
+            )
+            .unwrap();
+        };
+        // cpu0/cpu1 são irmãs SMT; cpu2 é outro núcleo do mesmo pacote.
+        write(0, 5, 10);
+        write(1, 5, 10);
+        write(2, 3, 10);
+        fs::create_dir_all(base.join("cpu3")).unwrap();
+
+        let before = read_throttle_counters(&base);
+        assert_eq!(before.len(), 3);
+        assert!(throttled_cpus(&HashMap::new(), &before).is_empty());
+        assert!(throttled_cpus(&before, &before).is_empty());
+
+        write(2, 4, 10);
+        let after = read_throttle_counters(&base);
+        let throttled = throttled_cpus(&before, &after);
+        assert_eq!(throttled, vec![2]);
+
+        let cores: Vec<CpuCoreMetrics> = [(0, 0), (1, 0), (2, 1)]
+            .into_iter()
+            .map(|(id, core_id)| CpuCoreMetrics {
+                id,
+                core_id: Some(core_id),
+                package_id: Some(0),
+                core_throttle_count: after[&id].core,
+                package_throttle_count: after[&id].package,
+                ..CpuCoreMetrics::default()
+            })
+            .collect();
+        assert_eq!(
+            summarize_throttle(&cores, &throttled),
+            CpuThrottleMetrics {
+                throttling: true,
+                throttled_cpus: vec![2],
+                core_throttle_count: 9,
+                package_throttle_count: 10,
+            }
+        );
+
+        fs::remove_dir_all(base).unwrap();
+    }
+}
@@ -0,0 +1,190 @@
+//! Exportação do histórico curto em CSV, uma linha por instante e uma coluna por série.
+//!
+//! O histórico não guarda o instante de cada amostra: a mais recente de cada série é
+//! considerada tomada em `end_unix_ms` e as anteriores recuam de `sample_interval_ms`
+//! em `sample_interval_ms`, a mesma convenção usada ao restaurar o checkpoint. Para
+//! alinhar séries com intervalos diferentes (rápidas, sensores, GPU), cada amostra cai
+//! na linha mais próxima de uma grade com o passo da série mais frequente.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use super::HistoryMetrics;
+
+/// Série selecionada para exportação, com a linha da grade de cada amostra.
+struct Column<'a> {
+    name: &'a str,
+    /// `(linhas antes do fim, valor)`, da amostra mais antiga para a mais recente.
+    samples: Vec<(u64, f64)>,
+}
+
+/// Gera o CSV das séries `names` (todas quando vazio) de `history`, terminando em `end_unix_ms`.
+/// Colunas: `timestamp_ms`, `time` em ISO 8601 UTC e uma por série; células ficam vazias
+/// nos instantes em que a série não tem amostra.
+pub fn history_csv(
+    history: &HistoryMetrics,
+    names: &[&str],
+    end_unix_ms: u64,
+) -> Result<String, String> {
+    let all = history.series();
+    let selected: Vec<_> = if names.is_empty() {
+        all
+    } else {
+        names
+            .iter()
+            .map(|name| {
+                all.iter()
+                    .find(|(series_name, _)| series_name == name)
+                    .copied()
+                    .ok_or_else(|| format!("série desconhecida: {name}"))
+            })
+            .collect::<Result<_, _>>()?
+    };
+
+    let step_ms = selected
+        .iter()
+        .map(|(_, series)| series.sample_interval_ms.max(1))
+        .min()
+        .unwrap_or(1);
+    let columns: Vec<Column> = selected
+        .iter()
+        .map(|(name, series)| {
+            let interval_ms = series.sample_interval_ms.max(1);
+            let last = series.count.saturating_sub(1) as u64;
+            let samples = series
+                .samples()
+                .enumerate()
+                .map(|(index, value)| {
+                    let age_ms = (last - index as u64) * interval_ms;
+                    ((age_ms + step_ms / 2) / step_ms, value)
+                })
+                .collect();
+            Column { name, samples }
+        })
+        .collect();
+
+    // Linhas indexadas por quantos passos antes do fim estão; ordem decrescente = cronológica.
+    let mut rows: BTreeMap<u64, Vec<Option<f64>>> = BTreeMap::new();
+    for (index, column) in columns.iter().enumerate() {
+        for &(steps_ago, value) in &column.samples {
+            rows.entry(steps_ago)
+                .or_insert_with(|| vec![None; columns.len()])[index] = Some(value);
+        }
+    }
+
+    let mut csv = String::from("timestamp_ms,time");
+    for column in &columns {
+        csv.push(',');
+        csv.push_str(column.name);
+    }
+    csv.push('\n');
+    for (steps_ago, values) in rows.iter().rev() {
+        let timestamp_ms = end_unix_ms.saturating_sub(steps_ago * step_ms);
+        let _ = write!(csv, "{timestamp_ms},{}", format_utc(timestamp_ms));
+        for value in values {
+            csv.push(',');
+            if let Some(value) = value.filter(|value| value.is_finite()) {
+                let _ = write!(csv, "{value}");
+            }
+        }
+        csv.push('\n');
+    }
+    Ok(csv)
+}
+
+/// Data do calendário gregoriano proléptico para dias desde 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// `2024-05-01T14:30:00.000Z` para milissegundos Unix.
+fn format_utc(unix_ms: u64) -> String {
+    let seconds = (unix_ms / 1000) as i64;
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    let second_of_day = seconds.rem_euclid(86_400);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        second_of_day / 3600,
+        second_of_day / 60 % 60,
+        second_of_day % 60,
+        unix_ms % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::{HistorySeries, SystemMonitor};
+
+    fn series(samples: &[f64], start: usize, sample_interval_ms: u64) -> HistorySeries {
+        HistorySeries {
+            buffer: samples.to_vec(),
+            start,
+            count: samples.len(),
+            sample_interval_ms,
+        }
+    }
+
+    #[test]
+    fn test_format_utc_renders_iso_timestamps() {
+        assert_eq!(format_utc(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(format_utc(1_714_573_800_250), "2024-05-01T14:30:00.250Z");
+        assert_eq!(format_utc(951_782_400_000), "2000-02-29T00:00:00.000Z");
+    }
+
+    #[test]
+    fn test_history_csv_unrolls_ring_buffer_and_aligns_intervals() {
+        let mut history = SystemMonitor::new_empty().get_history_metrics();
+        // Anel começando no índice 2: ordem cronológica 1, 2, 3, 4, 5, 6.
+        history.cpu_usage = series(&[5.0, 6.0, 1.0, 2.0, 3.0, 4.0], 2, 1000);
+        history.sensor_hottest_temperature = series(&[40.0, 41.0], 0, 3000);
+        history.gpu_usage = series(&[70.0, 80.0], 0, 4500);
+
+        let csv = history_csv(
+            &history,
+            &["cpu_usage", "sensor_hottest_temperature", "gpu_usage"],
+            1_714_573_810_000,
+        )
+        .unwrap();
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "timestamp_ms,time,cpu_usage,sensor_hottest_temperature,gpu_usage",
+                "1714573805000,2024-05-01T14:30:05.000Z,1,,70",
+                "1714573806000,2024-05-01T14:30:06.000Z,2,,",
+                "1714573807000,2024-05-01T14:30:07.000Z,3,40,",
+                "1714573808000,2024-05-01T14:30:08.000Z,4,,",
+                "1714573809000,2024-05-01T14:30:09.000Z,5,,",
+                "1714573810000,2024-05-01T14:30:10.000Z,6,41,80",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_history_csv_rejects_unknown_series() {
+        let history = SystemMonitor::new_empty().get_history_metrics();
+        assert_eq!(
+            history_csv(&history, &["bogus"], 0).unwrap_err(),
+            "série desconhecida: bogus"
+        );
+        let header = history_csv(&history, &[], 0).unwrap();
+        assert!(header.starts_with("timestamp_ms,time,cpu_usage,cpu_user,cpu_nice,"));
+        assert!(header.contains(",cpu_guest,memory_usage,gpu_usage,"));
+    }
+}
@@ -0,0 +1,437 @@
+//! Checkpoint compacto do histórico em disco.
+//!
+//! Formato (little-endian):
+//! `MTH1` | `u64` instante do checkpoint (ms desde a época Unix) | `u16` número de séries |
+//! por série: `u8` tamanho do nome, nome UTF-8, `u64` intervalo de amostragem (ms),
+//! `u32` número de amostras e as amostras em `f32`, da mais antiga para a mais recente.
+//! Em seguida, opcionalmente, os agregados de longo prazo: `u16` número de janelas;
+//! por janela: `u8` tamanho do nome, nome, `u8` índice em [`HistoryRange::ALL`],
+//! `u32` número de buckets e, por bucket, `u32` índice do bucket desde a época Unix,
+//! `u16` amostras e `f32` mínimo, média e máximo.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::rollup::HistoryRollups;
+use super::{HistoryMetrics, HistoryRange, HistorySeries, RollupBucket};
+use crate::config::state_dir;
+
+const MAGIC: &[u8; 4] = b"MTH1";
+const HISTORY_FILE_NAME: &str = "history.bin";
+
+/// Caminho do checkpoint: `$XDG_STATE_HOME/monitor-tray/history.bin`.
+pub fn history_path() -> PathBuf {
+    state_dir().join(HISTORY_FILE_NAME)
+}
+
+pub(crate) fn unix_time_ms(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn push_name(bytes: &mut Vec<u8>, name: &str) {
+    let name = &name.as_bytes()[..name.len().min(u8::MAX as usize)];
+    bytes.push(name.len() as u8);
+    bytes.extend_from_slice(name);
+}
+
+pub(crate) fn encode_history(
+    history: &HistoryMetrics,
+    rollups: &HistoryRollups,
+    saved_at_ms: u64,
+) -> Vec<u8> {
+    let series = history.series();
+    let mut bytes = Vec::with_capacity(16 + series.len() * (32 + 4 * 300));
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&saved_at_ms.to_le_bytes());
+    bytes.extend_from_slice(&(series.len() as u16).to_le_bytes());
+
+    for (name, series) in series {
+        push_name(&mut bytes, name);
+        bytes.extend_from_slice(&series.sample_interval_ms.to_le_bytes());
+        bytes.extend_from_slice(&(series.count as u32).to_le_bytes());
+        for sample in series.samples() {
+            bytes.extend_from_slice(&(sample as f32).to_le_bytes());
+        }
+    }
+
+    let tiers: Vec<_> = rollups
+        .tiers()
+        .filter(|(_, _, buckets)| !buckets.is_empty())
+        .collect();
+    bytes.extend_from_slice(&(tiers.len() as u16).to_le_bytes());
+    for (name, range, buckets) in tiers {
+        push_name(&mut bytes, name);
+        bytes.push(range_position(range));
+        bytes.extend_from_slice(&(buckets.len() as u32).to_le_bytes());
+        for bucket in buckets {
+            let index = bucket.start_unix_ms / range.bucket_ms();
+            bytes.extend_from_slice(&(index as u32).to_le_bytes());
+            bytes.extend_from_slice(&(bucket.samples.min(u16::MAX as u32) as u16).to_le_bytes());
+            for value in [bucket.min, bucket.avg, bucket.max] {
+                bytes.extend_from_slice(&(value as f32).to_le_bytes());
+            }
+        }
+    }
+
+    bytes
+}
+
+fn range_position(range: HistoryRange) -> u8 {
+    HistoryRange::ALL
+        .iter()
+        .position(|candidate| *candidate == range)
+        .unwrap_or(0) as u8
+}
+
+struct SavedSeries {
+    name: String,
+    sample_interval_ms: u64,
+    samples: Vec<f64>,
+}
+
+struct SavedTier {
+    name: String,
+    range: HistoryRange,
+    buckets: Vec<RollupBucket>,
+}
+
+struct SavedHistory {
+    saved_at_ms: u64,
+    series: Vec<SavedSeries>,
+    tiers: Vec<SavedTier>,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "checkpoint de histórico truncado",
+            ));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn name(&mut self) -> io::Result<String> {
+        let len = self.array::<1>()?[0] as usize;
+        Ok(String::from_utf8_lossy(self.take(len)?).into_owned())
+    }
+
+    fn f32(&mut self) -> io::Result<f64> {
+        Ok(f32::from_le_bytes(self.array()?) as f64)
+    }
+}
+
+fn decode_tiers(reader: &mut Reader<'_>) -> io::Result<Vec<SavedTier>> {
+    let tier_count = u16::from_le_bytes(reader.array()?);
+    let mut tiers = Vec::with_capacity(tier_count as usize);
+
+    for _ in 0..tier_count {
+        let name = reader.name()?;
+        let range = HistoryRange::ALL
+            .get(reader.array::<1>()?[0] as usize)
+            .copied()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "janela desconhecida"))?;
+        let bucket_count = u32::from_le_bytes(reader.array()?);
+        let mut buckets = Vec::with_capacity((bucket_count as usize).min(4096));
+        for _ in 0..bucket_count {
+            let index = u32::from_le_bytes(reader.array()?) as u64;
+            let samples = u16::from_le_bytes(reader.array()?) as u32;
+            buckets.push(RollupBucket {
+                start_unix_ms: index * range.bucket_ms(),
+                samples,
+                min: reader.f32()?,
+                avg: reader.f32()?,
+                max: reader.f32()?,
+            });
+        }
+        tiers.push(SavedTier {
+            name,
+            range,
+            buckets,
+        });
+    }
+
+    Ok(tiers)
+}
+
+fn decode_history(bytes: &[u8]) -> io::Result<SavedHistory> {
+    let mut reader = Reader { bytes };
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "formato de checkpoint de histórico desconhecido",
+        ));
+    }
+
+    let saved_at_ms = u64::from_le_bytes(reader.array()?);
+    let series_count = u16::from_le_bytes(reader.array()?);
+    let mut saved = Vec::with_capacity(series_count as usize);
+
+    for _ in 0..series_count {
+        let name = reader.name()?;
+        let sample_interval_ms = u64::from_le_bytes(reader.array()?);
+        let count = u32::from_le_bytes(reader.array()?) as usize;
+        let samples = reader
+            .take(count.saturating_mul(4))?
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as f64)
+            .collect();
+        saved.push(SavedSeries {
+            name,
+            sample_interval_ms,
+            samples,
+        });
+    }
+
+    // Checkpoints gravados antes dos agregados terminam após as séries.
+    let tiers = if reader.bytes.is_empty() {
+        Vec::new()
+    } else {
+        decode_tiers(&mut reader)?
+    };
+
+    Ok(SavedHistory {
+        saved_at_ms,
+        series: saved,
+        tiers,
+    })
+}
+
+/// Reconstrói uma série a partir das amostras salvas.
+///
+/// A amostra mais recente é considerada tomada em `saved_at_ms`; amostras que já
+/// saíram da janela em `now_ms` são descartadas e o intervalo em que o backend
+/// ficou parado é preenchido com `NaN` (sem leitura), mantendo o eixo de tempo dos gráficos.
+fn restore_series(
+    series: &mut HistorySeries,
+    samples: &[f64],
+    history_duration_ms: u64,
+    saved_at_ms: u64,
+    now_ms: u64,
+) {
+    let capacity = series.buffer.len();
+    let interval = series.sample_interval_ms.max(1);
+    let elapsed_ms = now_ms.saturating_sub(saved_at_ms);
+
+    let kept: Vec<f64> = samples
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| {
+            let age_ms = elapsed_ms + (samples.len() - 1 - index) as u64 * interval;
+            age_ms < history_duration_ms
+        })
+        .map(|(_, sample)| *sample)
+        .collect();
+    if kept.is_empty() || capacity == 0 {
+        return;
+    }
+
+    let gap = ((elapsed_ms / interval) as usize).min(capacity);
+    let mut restored = kept;
+    restored.resize(restored.len() + gap, f64::NAN);
+    let restored = &restored[restored.len().saturating_sub(capacity)..];
+
+    series.buffer.iter_mut().for_each(|sample| *sample = 0.0);
+    series.buffer[..restored.len()].copy_from_slice(restored);
+    series.start = 0;
+    series.count = restored.len();
+}
+
+fn restore_history_from_bytes(
+    history: &mut HistoryMetrics,
+    rollups: &mut HistoryRollups,
+    bytes: &[u8],
+    now_ms: u64,
+) -> io::Result<()> {
+    let saved = decode_history(bytes)?;
+    let history_duration_ms = history.history_duration_ms;
+
+    for (name, series) in history.series_mut() {
+        let Some(saved_series) = saved.series.iter().find(|saved| saved.name == name) else {
+            continue;
+        };
+        if saved_series.sample_interval_ms != series.sample_interval_ms {
+            continue;
+        }
+        restore_series(
+            series,
+            &saved_series.samples,
+            history_duration_ms,
+            saved.saved_at_ms,
+            now_ms,
+        );
+    }
+
+    for tier in saved.tiers {
+        rollups.restore_tier(&tier.name, tier.range, tier.buckets, now_ms);
+    }
+
+    Ok(())
+}
+
+/// Restaura `history` e `rollups` a partir do checkpoint em `path`.
+/// Arquivo ausente não é erro: o histórico simplesmente começa vazio.
+pub(crate) fn restore_history(
+    history: &mut HistoryMetrics,
+    rollups: &mut HistoryRollups,
+    path: &Path,
+) -> io::Result<()> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(error),
+    };
+    restore_history_from_bytes(history, rollups, &bytes, unix_time_ms(SystemTime::now()))
+}
+
+/// Grava o checkpoint de forma atômica (arquivo temporário + rename).
+pub(crate) fn save_history(
+    history: &HistoryMetrics,
+    rollups: &HistoryRollups,
+    path: &Path,
+) -> io::Result<()> {
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+    let bytes = encode_history(history, rollups, unix_time_ms(SystemTime::now()));
+    let temp_path = path.with_extension("bin.tmp");
+    std::fs::write(&temp_path, bytes)?;
+    std::fs::rename(&temp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series_with(samples: &[f64], capacity: usize, sample_interval_ms: u64) -> HistorySeries {
+        let mut buffer = vec![0.0; capacity];
+        buffer[..samples.len()].copy_from_slice(samples);
+        HistorySeries {
+            buffer,
+            start: 0,
+            count: samples.len(),
+            sample_interval_ms,
+        }
+    }
+
+    fn values(series: &HistorySeries) -> Vec<f64> {
+        series.samples().collect()
+    }
+
+    /// Amostras com `NaN` trocado por `None`, para comparar com `assert_eq!`.
+    fn readings(series: &HistorySeries) -> Vec<Option<f64>> {
+        series
+            .samples()
+            .map(|sample| (!sample.is_nan()).then_some(sample))
+            .collect()
+    }
+
+    #[test]
+    fn test_restore_series_marks_downtime_and_drops_expired_samples() {
+        let mut series = series_with(&[], 10, 1000);
+
+        // Checkpoint com 8 amostras (uma sem leitura); o backend ficou 4 s parado numa janela de 10 s.
+        restore_series(
+            &mut series,
+            &[1.0, 2.0, 3.0, 4.0, f64::NAN, 6.0, 7.0, 8.0],
+            10_000,
+            50_000,
+            54_000,
+        );
+
+        assert_eq!(
+            readings(&series),
+            vec![
+                Some(3.0),
+                Some(4.0),
+                None,
+                Some(6.0),
+                Some(7.0),
+                Some(8.0),
+                None,
+                None,
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_downtime_gap_survives_json_round_trip() {
+        let mut series = series_with(&[], 4, 1000);
+        restore_series(&mut series, &[1.0, 2.0], 4_000, 10_000, 12_000);
+
+        let json = serde_json::to_string(&series).unwrap();
+        assert!(json.contains("null"));
+        let decoded: HistorySeries = serde_json::from_str(&json).unwrap();
+        assert_eq!(readings(&decoded), vec![Some(1.0), Some(2.0), None, None]);
+    }
+
+    #[test]
+    fn test_restore_series_ignores_checkpoint_older_than_window() {
+        let mut series = series_with(&[], 10, 1000);
+
+        restore_series(&mut series, &[1.0, 2.0, 3.0], 10_000, 50_000, 70_000);
+
+        assert_eq!(series.count, 0);
+    }
+
+    #[test]
+    fn test_encode_and_restore_round_trip_preserves_ring_order() {
+        let mut history = HistoryMetrics {
+            history_duration_ms: 4000,
+            ..crate::monitor::SystemMonitor::new_empty().get_history_metrics()
+        };
+        history.cpu_usage = HistorySeries {
+            buffer: vec![5.0, 2.0, 3.0, 4.0],
+            start: 1,
+            count: 4,
+            sample_interval_ms: 1000,
+        };
+        let mut rollups = HistoryRollups::default();
+        rollups.record("cpu_usage", 40.0, 9_000);
+        rollups.record("cpu_usage", 60.0, 9_500);
+        let bytes = encode_history(&history, &rollups, 10_000);
+
+        let mut restored = history.clone();
+        restored.cpu_usage = series_with(&[], 4, 1000);
+        let mut restored_rollups = HistoryRollups::default();
+        restore_history_from_bytes(&mut restored, &mut restored_rollups, &bytes, 10_000)
+            .expect("should decode");
+
+        assert_eq!(values(&restored.cpu_usage), vec![2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(
+            restored_rollups
+                .range("cpu_usage", HistoryRange::Day, 10_000)
+                .buckets,
+            rollups
+                .range("cpu_usage", HistoryRange::Day, 10_000)
+                .buckets
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_truncated_or_foreign_files() {
+        let history = crate::monitor::SystemMonitor::new_empty().get_history_metrics();
+        let bytes = encode_history(&history, &HistoryRollups::default(), 0);
+
+        assert!(decode_history(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode_history(b"not a checkpoint").is_err());
+    }
+}
@@ -76,8 +76,13 @@ fn hwmon_chip_name(directory: &Path) -> String {
 
 /// Monta o rótulo completo de um sensor no formato `"chip: rótulo"` ou
 /// `"chip: TipoFallback índice"` quando não há arquivo `*_label`.
-fn hwmon_sensor_label(directory: &Path, prefix: &str, index: &str, fallback_name: &str) -> String {
-    let chip_name = hwmon_chip_name(directory);
+fn hwmon_sensor_label(
+    directory: &Path,
+    chip_name: &str,
+    prefix: &str,
+    index: &str,
+    fallback_name: &str,
+) -> String {
     let label_path = directory.join(format!("{prefix}{index}_label"));
 
     if let Some(label) = read_trimmed(&label_path) {
@@ -111,6 +116,7 @@ pub(crate) fn collect_hwmon_metrics_from_path(base_path: &Path) -> HwmonMetrics
             Ok(entries) => entries,
             Err(_) => continue,
         };
+        let chip = hwmon_chip_name(&path);
 
         for file in files.filter_map(Result::ok) {
             let file_path = file.path();
@@ -125,7 +131,6 @@ pub(crate) fn collect_hwmon_metrics_from_path(base_path: &Path) -> HwmonMetrics
             if let Some(index) = parse_sensor_index(&file_name, "temp", "_input") {
                 if let Some(celsius) = read_scaled_f32(&file_path, MILLI_SCALE) {
                     if celsius.is_finite() {
-                        let chip = hwmon_chip_name(&path);
                         let label = read_trimmed(&path.join(format!("temp{index}_label")))
                             .map(|l| prettify_identifier(&l))
                             .unwrap_or_else(|| format!("Temp {index}"));
@@ -135,7 +140,7 @@ pub(crate) fn collect_hwmon_metrics_from_path(base_path: &Path) -> HwmonMetrics
                             read_scaled_f32(&path.join(format!("temp{index}_crit")), MILLI_SCALE);
                         metrics.temperatures.push(TemperatureSensor {
                             label,
-                            chip,
+                            chip: chip.clone(),
                             temperature_celsius: celsius,
                             max_celsius,
                             critical_celsius,
@@ -151,7 +156,8 @@ pub(crate) fn collect_hwmon_metrics_from_path(base_path: &Path) -> HwmonMetrics
                         .map(|value| (value / PWM_MAX_VALUE * 100.0).clamp(0.0, 100.0));
 
                     metrics.fans.push(FanSensor {
-                        label: hwmon_sensor_label(&path, "fan", &index, "Fan"),
+                        label: hwmon_sensor_label(&path, &chip, "fan", &index, "Fan"),
+                        chip: chip.clone(),
                         rpm,
                         duty_percent,
                     });
@@ -162,7 +168,8 @@ pub(crate) fn collect_hwmon_metrics_from_path(base_path: &Path) -> HwmonMetrics
             if let Some(index) = parse_sensor_index(&file_name, "in", "_input") {
                 if let Some(volts) = read_scaled_f32(&file_path, MILLI_SCALE) {
                     metrics.voltages.push(VoltageSensor {
-                        label: hwmon_sensor_label(&path, "in", &index, "Voltage"),
+                        label: hwmon_sensor_label(&path, &chip, "in", &index, "Voltage"),
+                        chip: chip.clone(),
                         volts,
                     });
                 }
@@ -172,7 +179,8 @@ pub(crate) fn collect_hwmon_metrics_from_path(base_path: &Path) -> HwmonMetrics
             if let Some(index) = parse_sensor_index(&file_name, "curr", "_input") {
                 if let Some(amps) = read_scaled_f32(&file_path, MILLI_SCALE) {
                     metrics.currents.push(CurrentSensor {
-                        label: hwmon_sensor_label(&path, "curr", &index, "Current"),
+                        label: hwmon_sensor_label(&path, &chip, "curr", &index, "Current"),
+                        chip: chip.clone(),
                         amps,
                     });
                 }
@@ -182,7 +190,8 @@ pub(crate) fn collect_hwmon_metrics_from_path(base_path: &Path) -> HwmonMetrics
             if let Some(index) = parse_sensor_index(&file_name, "power", "_input") {
                 if let Some(watts) = read_scaled_f32(&file_path, MICRO_SCALE) {
                     metrics.powers.push(PowerSensor {
-                        label: hwmon_sensor_label(&path, "power", &index, "Power"),
+                        label: hwmon_sensor_label(&path, &chip, "power", &index, "Power"),
+                        chip: chip.clone(),
                         watts,
                     });
                 }
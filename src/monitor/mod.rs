@@ -1,34 +1,36 @@
 mod collector;
+mod cpufreq;
 pub(crate) mod gpu;
+mod history_csv;
+mod history_store;
 mod hwmon;
 mod models;
+mod pressure;
+mod rollup;
+mod topology;
 
-pub use collector::SystemMonitor;
+pub use collector::{SlowSubsystems, SystemMonitor};
+pub use history_csv::history_csv;
+pub use history_store::history_path;
 pub use models::{
-    CpuMetrics, CurrentSensor, DiskInfo, DiskMetrics, FanSensor, FastMetrics, GpuInfo, GpuVendor,
-    HistoryMetrics, HistorySeries, MemoryMetrics, NetworkInterface, NetworkMetrics,
-    NetworkSpeedTestPhase, NetworkSpeedTestState, NetworkSpeedTestStatus, PowerSensor, ProcessInfo,
-    SensorMetrics, SlowMetrics, SystemInfo, SystemMetrics, TemperatureSensor, VoltageSensor,
+    CgroupPressureMetrics, CpuCoreClassMetrics, CpuCoreMetrics, CpuCoreType, CpuFrequencyPolicy,
+    CpuMetrics, CpuThrottleMetrics, CurrentSensor, DiskInfo, DiskMetrics, FanSensor, FastMetrics,
+    GpuInfo, GpuVendor, HistoryMetrics, HistoryRange, HistoryRangeMetrics, HistorySeries,
+    MemoryMetrics, NetworkInterface, NetworkMetrics, NetworkSpeedTestPhase, NetworkSpeedTestState,
+    NetworkSpeedTestStatus, PowerSensor, PressureMetrics, PressureResource, PressureStall,
+    ProcessInfo, RollupBucket, SensorMetrics, SlowMetrics, SystemInfo, SystemMetrics,
+    TemperatureSensor, VoltageSensor,
 };
 
 #[cfg(test)]
 mod tests {
     use std::fs;
-    use std::path::PathBuf;
-    use std::time::{SystemTime, UNIX_EPOCH};
 
     use sysinfo::System;
 
     use super::collector::{bytes_to_gb, SystemMonitor};
     use super::hwmon::{collect_hwmon_metrics_from_path, parse_sensor_index};
-
-    fn temp_fixture_dir() -> PathBuf {
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("clock should be after unix epoch")
-            .as_nanos();
-        std::env::temp_dir().join(format!("monitor-tray-tests-{unique}"))
-    }
+    use crate::test_support::temp_fixture_dir;
 
     #[test]
     fn test_bytes_to_gb_converts_gibibytes() {
@@ -70,6 +72,7 @@ mod tests {
 
         assert_eq!(metrics.fans.len(), 1);
         assert_eq!(metrics.fans[0].label, "nct6798: CPU Fan");
+        assert_eq!(metrics.fans[0].chip, "nct6798");
         assert_eq!(metrics.fans[0].rpm, 1450);
         assert!(metrics.fans[0]
             .duty_percent
@@ -86,6 +89,7 @@ mod tests {
 
         assert_eq!(metrics.powers.len(), 1);
         assert_eq!(metrics.powers[0].label, "nct6798: Power 1");
+        assert_eq!(metrics.powers[0].chip, "nct6798");
         assert!((metrics.powers[0].watts - 65.5).abs() < f32::EPSILON);
 
         assert_eq!(metrics.temperatures.len(), 1);
@@ -203,6 +207,29 @@ mod tests {
         assert!(sensors.powers.iter().all(|sensor| sensor.watts.is_finite()));
     }
 
+    #[test]
+    fn test_history_series_names_match_json_fields() {
+        let history = SystemMonitor::new_empty().get_history_metrics();
+
+        let json = serde_json::to_value(&history).expect("history should serialize");
+        let mut json_fields: Vec<String> = json
+            .as_object()
+            .expect("history should serialize as an object")
+            .keys()
+            .filter(|key| key.as_str() != "history_duration_ms")
+            .cloned()
+            .collect();
+        let mut series_names: Vec<String> = history
+            .series()
+            .into_iter()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        json_fields.sort();
+        series_names.sort();
+
+        assert_eq!(series_names, json_fields);
+    }
+
     #[test]
     fn test_get_all_metrics_returns_non_negative_snapshot() {
         let monitor = SystemMonitor::new();
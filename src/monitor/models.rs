@@ -1,20 +1,141 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use zbus::zvariant::Type;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CpuMetrics {
     pub usage_percent: f32,
+    /// `user` de `/proc/stat` sem `nice` nem o tempo de convidados.
     pub user_percent: f32,
+    /// Só `system`; `irq` e `softirq` têm campos próprios.
     pub system_percent: f32,
+    /// Só `idle`; `iowait` tem campo próprio.
     pub idle_percent: f32,
     pub steal_percent: f32,
+    #[serde(default)]
+    pub iowait_percent: f32,
+    #[serde(default)]
+    pub irq_percent: f32,
+    #[serde(default)]
+    pub softirq_percent: f32,
+    /// `nice` sem o tempo de convidados com nice.
+    #[serde(default)]
+    pub nice_percent: f32,
+    /// `guest + guest_nice`: tempo executando vCPUs de VMs neste host.
+    #[serde(default)]
+    pub guest_percent: f32,
     pub core_count: usize,
     pub per_core_usage: Vec<f32>,
     pub frequency: u64,
     pub name: String,
+    /// Detalhamento por CPU lógica a partir das linhas `cpuN` de `/proc/stat`.
+    #[serde(default)]
+    pub cores: Vec<CpuCoreMetrics>,
+    /// Uso por classe de núcleo (P-cores e E-cores); vazio em CPUs sem núcleos híbridos.
+    #[serde(default)]
+    pub core_classes: Vec<CpuCoreClassMetrics>,
+    #[serde(default)]
+    pub frequency_policy: CpuFrequencyPolicy,
+    #[serde(default)]
+    pub throttle: CpuThrottleMetrics,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Política do cpufreq; campos ausentes quando o driver não expõe o arquivo.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuFrequencyPolicy {
+    /// `scaling_governor` da primeira CPU com cpufreq.
+    pub governor: Option<String>,
+    /// `energy_performance_preference` (intel_pstate e amd-pstate ativos).
+    pub energy_performance_preference: Option<String>,
+    /// Menor `scaling_min_freq` entre as CPUs.
+    pub scaling_min_mhz: Option<u64>,
+    /// Maior `scaling_max_freq` entre as CPUs.
+    pub scaling_max_mhz: Option<u64>,
+    /// Turbo/boost: `cpufreq/boost` ou o inverso de `intel_pstate/no_turbo`.
+    pub boost_enabled: Option<bool>,
+}
+
+/// Throttling térmico a partir de `cpuN/thermal_throttle`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuThrottleMetrics {
+    /// Algum contador subiu desde a atualização anterior.
+    pub throttling: bool,
+    /// CPUs cujos contadores subiram desde a atualização anterior.
+    pub throttled_cpus: Vec<usize>,
+    /// Soma de `core_throttle_count` por núcleo físico desde o boot.
+    pub core_throttle_count: u64,
+    /// Soma de `package_throttle_count` por pacote desde o boot.
+    pub package_throttle_count: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuCoreMetrics {
+    /// Número `N` de `cpuN`; CPUs offline não aparecem, então pode haver lacunas.
+    pub id: usize,
+    pub usage_percent: f32,
+    /// Mesma divisão de [`CpuMetrics`]: os nove campos somam 100%.
+    pub user_percent: f32,
+    pub system_percent: f32,
+    pub iowait_percent: f32,
+    pub steal_percent: f32,
+    #[serde(default)]
+    pub nice_percent: f32,
+    #[serde(default)]
+    pub idle_percent: f32,
+    #[serde(default)]
+    pub irq_percent: f32,
+    #[serde(default)]
+    pub softirq_percent: f32,
+    #[serde(default)]
+    pub guest_percent: f32,
+    /// `scaling_cur_freq` do cpufreq; sem cpufreq, a frequência do sysinfo para `cpuN`.
+    pub frequency_mhz: u64,
+    /// Classe do núcleo em CPUs híbridas; `None` quando todos os núcleos são iguais.
+    #[serde(default)]
+    pub core_type: Option<CpuCoreType>,
+    /// `topology/core_id`: núcleo físico dentro do pacote.
+    #[serde(default)]
+    pub core_id: Option<u32>,
+    #[serde(default)]
+    pub package_id: Option<u32>,
+    /// `topology/cluster_id`, disponível a partir do kernel 5.16.
+    #[serde(default)]
+    pub cluster_id: Option<u32>,
+    /// Outras CPUs lógicas no mesmo núcleo físico (SMT); vazio sem SMT.
+    #[serde(default)]
+    pub smt_siblings: Vec<usize>,
+    /// `thermal_throttle/core_throttle_count`; compartilhado pelas irmãs SMT.
+    #[serde(default)]
+    pub core_throttle_count: Option<u64>,
+    /// `thermal_throttle/package_throttle_count`; igual em todas as CPUs do pacote.
+    #[serde(default)]
+    pub package_throttle_count: Option<u64>,
+}
+
+/// Tipo de núcleo em CPUs híbridas, pela PMU que o kernel associa à CPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CpuCoreType {
+    /// P-core (`/sys/devices/cpu_core`).
+    Performance,
+    /// E-core (`/sys/devices/cpu_atom`).
+    Efficiency,
+}
+
+/// Uso agregado das CPUs lógicas de uma mesma classe de núcleo.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CpuCoreClassMetrics {
+    pub core_type: CpuCoreType,
+    /// CPUs lógicas da classe.
+    pub cpu_count: usize,
+    /// Núcleos físicos distintos (`package_id`, `core_id`) da classe.
+    pub physical_core_count: usize,
+    pub usage_percent: f32,
+    /// Média de `frequency_mhz` das CPUs da classe.
+    pub frequency_mhz: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct MemoryMetrics {
     pub total_memory: f64,
     pub used_memory: f64,
@@ -24,7 +145,7 @@ pub struct MemoryMetrics {
     pub used_swap: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct DiskMetrics {
     pub disks: Vec<DiskInfo>,
     pub total_space: f64,
@@ -34,7 +155,7 @@ pub struct DiskMetrics {
     pub total_write_bytes_per_sec: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct DiskInfo {
     pub name: String,
     pub mount_point: String,
@@ -53,9 +174,11 @@ pub struct NetworkMetrics {
     pub total_bytes_transmitted: u64,
     pub gateway_ip: Option<String>,
     pub gateway_latency_ms: Option<f32>,
+    /// Host configurado para a medição de latência; `None` indica o gateway padrão.
+    pub latency_target: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct NetworkInterface {
     pub bytes_received: u64,
     pub bytes_transmitted: u64,
@@ -131,26 +254,42 @@ pub struct TemperatureSensor {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FanSensor {
+    /// `"chip: rótulo"`, como exibido.
     pub label: String,
+    /// Chip hwmon (arquivo `name`), também usado por `hidden_sensors`.
+    #[serde(default)]
+    pub chip: String,
     pub rpm: u64,
     pub duty_percent: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VoltageSensor {
+    /// `"chip: rótulo"`, como exibido.
     pub label: String,
+    /// Chip hwmon (arquivo `name`), também usado por `hidden_sensors`.
+    #[serde(default)]
+    pub chip: String,
     pub volts: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CurrentSensor {
+    /// `"chip: rótulo"`, como exibido.
     pub label: String,
+    /// Chip hwmon (arquivo `name`), também usado por `hidden_sensors`.
+    #[serde(default)]
+    pub chip: String,
     pub amps: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PowerSensor {
+    /// `"chip: rótulo"`, como exibido.
     pub label: String,
+    /// Chip hwmon (arquivo `name`), também usado por `hidden_sensors`.
+    #[serde(default)]
+    pub chip: String,
     pub watts: f32,
 }
 
@@ -170,7 +309,7 @@ pub struct SensorMetrics {
     pub powers: Vec<PowerSensor>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
@@ -197,6 +336,18 @@ pub enum GpuVendor {
     Unknown,
 }
 
+impl GpuVendor {
+    /// Nome `snake_case` usado no JSON, em rótulos de métricas e no SQLite.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GpuVendor::Amd => "amd",
+            GpuVendor::Nvidia => "nvidia",
+            GpuVendor::Intel => "intel",
+            GpuVendor::Unknown => "unknown",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GpuInfo {
     pub name: String,
@@ -213,6 +364,48 @@ pub struct GpuInfo {
     pub fan_duty_percent: Option<f32>,
 }
 
+/// Pressure Stall Information do kernel; recursos ficam `None` sem `CONFIG_PSI`
+/// ou com `psi=0` na linha de comando do kernel.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PressureMetrics {
+    pub cpu: Option<PressureResource>,
+    pub memory: Option<PressureResource>,
+    pub io: Option<PressureResource>,
+    /// Os mesmos valores para o cgroup configurado em `pressure_cgroup`.
+    pub cgroup: Option<CgroupPressureMetrics>,
+}
+
+/// `*.pressure` de um cgroup v2.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CgroupPressureMetrics {
+    /// Caminho relativo a `/sys/fs/cgroup`, como na configuração.
+    pub path: String,
+    pub cpu: Option<PressureResource>,
+    pub memory: Option<PressureResource>,
+    pub io: Option<PressureResource>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PressureResource {
+    /// Tempo em que ao menos uma tarefa esteve travada esperando o recurso.
+    pub some: PressureStall,
+    /// Tempo em que todas as tarefas não ociosas estiveram travadas ao mesmo tempo.
+    /// Ausente para CPU em kernels anteriores ao 5.13.
+    pub full: Option<PressureStall>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct PressureStall {
+    /// Médias móveis do kernel em 10 s, 60 s e 300 s, em %.
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+    /// Tempo travado acumulado desde o boot.
+    pub total_us: u64,
+    /// `Δtotal` sobre o tempo decorrido desde a amostra anterior, em %; 0 na primeira.
+    pub stall_percent: f32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FastMetrics {
     pub cpu: CpuMetrics,
@@ -221,6 +414,8 @@ pub struct FastMetrics {
     pub network: NetworkMetrics,
     pub uptime: u64,
     pub load_average: (f64, f64, f64),
+    #[serde(default)]
+    pub pressure: PressureMetrics,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -233,16 +428,107 @@ pub struct SlowMetrics {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistorySeries {
+    /// Amostras sem leitura são `NaN`, serializadas como `null` em JSON.
+    #[serde(deserialize_with = "deserialize_samples")]
     pub buffer: Vec<f64>,
     pub start: usize,
     pub count: usize,
     pub sample_interval_ms: u64,
 }
 
+fn deserialize_samples<'de, D>(deserializer: D) -> Result<Vec<f64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let samples: Vec<Option<f64>> = Deserialize::deserialize(deserializer)?;
+    Ok(samples
+        .into_iter()
+        .map(|sample| sample.unwrap_or(f64::NAN))
+        .collect())
+}
+
+impl HistorySeries {
+    /// Amostras em ordem cronológica, da mais antiga para a mais recente.
+    pub fn samples(&self) -> impl Iterator<Item = f64> + '_ {
+        (0..self.count).map(|offset| self.buffer[(self.start + offset) % self.buffer.len()])
+    }
+}
+
+/// Janelas de histórico agregado disponíveis em `HistoryRangeJson`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HistoryRange {
+    #[serde(rename = "1h")]
+    Hour,
+    #[serde(rename = "24h")]
+    Day,
+    #[serde(rename = "7d")]
+    Week,
+}
+
+impl HistoryRange {
+    pub const ALL: [HistoryRange; 3] = [HistoryRange::Hour, HistoryRange::Day, HistoryRange::Week];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|range| range.as_str() == value)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HistoryRange::Hour => "1h",
+            HistoryRange::Day => "24h",
+            HistoryRange::Week => "7d",
+        }
+    }
+
+    pub fn duration_ms(self) -> u64 {
+        match self {
+            HistoryRange::Hour => 60 * 60 * 1000,
+            HistoryRange::Day => 24 * 60 * 60 * 1000,
+            HistoryRange::Week => 7 * 24 * 60 * 60 * 1000,
+        }
+    }
+
+    /// Largura de cada bucket: 30 s, 5 min e 30 min, respectivamente.
+    pub fn bucket_ms(self) -> u64 {
+        match self {
+            HistoryRange::Hour => 30 * 1000,
+            HistoryRange::Day => 5 * 60 * 1000,
+            HistoryRange::Week => 30 * 60 * 1000,
+        }
+    }
+}
+
+/// Agregado de todas as amostras de uma série dentro de um bucket alinhado ao relógio.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RollupBucket {
+    pub start_unix_ms: u64,
+    pub samples: u32,
+    pub min: f64,
+    pub avg: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRangeMetrics {
+    pub series: String,
+    pub range: HistoryRange,
+    pub bucket_ms: u64,
+    pub buckets: Vec<RollupBucket>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryMetrics {
     pub history_duration_ms: u64,
     pub cpu_usage: HistorySeries,
+    /// Componentes de `/proc/stat` que, empilhados, somam o tempo não ocioso da CPU.
+    pub cpu_user: HistorySeries,
+    pub cpu_nice: HistorySeries,
+    pub cpu_system: HistorySeries,
+    pub cpu_iowait: HistorySeries,
+    pub cpu_irq: HistorySeries,
+    pub cpu_softirq: HistorySeries,
+    pub cpu_steal: HistorySeries,
+    pub cpu_guest: HistorySeries,
     pub memory_usage: HistorySeries,
     pub gpu_usage: HistorySeries,
     pub disk_read: HistorySeries,
@@ -259,6 +545,113 @@ pub struct HistoryMetrics {
     pub system_load_5: HistorySeries,
     pub system_load_15: HistorySeries,
     pub system_process_count: HistorySeries,
+    /// `stall_percent` de `/proc/pressure`; sem PSI, ficam em zero.
+    pub pressure_cpu_some: HistorySeries,
+    pub pressure_memory_some: HistorySeries,
+    pub pressure_memory_full: HistorySeries,
+    pub pressure_io_some: HistorySeries,
+    pub pressure_io_full: HistorySeries,
+}
+
+impl HistoryMetrics {
+    /// Lista as séries com o mesmo nome usado no payload JSON.
+    pub fn series(&self) -> Vec<(&'static str, &HistorySeries)> {
+        vec![
+            ("cpu_usage", &self.cpu_usage),
+            ("cpu_user", &self.cpu_user),
+            ("cpu_nice", &self.cpu_nice),
+            ("cpu_system", &self.cpu_system),
+            ("cpu_iowait", &self.cpu_iowait),
+            ("cpu_irq", &self.cpu_irq),
+            ("cpu_softirq", &self.cpu_softirq),
+            ("cpu_steal", &self.cpu_steal),
+            ("cpu_guest", &self.cpu_guest),
+            ("memory_usage", &self.memory_usage),
+            ("gpu_usage", &self.gpu_usage),
+            ("disk_read", &self.disk_read),
+            ("disk_write", &self.disk_write),
+            ("network_download", &self.network_download),
+            ("network_upload", &self.network_upload),
+            (
+                "sensor_average_temperature",
+                &self.sensor_average_temperature,
+            ),
+            (
+                "sensor_hottest_temperature",
+                &self.sensor_hottest_temperature,
+            ),
+            (
+                "sensor_hottest_cpu_temperature",
+                &self.sensor_hottest_cpu_temperature,
+            ),
+            (
+                "sensor_hottest_gpu_temperature",
+                &self.sensor_hottest_gpu_temperature,
+            ),
+            ("sensor_highest_fan_rpm", &self.sensor_highest_fan_rpm),
+            ("sensor_total_power_watts", &self.sensor_total_power_watts),
+            ("system_load_1", &self.system_load_1),
+            ("system_load_5", &self.system_load_5),
+            ("system_load_15", &self.system_load_15),
+            ("system_process_count", &self.system_process_count),
+            ("pressure_cpu_some", &self.pressure_cpu_some),
+            ("pressure_memory_some", &self.pressure_memory_some),
+            ("pressure_memory_full", &self.pressure_memory_full),
+            ("pressure_io_some", &self.pressure_io_some),
+            ("pressure_io_full", &self.pressure_io_full),
+        ]
+    }
+
+    /// Versão mutável de [`HistoryMetrics::series`].
+    pub fn series_mut(&mut self) -> Vec<(&'static str, &mut HistorySeries)> {
+        vec![
+            ("cpu_usage", &mut self.cpu_usage),
+            ("cpu_user", &mut self.cpu_user),
+            ("cpu_nice", &mut self.cpu_nice),
+            ("cpu_system", &mut self.cpu_system),
+            ("cpu_iowait", &mut self.cpu_iowait),
+            ("cpu_irq", &mut self.cpu_irq),
+            ("cpu_softirq", &mut self.cpu_softirq),
+            ("cpu_steal", &mut self.cpu_steal),
+            ("cpu_guest", &mut self.cpu_guest),
+            ("memory_usage", &mut self.memory_usage),
+            ("gpu_usage", &mut self.gpu_usage),
+            ("disk_read", &mut self.disk_read),
+            ("disk_write", &mut self.disk_write),
+            ("network_download", &mut self.network_download),
+            ("network_upload", &mut self.network_upload),
+            (
+                "sensor_average_temperature",
+                &mut self.sensor_average_temperature,
+            ),
+            (
+                "sensor_hottest_temperature",
+                &mut self.sensor_hottest_temperature,
+            ),
+            (
+                "sensor_hottest_cpu_temperature",
+                &mut self.sensor_hottest_cpu_temperature,
+            ),
+            (
+                "sensor_hottest_gpu_temperature",
+                &mut self.sensor_hottest_gpu_temperature,
+            ),
+            ("sensor_highest_fan_rpm", &mut self.sensor_highest_fan_rpm),
+            (
+                "sensor_total_power_watts",
+                &mut self.sensor_total_power_watts,
+            ),
+            ("system_load_1", &mut self.system_load_1),
+            ("system_load_5", &mut self.system_load_5),
+            ("system_load_15", &mut self.system_load_15),
+            ("system_process_count", &mut self.system_process_count),
+            ("pressure_cpu_some", &mut self.pressure_cpu_some),
+            ("pressure_memory_some", &mut self.pressure_memory_some),
+            ("pressure_memory_full", &mut self.pressure_memory_full),
+            ("pressure_io_some", &mut self.pressure_io_some),
+            ("pressure_io_full", &mut self.pressure_io_full),
+        ]
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -273,4 +666,25 @@ pub struct SystemMetrics {
     pub system_info: SystemInfo,
     pub uptime: u64,
     pub load_average: (f64, f64, f64),
+    #[serde(default)]
+    pub pressure: PressureMetrics,
+}
+
+impl SystemMetrics {
+    /// Junta os caminhos rápido e lento no snapshot completo de `GetMetricsJson`.
+    pub fn from_parts(fast: FastMetrics, slow: SlowMetrics) -> Self {
+        Self {
+            cpu: fast.cpu,
+            memory: fast.memory,
+            disk: fast.disk,
+            network: fast.network,
+            sensors: slow.sensors,
+            gpus: slow.gpus,
+            top_processes: slow.top_processes,
+            system_info: slow.system_info,
+            uptime: fast.uptime,
+            load_average: fast.load_average,
+            pressure: fast.pressure,
+        }
+    }
 }
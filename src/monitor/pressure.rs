@@ -0,0 +1,206 @@
+//! Pressure Stall Information (`/proc/pressure/*` e `*.pressure` de cgroups v2).
+//!
+//! Cada arquivo tem uma linha `some` e, exceto CPU em kernels antigos, uma `full`:
+//! `some avg10=1.53 avg60=0.87 avg300=0.35 total=1234567`.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use super::{CgroupPressureMetrics, PressureMetrics, PressureResource, PressureStall};
+
+pub(crate) const PROC_PRESSURE_PATH: &str = "/proc/pressure";
+pub(crate) const CGROUP_ROOT_PATH: &str = "/sys/fs/cgroup";
+
+fn parse_stall(fields: &str) -> Option<PressureStall> {
+    let mut stall = PressureStall::default();
+    for field in fields.split_whitespace() {
+        let (key, value) = field.split_once('=')?;
+        match key {
+            "avg10" => stall.avg10 = value.parse().ok()?,
+            "avg60" => stall.avg60 = value.parse().ok()?,
+            "avg300" => stall.avg300 = value.parse().ok()?,
+            "total" => stall.total_us = value.parse().ok()?,
+            _ => {}
+        }
+    }
+    Some(stall)
+}
+
+pub(crate) fn parse_pressure(content: &str) -> Option<PressureResource> {
+    let mut some = None;
+    let mut full = None;
+    for line in content.lines() {
+        if let Some(fields) = line.strip_prefix("some ") {
+            some = parse_stall(fields);
+        } else if let Some(fields) = line.strip_prefix("full ") {
+            full = parse_stall(fields);
+        }
+    }
+    Some(PressureResource { some: some?, full })
+}
+
+fn read_pressure(path: &Path) -> Option<PressureResource> {
+    parse_pressure(&fs::read_to_string(path).ok()?)
+}
+
+fn stall_rate(current: &mut PressureStall, previous: &PressureStall, elapsed: Duration) {
+    let elapsed_us = elapsed.as_micros() as f64;
+    if elapsed_us > 0.0 {
+        let stalled_us = current.total_us.saturating_sub(previous.total_us) as f64;
+        current.stall_percent = (stalled_us * 100.0 / elapsed_us).min(100.0) as f32;
+    }
+}
+
+/// Preenche `stall_percent` a partir da amostra anterior do mesmo recurso.
+fn with_rates(
+    current: Option<PressureResource>,
+    previous: Option<&PressureResource>,
+    elapsed: Duration,
+) -> Option<PressureResource> {
+    let mut current = current?;
+    if let Some(previous) = previous {
+        stall_rate(&mut current.some, &previous.some, elapsed);
+        if let (Some(full), Some(previous_full)) = (current.full.as_mut(), previous.full.as_ref()) {
+            stall_rate(full, previous_full, elapsed);
+        }
+    }
+    Some(current)
+}
+
+/// Lê PSI do sistema em `proc_dir` e, se configurado, do cgroup `cgroup` sob `cgroup_root`.
+/// `previous` é a amostra de `elapsed` atrás, usada para as taxas de tempo travado.
+pub(crate) fn collect_pressure(
+    proc_dir: &Path,
+    cgroup_root: &Path,
+    cgroup: Option<&str>,
+    previous: Option<&PressureMetrics>,
+    elapsed: Duration,
+) -> PressureMetrics {
+    let previous_cgroup = previous
+        .and_then(|previous| previous.cgroup.as_ref())
+        .filter(|previous| Some(previous.path.as_str()) == cgroup);
+    let cgroup = cgroup.map(|path| {
+        let dir = cgroup_root.join(path);
+        CgroupPressureMetrics {
+            path: path.to_string(),
+            cpu: with_rates(
+                read_pressure(&dir.join("cpu.pressure")),
+                previous_cgroup.and_then(|previous| previous.cpu.as_ref()),
+                elapsed,
+            ),
+            memory: with_rates(
+                read_pressure(&dir.join("memory.pressure")),
+                previous_cgroup.and_then(|previous| previous.memory.as_ref()),
+                elapsed,
+            ),
+            io: with_rates(
+                read_pressure(&dir.join("io.pressure")),
+                previous_cgroup.and_then(|previous| previous.io.as_ref()),
+                elapsed,
+            ),
+        }
+    });
+
+    PressureMetrics {
+        cpu: with_rates(
+            read_pressure(&proc_dir.join("cpu")),
+            previous.and_then(|previous| previous.cpu.as_ref()),
+            elapsed,
+        ),
+        memory: with_rates(
+            read_pressure(&proc_dir.join("memory")),
+            previous.and_then(|previous| previous.memory.as_ref()),
+            elapsed,
+        ),
+        io: with_rates(
+            read_pressure(&proc_dir.join("io")),
+            previous.and_then(|previous| previous.io.as_ref()),
+            elapsed,
+        ),
+        cgroup,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::resolve_metric;
+    use crate::test_support::temp_fixture_dir;
+
+    #[test]
+    fn test_parse_pressure_reads_some_and_full() {
+        let memory = parse_pressure(
+            "some avg10=1.53 avg60=0.87 avg300=0.35 total=1234567\n\
+             full avg10=0.50 avg60=0.25 avg300=0.10 total=456789\n",
+        )
+        .expect("arquivo válido");
+        assert_eq!(memory.some.avg10, 1.53);
+        assert_eq!(memory.some.avg300, 0.35);
+        assert_eq!(memory.some.total_us, 1_234_567);
+        assert_eq!(memory.full.as_ref().map(|full| full.avg60), Some(0.25));
+
+        // CPU em kernels antes do 5.13 não tem a linha `full`.
+        let cpu = parse_pressure("some avg10=0.00 avg60=0.00 avg300=0.00 total=0\n").unwrap();
+        assert!(cpu.full.is_none());
+        assert!(parse_pressure("").is_none());
+        assert!(parse_pressure("some avg10=x total=1\n").is_none());
+    }
+
+    #[test]
+    fn test_collect_pressure_computes_stall_rates_and_reads_cgroup() {
+        let base = temp_fixture_dir();
+        let proc_dir = base.join("pressure");
+        let cgroup_dir = base.join("cgroup/user.slice");
+        fs::create_dir_all(&proc_dir).unwrap();
+        fs::create_dir_all(&cgroup_dir).unwrap();
+        let write = |path: &Path, some: u64, full: u64| {
+            fs::write(
+                path,
+                format!(
+                    "some avg10=2.00 avg60=1.00 avg300=0.50 total={some}\n\
+                     full avg10=1.00 avg60=0.50 avg300=0.25 total={full}\n"
+                ),
+            )
+            .unwrap();
+        };
+        write(&proc_dir.join("memory"), 1_000_000, 500_000);
+        write(&cgroup_dir.join("io.pressure"), 0, 0);
+
+        let first = collect_pressure(
+            &proc_dir,
+            &base.join("cgroup"),
+            Some("user.slice"),
+            None,
+            Duration::ZERO,
+        );
+        assert_eq!(first.memory.as_ref().unwrap().some.stall_percent, 0.0);
+        assert!(first.cpu.is_none());
+
+        write(&proc_dir.join("memory"), 1_250_000, 600_000);
+        write(&cgroup_dir.join("io.pressure"), 100_000, 0);
+        let second = collect_pressure(
+            &proc_dir,
+            &base.join("cgroup"),
+            Some("user.slice"),
+            Some(&first),
+            Duration::from_secs(1),
+        );
+        let memory = second.memory.as_ref().unwrap();
+        assert!((memory.some.stall_percent - 25.0).abs() < 0.01);
+        assert!((memory.full.as_ref().unwrap().stall_percent - 10.0).abs() < 0.01);
+        let cgroup = second.cgroup.as_ref().unwrap();
+        assert_eq!(cgroup.path, "user.slice");
+        assert!((cgroup.io.as_ref().unwrap().some.stall_percent - 10.0).abs() < 0.01);
+        assert!(cgroup.memory.is_none());
+
+        // Regras de alerta enxergam os valores pelo caminho no JSON.
+        let json = serde_json::json!({ "pressure": second });
+        assert_eq!(
+            resolve_metric(&json, "pressure.memory.full.avg10"),
+            vec![("pressure.memory.full.avg10".to_string(), Some(1.0))]
+        );
+
+        fs::remove_dir_all(base).unwrap();
+    }
+}
@@ -0,0 +1,189 @@
+//! Agregação do histórico em janelas longas (1 h, 24 h, 7 d).
+//!
+//! Cada amostra registrada no histórico curto também alimenta um bucket por janela,
+//! alinhado ao relógio de parede, que guarda mínimo, média e máximo.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::{HistoryRange, HistoryRangeMetrics, RollupBucket};
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HistoryRollups {
+    series: HashMap<String, [VecDeque<RollupBucket>; 3]>,
+}
+
+fn range_index(range: HistoryRange) -> usize {
+    match range {
+        HistoryRange::Hour => 0,
+        HistoryRange::Day => 1,
+        HistoryRange::Week => 2,
+    }
+}
+
+fn window_start_ms(range: HistoryRange, now_ms: u64) -> u64 {
+    now_ms.saturating_sub(range.duration_ms())
+}
+
+fn prune(buckets: &mut VecDeque<RollupBucket>, range: HistoryRange, now_ms: u64) {
+    let window_start = window_start_ms(range, now_ms);
+    while buckets
+        .front()
+        .is_some_and(|bucket| bucket.start_unix_ms + range.bucket_ms() <= window_start)
+    {
+        buckets.pop_front();
+    }
+}
+
+impl HistoryRollups {
+    pub(crate) fn record(&mut self, series: &str, value: f64, now_ms: u64) {
+        if !value.is_finite() {
+            return;
+        }
+
+        let tiers = self.series.entry(series.to_string()).or_default();
+        for range in HistoryRange::ALL {
+            let buckets = &mut tiers[range_index(range)];
+            let bucket_start = now_ms - now_ms % range.bucket_ms();
+
+            match buckets.back_mut() {
+                Some(bucket) if bucket.start_unix_ms == bucket_start => {
+                    bucket.samples += 1;
+                    bucket.min = bucket.min.min(value);
+                    bucket.max = bucket.max.max(value);
+                    bucket.avg += (value - bucket.avg) / bucket.samples as f64;
+                }
+                _ => buckets.push_back(RollupBucket {
+                    start_unix_ms: bucket_start,
+                    samples: 1,
+                    min: value,
+                    avg: value,
+                    max: value,
+                }),
+            }
+
+            prune(buckets, range, now_ms);
+        }
+    }
+
+    /// Buckets de `series` que ainda estão dentro de `range` em `now_ms`.
+    pub(crate) fn range(
+        &self,
+        series: &str,
+        range: HistoryRange,
+        now_ms: u64,
+    ) -> HistoryRangeMetrics {
+        let window_start = window_start_ms(range, now_ms);
+        let buckets = self
+            .series
+            .get(series)
+            .map(|tiers| {
+                tiers[range_index(range)]
+                    .iter()
+                    .filter(|bucket| bucket.start_unix_ms + range.bucket_ms() > window_start)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        HistoryRangeMetrics {
+            series: series.to_string(),
+            range,
+            bucket_ms: range.bucket_ms(),
+            buckets,
+        }
+    }
+
+    pub(crate) fn tiers(
+        &self,
+    ) -> impl Iterator<Item = (&str, HistoryRange, &VecDeque<RollupBucket>)> {
+        self.series.iter().flat_map(|(name, tiers)| {
+            HistoryRange::ALL
+                .into_iter()
+                .map(move |range| (name.as_str(), range, &tiers[range_index(range)]))
+        })
+    }
+
+    /// Substitui os buckets de uma janela, descartando os que já expiraram.
+    pub(crate) fn restore_tier(
+        &mut self,
+        series: &str,
+        range: HistoryRange,
+        buckets: Vec<RollupBucket>,
+        now_ms: u64,
+    ) {
+        let tier = &mut self.series.entry(series.to_string()).or_default()[range_index(range)];
+        *tier = buckets.into();
+        prune(tier, range, now_ms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_aggregates_min_avg_max_per_aligned_bucket() {
+        let mut rollups = HistoryRollups::default();
+
+        rollups.record("cpu_usage", 10.0, 60_000);
+        rollups.record("cpu_usage", 30.0, 61_000);
+        rollups.record("cpu_usage", 20.0, 89_999);
+        rollups.record("cpu_usage", 50.0, 90_000);
+
+        let hour = rollups.range("cpu_usage", HistoryRange::Hour, 90_000);
+        assert_eq!(hour.bucket_ms, 30_000);
+        assert_eq!(hour.buckets.len(), 2);
+        assert_eq!(
+            hour.buckets[0],
+            RollupBucket {
+                start_unix_ms: 60_000,
+                samples: 3,
+                min: 10.0,
+                avg: 20.0,
+                max: 30.0,
+            }
+        );
+        assert_eq!(hour.buckets[1].start_unix_ms, 90_000);
+
+        let day = rollups.range("cpu_usage", HistoryRange::Day, 90_000);
+        assert_eq!(day.buckets.len(), 1);
+        assert_eq!(day.buckets[0].samples, 4);
+        assert_eq!(day.buckets[0].max, 50.0);
+    }
+
+    #[test]
+    fn test_buckets_outside_window_are_dropped() {
+        let mut rollups = HistoryRollups::default();
+        let hour_ms = HistoryRange::Hour.duration_ms();
+
+        rollups.record("cpu_usage", 10.0, 0);
+        rollups.record("cpu_usage", 20.0, hour_ms + 30_000);
+
+        let hour = rollups.range("cpu_usage", HistoryRange::Hour, hour_ms + 30_000);
+        assert_eq!(hour.buckets.len(), 1);
+        assert_eq!(hour.buckets[0].avg, 20.0);
+        assert_eq!(
+            rollups
+                .range("cpu_usage", HistoryRange::Day, hour_ms + 30_000)
+                .buckets
+                .len(),
+            2
+        );
+
+        // Séries que deixaram de receber amostras também expiram na leitura.
+        let later = HistoryRange::Hour.duration_ms() * 3;
+        assert!(rollups
+            .range("cpu_usage", HistoryRange::Hour, later)
+            .buckets
+            .is_empty());
+    }
+
+    #[test]
+    fn test_range_of_unknown_series_is_empty() {
+        let rollups = HistoryRollups::default();
+        assert!(rollups
+            .range("gpu_usage", HistoryRange::Week, 0)
+            .buckets
+            .is_empty());
+    }
+}
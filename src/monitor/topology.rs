@@ -0,0 +1,205 @@
+//! Topologia das CPUs lógicas: núcleo físico, pacote, cluster e irmãs SMT a partir de
+//! `/sys/devices/system/cpu/cpuN/topology`, e o tipo de núcleo em CPUs híbridas pelas
+//! PMUs `cpu_core` (P-cores) e `cpu_atom` (E-cores) em `/sys/devices`.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use super::cpufreq::cpu_dirs;
+use super::{CpuCoreClassMetrics, CpuCoreMetrics, CpuCoreType};
+
+pub(crate) const DEVICES_SYSFS_PATH: &str = "/sys/devices";
+
+const HYBRID_PMUS: [(&str, CpuCoreType); 2] = [
+    ("cpu_core", CpuCoreType::Performance),
+    ("cpu_atom", CpuCoreType::Efficiency),
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CpuTopology {
+    pub(crate) core_type: Option<CpuCoreType>,
+    pub(crate) core_id: Option<u32>,
+    pub(crate) package_id: Option<u32>,
+    pub(crate) cluster_id: Option<u32>,
+    pub(crate) smt_siblings: Vec<usize>,
+}
+
+/// Interpreta listas do kernel como `0-3,8,10-11`.
+pub(crate) fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.trim()
+        .split(',')
+        .filter(|range| !range.is_empty())
+        .flat_map(|range| {
+            let (start, end) = range.split_once('-').unwrap_or((range, range));
+            match (start.trim().parse::<usize>(), end.trim().parse::<usize>()) {
+                (Ok(start), Ok(end)) if start <= end => start..end + 1,
+                _ => 0..0,
+            }
+        })
+        .collect()
+}
+
+/// `-1` (sem informação, como `cluster_id` em algumas VMs) vira `None`.
+fn read_id(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn read_cpu_list(path: &Path) -> Option<Vec<usize>> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|content| parse_cpu_list(&content))
+}
+
+/// Lê a topologia de cada `cpuN` em `cpu_base`, com o tipo de núcleo vindo das PMUs
+/// híbridas em `devices_base`.
+pub(crate) fn read_cpu_topology(
+    cpu_base: &Path,
+    devices_base: &Path,
+) -> HashMap<usize, CpuTopology> {
+    let core_types: HashMap<usize, CpuCoreType> = HYBRID_PMUS
+        .iter()
+        .filter_map(|(pmu, core_type)| {
+            read_cpu_list(&devices_base.join(pmu).join("cpus"))
+                .map(|cpus| cpus.into_iter().map(move |cpu| (cpu, *core_type)))
+        })
+        .flatten()
+        .collect();
+
+    cpu_dirs(cpu_base)
+        .into_iter()
+        .map(|(id, dir)| {
+            let topology = dir.join("topology");
+            // `core_cpus_list` substituiu `thread_siblings_list` no kernel 5.7.
+            let siblings = read_cpu_list(&topology.join("core_cpus_list"))
+                .or_else(|| read_cpu_list(&topology.join("thread_siblings_list")))
+                .unwrap_or_default();
+            (
+                id,
+                CpuTopology {
+                    core_type: core_types.get(&id).copied(),
+                    core_id: read_id(&topology.join("core_id")),
+                    package_id: read_id(&topology.join("physical_package_id")),
+                    cluster_id: read_id(&topology.join("cluster_id")),
+                    smt_siblings: siblings.into_iter().filter(|cpu| *cpu != id).collect(),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Agrupa `cores` por tipo de núcleo, na ordem P-cores e depois E-cores.
+pub(crate) fn aggregate_core_classes(cores: &[CpuCoreMetrics]) -> Vec<CpuCoreClassMetrics> {
+    HYBRID_PMUS
+        .iter()
+        .filter_map(|(_, core_type)| {
+            let members: Vec<&CpuCoreMetrics> = cores
+                .iter()
+                .filter(|core| core.core_type == Some(*core_type))
+                .collect();
+            if members.is_empty() {
+                return None;
+            }
+            let physical_cores: HashSet<(Option<u32>, Option<u32>, usize)> = members
+                .iter()
+                .map(|core| {
+                    // Sem `core_id`, cada CPU lógica conta como um núcleo.
+                    let fallback = if core.core_id.is_some() { 0 } else { core.id };
+                    (core.package_id, core.core_id, fallback)
+                })
+                .collect();
+            let count = members.len();
+            Some(CpuCoreClassMetrics {
+                core_type: *core_type,
+                cpu_count: count,
+                physical_core_count: physical_cores.len(),
+                usage_percent: members.iter().map(|core| core.usage_percent).sum::<f32>()
+                    / count as f32,
+                frequency_mhz: members.iter().map(|core| core.frequency_mhz).sum::<u64>()
+                    / count as u64,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_fixture_dir;
+
+    #[test]
+    fn test_parse_cpu_list_expands_ranges() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list("5"), vec![5]);
+        assert!(parse_cpu_list("").is_empty());
+        assert!(parse_cpu_list("3-1").is_empty());
+    }
+
+    #[test]
+    fn test_read_cpu_topology_detects_hybrid_cores_and_smt_siblings() {
+        let base = temp_fixture_dir();
+        let cpu_base = base.join("system/cpu");
+        // cpu0/cpu1: P-core com SMT; cpu2/cpu3: E-cores do mesmo cluster.
+        for (cpu, core_id, siblings, cluster) in [
+            (0, 0, "0-1", "0"),
+            (1, 0, "0-1", "0"),
+            (2, 8, "2", "1"),
+            (3, 9, "3", "1"),
+        ] {
+            let topology = cpu_base.join(format!("cpu{cpu}/topology"));
+            fs::create_dir_all(&topology).unwrap();
+            fs::write(topology.join("core_id"), format!("{core_id}\n")).unwrap();
+            fs::write(topology.join("physical_package_id"), "0\n").unwrap();
+            fs::write(topology.join("cluster_id"), format!("{cluster}\n")).unwrap();
+            fs::write(topology.join("thread_siblings_list"), siblings).unwrap();
+        }
+        fs::create_dir_all(cpu_base.join("cpufreq")).unwrap();
+        fs::create_dir_all(base.join("cpu_core")).unwrap();
+        fs::create_dir_all(base.join("cpu_atom")).unwrap();
+        fs::write(base.join("cpu_core/cpus"), "0-1\n").unwrap();
+        fs::write(base.join("cpu_atom/cpus"), "2-3\n").unwrap();
+
+        let topology = read_cpu_topology(&cpu_base, &base);
+        assert_eq!(topology.len(), 4);
+        assert_eq!(topology[&0].core_type, Some(CpuCoreType::Performance));
+        assert_eq!(topology[&0].smt_siblings, vec![1]);
+        assert_eq!(topology[&3].core_type, Some(CpuCoreType::Efficiency));
+        assert_eq!(topology[&3].core_id, Some(9));
+        assert_eq!(topology[&3].cluster_id, Some(1));
+        assert!(topology[&3].smt_siblings.is_empty());
+
+        let cores: Vec<CpuCoreMetrics> = (0..4)
+            .map(|id| CpuCoreMetrics {
+                id,
+                usage_percent: [80.0, 40.0, 10.0, 30.0][id],
+                frequency_mhz: [4000, 4200, 3000, 3200][id],
+                core_type: topology[&id].core_type,
+                core_id: topology[&id].core_id,
+                package_id: topology[&id].package_id,
+                ..CpuCoreMetrics::default()
+            })
+            .collect();
+        let classes = aggregate_core_classes(&cores);
+        assert_eq!(
+            classes,
+            vec![
+                CpuCoreClassMetrics {
+                    core_type: CpuCoreType::Performance,
+                    cpu_count: 2,
+                    physical_core_count: 1,
+                    usage_percent: 60.0,
+                    frequency_mhz: 4100,
+                },
+                CpuCoreClassMetrics {
+                    core_type: CpuCoreType::Efficiency,
+                    cpu_count: 2,
+                    physical_core_count: 2,
+                    usage_percent: 20.0,
+                    frequency_mhz: 3100,
+                },
+            ]
+        );
+
+        fs::remove_dir_all(base).unwrap();
+    }
+}
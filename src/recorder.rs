@@ -0,0 +1,508 @@
+//! Gravação de snapshots de `SystemMetrics` em SQLite, para correlacionar incidentes com o
+//! estado da máquina ao longo de semanas.
+//!
+//! Cada snapshot ocupa uma linha em `snapshots`, com os escalares de CPU, memória e sistema,
+//! e linhas filhas em `disks`, `interfaces`, `sensors` e `gpus` ligadas por `snapshot_id`.
+//! Apagar um snapshot apaga as linhas filhas (`ON DELETE CASCADE`).
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection, Statement};
+use serde_json::{Map, Number, Value};
+
+use crate::monitor::SystemMetrics;
+
+/// Versão do esquema gravada em `PRAGMA user_version`.
+const SCHEMA_VERSION: i32 = 1;
+/// A retenção é aplicada no máximo uma vez por hora; o custo do DELETE fica diluído.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS snapshots (
+    id INTEGER PRIMARY KEY,
+    timestamp_ms INTEGER NOT NULL,
+    hostname TEXT NOT NULL,
+    cpu_usage_percent REAL NOT NULL,
+    cpu_user_percent REAL NOT NULL,
+    cpu_system_percent REAL NOT NULL,
+    cpu_idle_percent REAL NOT NULL,
+    cpu_steal_percent REAL NOT NULL,
+    cpu_frequency_mhz INTEGER NOT NULL,
+    memory_total_gb REAL NOT NULL,
+    memory_used_gb REAL NOT NULL,
+    memory_available_gb REAL NOT NULL,
+    memory_usage_percent REAL NOT NULL,
+    swap_total_gb REAL NOT NULL,
+    swap_used_gb REAL NOT NULL,
+    load_1 REAL NOT NULL,
+    load_5 REAL NOT NULL,
+    load_15 REAL NOT NULL,
+    uptime_seconds INTEGER NOT NULL,
+    process_count INTEGER NOT NULL,
+    gateway_latency_ms REAL
+);
+CREATE INDEX IF NOT EXISTS snapshots_timestamp ON snapshots (timestamp_ms);
+CREATE TABLE IF NOT EXISTS disks (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    mount_point TEXT NOT NULL,
+    total_gb REAL NOT NULL,
+    used_gb REAL NOT NULL,
+    usage_percent REAL NOT NULL,
+    read_bytes_per_sec INTEGER NOT NULL,
+    write_bytes_per_sec INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS disks_snapshot ON disks (snapshot_id);
+CREATE TABLE IF NOT EXISTS interfaces (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    is_up INTEGER NOT NULL,
+    bytes_received INTEGER NOT NULL,
+    bytes_transmitted INTEGER NOT NULL,
+    packets_received INTEGER NOT NULL,
+    packets_transmitted INTEGER NOT NULL,
+    errors_received INTEGER NOT NULL,
+    errors_transmitted INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS interfaces_snapshot ON interfaces (snapshot_id);
+CREATE TABLE IF NOT EXISTS sensors (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots (id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    chip TEXT,
+    label TEXT NOT NULL,
+    value REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS sensors_snapshot ON sensors (snapshot_id);
+CREATE TABLE IF NOT EXISTS gpus (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    vendor TEXT NOT NULL,
+    usage_percent REAL,
+    vram_used_gb REAL,
+    vram_total_gb REAL,
+    temperature_celsius REAL,
+    power_watts REAL,
+    shader_clock_mhz INTEGER,
+    memory_clock_mhz INTEGER,
+    fan_rpm INTEGER
+);
+CREATE INDEX IF NOT EXISTS gpus_snapshot ON gpus (snapshot_id);
+";
+
+/// Tabelas filhas, na ordem em que aparecem em cada snapshot exportado.
+const CHILD_TABLES: [&str; 4] = ["disks", "interfaces", "sensors", "gpus"];
+
+fn open_connection(path: &Path) -> Result<Connection, String> {
+    let describe = |error: rusqlite::Error| format!("{}: {error}", path.display());
+    let connection = Connection::open(path).map_err(describe)?;
+    // WAL permite que `monitor-tray query` leia enquanto o serviço grava.
+    connection
+        .execute_batch(
+            "PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON; PRAGMA busy_timeout = 5000;",
+        )
+        .map_err(describe)?;
+    Ok(connection)
+}
+
+pub struct MetricsRecorder {
+    connection: Connection,
+    retention: Duration,
+    last_prune: Option<Instant>,
+}
+
+impl MetricsRecorder {
+    /// Abre (ou cria) o banco em `path`, criando o diretório e o esquema quando necessário.
+    pub fn open(path: &Path, retention: Duration) -> Result<Self, String> {
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)
+                .map_err(|error| format!("{}: {error}", directory.display()))?;
+        }
+        let connection = open_connection(path)?;
+        let version: i32 = connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(|error| error.to_string())?;
+        if version > SCHEMA_VERSION {
+            return Err(format!(
+                "{}: esquema {version} é mais novo que o suportado ({SCHEMA_VERSION})",
+                path.display()
+            ));
+        }
+        connection
+            .execute_batch(SCHEMA)
+            .and_then(|_| connection.pragma_update(None, "user_version", SCHEMA_VERSION))
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+        Ok(Self {
+            connection,
+            retention,
+            last_prune: None,
+        })
+    }
+
+    pub fn set_retention(&mut self, retention: Duration) {
+        if retention != self.retention {
+            self.retention = retention;
+            self.last_prune = None;
+        }
+    }
+
+    /// Grava `metrics` como um snapshot em `timestamp_ms` numa única transação e, quando
+    /// devido, apaga o que passou da retenção.
+    pub fn record(&mut self, metrics: &SystemMetrics, timestamp_ms: u64) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+        let (load_1, load_5, load_15) = metrics.load_average;
+        transaction.execute(
+            "INSERT INTO snapshots (
+                timestamp_ms, hostname, cpu_usage_percent, cpu_user_percent, cpu_system_percent,
+                cpu_idle_percent, cpu_steal_percent, cpu_frequency_mhz, memory_total_gb,
+                memory_used_gb, memory_available_gb, memory_usage_percent, swap_total_gb,
+                swap_used_gb, load_1, load_5, load_15, uptime_seconds, process_count,
+                gateway_latency_ms
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+                ?17, ?18, ?19, ?20)",
+            params![
+                timestamp_ms as i64,
+                metrics.system_info.hostname,
+                metrics.cpu.usage_percent,
+                metrics.cpu.user_percent,
+                metrics.cpu.system_percent,
+                metrics.cpu.idle_percent,
+                metrics.cpu.steal_percent,
+                metrics.cpu.frequency as i64,
+                metrics.memory.total_memory,
+                metrics.memory.used_memory,
+                metrics.memory.available_memory,
+                metrics.memory.usage_percent,
+                metrics.memory.total_swap,
+                metrics.memory.used_swap,
+                load_1,
+                load_5,
+                load_15,
+                metrics.uptime as i64,
+                metrics.system_info.process_count as i64,
+                metrics.network.gateway_latency_ms,
+            ],
+        )?;
+        let snapshot_id = transaction.last_insert_rowid();
+
+        {
+            let mut insert = transaction
+                .prepare_cached("INSERT INTO disks VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")?;
+            for disk in &metrics.disk.disks {
+                insert.execute(params![
+                    snapshot_id,
+                    disk.name,
+                    disk.mount_point,
+                    disk.total_space,
+                    disk.used_space,
+                    disk.usage_percent,
+                    disk.read_bytes_per_sec as i64,
+                    disk.write_bytes_per_sec as i64,
+                ])?;
+            }
+
+            let mut insert = transaction.prepare_cached(
+                "INSERT INTO interfaces VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            for (name, interface) in &metrics.network.interfaces {
+                insert.execute(params![
+                    snapshot_id,
+                    name,
+                    interface.is_up,
+                    interface.bytes_received as i64,
+                    interface.bytes_transmitted as i64,
+                    interface.packets_received as i64,
+                    interface.packets_transmitted as i64,
+                    interface.errors_received as i64,
+                    interface.errors_transmitted as i64,
+                ])?;
+            }
+
+            let sensors = &metrics.sensors;
+            let mut insert =
+                transaction.prepare_cached("INSERT INTO sensors VALUES (?1, ?2, ?3, ?4, ?5)")?;
+            for sensor in &sensors.temperatures {
+                insert.execute(params![
+                    snapshot_id,
+                    "temperature",
+                    sensor.chip,
+                    sensor.label,
+                    sensor.temperature_celsius,
+                ])?;
+            }
+            let others =
+                sensors
+                    .fans
+                    .iter()
+                    .map(|fan| ("fan_rpm", &fan.chip, &fan.label, fan.rpm as f64))
+                    .chain(sensors.voltages.iter().map(|sensor| {
+                        ("voltage", &sensor.chip, &sensor.label, sensor.volts as f64)
+                    }))
+                    .chain(
+                        sensors.currents.iter().map(|sensor| {
+                            ("current", &sensor.chip, &sensor.label, sensor.amps as f64)
+                        }),
+                    )
+                    .chain(
+                        sensors.powers.iter().map(|sensor| {
+                            ("power", &sensor.chip, &sensor.label, sensor.watts as f64)
+                        }),
+                    );
+            for (kind, chip, label, value) in others {
+                let chip = (!chip.is_empty()).then_some(chip);
+                insert.execute(params![snapshot_id, kind, chip, label, value])?;
+            }
+
+            let mut insert = transaction.prepare_cached(
+                "INSERT INTO gpus VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?;
+            for gpu in &metrics.gpus {
+                insert.execute(params![
+                    snapshot_id,
+                    gpu.name,
+                    gpu.vendor.as_str(),
+                    gpu.usage_percent,
+                    gpu.vram_used_gb,
+                    gpu.vram_total_gb,
+                    gpu.temperature_celsius,
+                    gpu.power_watts,
+                    gpu.shader_clock_mhz.map(|mhz| mhz as i64),
+                    gpu.memory_clock_mhz.map(|mhz| mhz as i64),
+                    gpu.fan_rpm.map(|rpm| rpm as i64),
+                ])?;
+            }
+        }
+        transaction.commit()?;
+
+        if self
+            .last_prune
+            .is_none_or(|last| last.elapsed() >= PRUNE_INTERVAL)
+        {
+            self.last_prune = Some(Instant::now());
+            self.prune(timestamp_ms)?;
+        }
+        Ok(())
+    }
+
+    /// Apaga snapshots mais antigos que a retenção; retorna quantos foram removidos.
+    pub fn prune(&self, now_ms: u64) -> rusqlite::Result<usize> {
+        let cutoff = now_ms.saturating_sub(self.retention.as_millis() as u64);
+        self.connection.execute(
+            "DELETE FROM snapshots WHERE timestamp_ms < ?1",
+            [cutoff as i64],
+        )
+    }
+}
+
+fn json_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(integer) => Value::from(integer),
+        ValueRef::Real(real) => Number::from_f64(real).map_or(Value::Null, Value::Number),
+        ValueRef::Text(text) => Value::String(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(_) => Value::Null,
+    }
+}
+
+/// Converte cada linha num objeto JSON com os nomes das colunas.
+fn query_objects(
+    statement: &mut Statement<'_>,
+    range: [i64; 2],
+) -> rusqlite::Result<Vec<Map<String, Value>>> {
+    let columns: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let mut rows = statement.query(range)?;
+    let mut objects = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut object = Map::new();
+        for (index, column) in columns.iter().enumerate() {
+            object.insert(column.clone(), json_value(row.get_ref(index)?));
+        }
+        objects.push(object);
+    }
+    Ok(objects)
+}
+
+/// Exporta os snapshots com `from_ms <= timestamp_ms <= to_ms`, em ordem cronológica,
+/// cada um com as linhas de discos, interfaces, sensores e GPUs aninhadas.
+pub fn query_snapshots(path: &Path, from_ms: u64, to_ms: u64) -> Result<Vec<Value>, String> {
+    if !path.exists() {
+        return Err(format!(
+            "{}: banco não encontrado; ative [recorder] na configuração",
+            path.display()
+        ));
+    }
+    let connection = open_connection(path)?;
+    let range = [from_ms as i64, to_ms as i64];
+    let run = || -> rusqlite::Result<Vec<Value>> {
+        let mut snapshots = query_objects(
+            &mut connection.prepare(
+                "SELECT * FROM snapshots WHERE timestamp_ms BETWEEN ?1 AND ?2
+                 ORDER BY timestamp_ms, id",
+            )?,
+            range,
+        )?;
+        let positions: HashMap<i64, usize> = snapshots
+            .iter()
+            .enumerate()
+            .filter_map(|(position, snapshot)| Some((snapshot.get("id")?.as_i64()?, position)))
+            .collect();
+        for snapshot in &mut snapshots {
+            for table in CHILD_TABLES {
+                snapshot.insert(table.to_string(), Value::Array(Vec::new()));
+            }
+        }
+
+        for table in CHILD_TABLES {
+            let mut statement = connection.prepare(&format!(
+                "SELECT {table}.* FROM {table} JOIN snapshots ON snapshots.id = {table}.snapshot_id
+                 WHERE snapshots.timestamp_ms BETWEEN ?1 AND ?2 ORDER BY {table}.rowid"
+            ))?;
+            for mut row in query_objects(&mut statement, range)? {
+                let Some(Value::Number(id)) = row.remove("snapshot_id") else {
+                    continue;
+                };
+                let Some(&position) = id.as_i64().and_then(|id| positions.get(&id)) else {
+                    continue;
+                };
+                if let Some(Value::Array(rows)) = snapshots[position].get_mut(table) {
+                    rows.push(Value::Object(row));
+                }
+            }
+        }
+
+        Ok(snapshots
+            .into_iter()
+            .map(|mut snapshot| {
+                snapshot.remove("id");
+                Value::Object(snapshot)
+            })
+            .collect())
+    };
+    run().map_err(|error| format!("{}: {error}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::{
+        DiskInfo, FanSensor, GpuInfo, GpuVendor, NetworkInterface, SystemMonitor,
+    };
+    use crate::test_support::temp_fixture_dir;
+
+    const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+    fn temp_database() -> std::path::PathBuf {
+        temp_fixture_dir().join("metrics.sqlite")
+    }
+
+    fn sample_metrics() -> SystemMetrics {
+        let mut metrics = SystemMonitor::new_empty().get_all_metrics();
+        metrics.cpu.usage_percent = 42.5;
+        metrics.disk.disks = vec![DiskInfo {
+            name: "nvme0n1p2".to_string(),
+            mount_point: "/".to_string(),
+            total_space: 500.0,
+            available_space: 200.0,
+            used_space: 300.0,
+            usage_percent: 60.0,
+            read_bytes_per_sec: 1024,
+            write_bytes_per_sec: 2048,
+        }];
+        metrics.network.interfaces = HashMap::from([(
+            "eth0".to_string(),
+            NetworkInterface {
+                bytes_received: 10,
+                bytes_transmitted: 20,
+                packets_received: 1,
+                packets_transmitted: 2,
+                errors_received: 0,
+                errors_transmitted: 0,
+                is_up: true,
+            },
+        )]);
+        metrics.sensors.temperatures.clear();
+        metrics.sensors.voltages.clear();
+        metrics.sensors.currents.clear();
+        metrics.sensors.powers.clear();
+        metrics.sensors.fans = vec![FanSensor {
+            label: "nct6798: CPU Fan".to_string(),
+            chip: "nct6798".to_string(),
+            rpm: 1450,
+            duty_percent: None,
+        }];
+        metrics.gpus = vec![GpuInfo {
+            name: "Radeon".to_string(),
+            vendor: GpuVendor::Amd,
+            usage_percent: Some(12.0),
+            vram_used_gb: None,
+            vram_total_gb: None,
+            vram_usage_percent: None,
+            shader_clock_mhz: Some(2400),
+            memory_clock_mhz: None,
+            temperature_celsius: None,
+            power_watts: None,
+            fan_rpm: None,
+            fan_duty_percent: None,
+        }];
+        metrics
+    }
+
+    #[test]
+    fn test_query_returns_snapshots_in_range_with_child_rows() {
+        let path = temp_database();
+        let mut recorder = MetricsRecorder::open(&path, Duration::from_secs(3600)).unwrap();
+        let metrics = sample_metrics();
+        recorder.record(&metrics, 1_000).unwrap();
+        recorder.record(&metrics, 2_000).unwrap();
+        recorder.record(&metrics, 3_000).unwrap();
+
+        let snapshots = query_snapshots(&path, 1_500, 3_000).unwrap();
+
+        assert_eq!(snapshots.len(), 2);
+        let first = &snapshots[0];
+        assert_eq!(first["timestamp_ms"], 2_000);
+        assert_eq!(first["cpu_usage_percent"], 42.5);
+        assert!(first.get("id").is_none());
+        assert_eq!(first["disks"][0]["mount_point"], "/");
+        assert_eq!(first["disks"][0]["read_bytes_per_sec"], 1024);
+        assert!(first["disks"][0].get("snapshot_id").is_none());
+        assert_eq!(first["interfaces"][0]["is_up"], 1);
+        assert_eq!(first["sensors"][0]["kind"], "fan_rpm");
+        assert_eq!(first["sensors"][0]["chip"], "nct6798");
+        assert_eq!(first["sensors"][0]["value"], 1450.0);
+        assert_eq!(first["gpus"][0]["vendor"], "amd");
+        assert_eq!(first["gpus"][0]["vram_used_gb"], Value::Null);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_prune_removes_expired_snapshots_and_their_rows() {
+        let path = temp_database();
+        let mut recorder = MetricsRecorder::open(&path, Duration::from_millis(DAY_MS)).unwrap();
+        let now = 10 * DAY_MS;
+        recorder
+            .record(&sample_metrics(), now - 2 * DAY_MS)
+            .unwrap();
+        recorder
+            .record(&sample_metrics(), now - DAY_MS / 2)
+            .unwrap();
+
+        assert_eq!(recorder.prune(now).unwrap(), 1);
+
+        let remaining: i64 = recorder
+            .connection
+            .query_row("SELECT COUNT(*) FROM disks", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(remaining, 1);
+        assert_eq!(query_snapshots(&path, 0, now).unwrap().len(), 1);
+        drop(recorder);
+        assert!(MetricsRecorder::open(&path, Duration::from_millis(DAY_MS)).is_ok());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -1,15 +1,13 @@
-use std::io;
-use std::process::Stdio;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use serde_json::Value;
-use tokio::io::AsyncReadExt;
 use tokio::process::Command;
-use tokio::sync::{Mutex, Notify};
+use tokio::sync::{watch, Notify};
 
 use crate::monitor::{NetworkSpeedTestPhase, NetworkSpeedTestState, NetworkSpeedTestStatus};
+use crate::subprocess::{run_command, CommandError};
 
 const SPEED_TEST_TIMEOUT: Duration = Duration::from_secs(45);
 
@@ -19,7 +17,7 @@ pub struct NetworkSpeedTestManager {
 }
 
 struct Inner {
-    status: Mutex<NetworkSpeedTestStatus>,
+    status: watch::Sender<NetworkSpeedTestStatus>,
     running: AtomicBool,
     cancel_notify: Notify,
 }
@@ -62,7 +60,7 @@ impl NetworkSpeedTestManager {
     pub fn new() -> Self {
         Self {
             inner: Arc::new(Inner {
-                status: Mutex::new(NetworkSpeedTestStatus::default()),
+                status: watch::Sender::new(NetworkSpeedTestStatus::default()),
                 running: AtomicBool::new(false),
                 cancel_notify: Notify::new(),
             }),
@@ -100,19 +98,25 @@ impl NetworkSpeedTestManager {
     }
 
     pub async fn get_status(&self) -> NetworkSpeedTestStatus {
-        self.inner.status.lock().await.clone()
+        self.inner.status.borrow().clone()
+    }
+
+    /// Retorna um receptor notificado a cada mudança de estado ou fase do teste.
+    pub fn subscribe(&self) -> watch::Receiver<NetworkSpeedTestStatus> {
+        self.inner.status.subscribe()
     }
 
     async fn set_status(&self, status: NetworkSpeedTestStatus) {
-        *self.inner.status.lock().await = status;
+        self.inner.status.send_replace(status);
     }
 
     async fn update_phase(&self, phase: NetworkSpeedTestPhase, tool: Option<&str>) {
-        let mut status = self.inner.status.lock().await;
-        status.phase = phase;
-        if let Some(tool_name) = tool {
-            status.tool = Some(tool_name.to_string());
-        }
+        self.inner.status.send_modify(|status| {
+            status.phase = phase;
+            if let Some(tool_name) = tool {
+                status.tool = Some(tool_name.to_string());
+            }
+        });
     }
 
     async fn run_in_background(&self) {
@@ -213,67 +217,30 @@ impl NetworkSpeedTestManager {
     }
 
     async fn run_tool(&self, tool: &ToolSpec) -> Result<ParsedSpeedTest, SpeedTestExecutionError> {
-        let mut child = Command::new(tool.command)
-            .args(tool.args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|error| {
-                if error.kind() == io::ErrorKind::NotFound {
-                    SpeedTestExecutionError::ToolNotFound
-                } else {
-                    SpeedTestExecutionError::Failed(format!(
-                        "Falha ao iniciar `{}`: {}",
-                        tool.command, error
-                    ))
-                }
-            })?;
-
-        let stdout = child.stdout.take().ok_or_else(|| {
-            SpeedTestExecutionError::Failed("Falha ao capturar stdout do speed test".to_string())
-        })?;
-        let stderr = child.stderr.take().ok_or_else(|| {
-            SpeedTestExecutionError::Failed("Falha ao capturar stderr do speed test".to_string())
+        let output = run_command(
+            Command::new(tool.command).args(tool.args),
+            None,
+            self.inner.cancel_notify.notified(),
+            SPEED_TEST_TIMEOUT,
+        )
+        .await
+        .map_err(|error| match error {
+            CommandError::NotFound => SpeedTestExecutionError::ToolNotFound,
+            CommandError::Spawn(error) => SpeedTestExecutionError::Failed(format!(
+                "Falha ao iniciar `{}`: {}",
+                tool.command, error
+            )),
+            CommandError::Wait(error) => SpeedTestExecutionError::Failed(format!(
+                "Falha ao aguardar `{}`: {}",
+                tool.command, error
+            )),
+            CommandError::Cancelled => SpeedTestExecutionError::Cancelled,
+            CommandError::TimedOut => SpeedTestExecutionError::TimedOut,
         })?;
+        let stdout_text = output.stdout;
+        let stderr_text = output.stderr;
 
-        let stdout_task = tokio::spawn(async move {
-            let mut reader = stdout;
-            let mut buffer = Vec::new();
-            let _ = reader.read_to_end(&mut buffer).await;
-            buffer
-        });
-        let stderr_task = tokio::spawn(async move {
-            let mut reader = stderr;
-            let mut buffer = Vec::new();
-            let _ = reader.read_to_end(&mut buffer).await;
-            buffer
-        });
-
-        let exit_status = tokio::select! {
-            status = child.wait() => {
-                status.map_err(|error| SpeedTestExecutionError::Failed(format!(
-                    "Falha ao aguardar `{}`: {}",
-                    tool.command, error
-                )))?
-            }
-            _ = self.inner.cancel_notify.notified() => {
-                let _ = child.start_kill();
-                let _ = child.wait().await;
-                return Err(SpeedTestExecutionError::Cancelled);
-            }
-            _ = tokio::time::sleep(SPEED_TEST_TIMEOUT) => {
-                let _ = child.start_kill();
-                let _ = child.wait().await;
-                return Err(SpeedTestExecutionError::TimedOut);
-            }
-        };
-
-        let stdout_bytes = stdout_task.await.unwrap_or_default();
-        let stderr_bytes = stderr_task.await.unwrap_or_default();
-        let stdout_text = String::from_utf8_lossy(&stdout_bytes).trim().to_string();
-        let stderr_text = String::from_utf8_lossy(&stderr_bytes).trim().to_string();
-
-        if !exit_status.success() {
+        if !output.status.success() {
             return Err(SpeedTestExecutionError::Failed(build_exit_error_message(
                 tool.command,
                 &stderr_text,
@@ -447,4 +414,19 @@ mod tests {
             Some("BR · node1.example.com")
         );
     }
+
+    #[tokio::test]
+    async fn test_subscribe_observes_phase_updates() {
+        let manager = NetworkSpeedTestManager::new();
+        let mut status = manager.subscribe();
+
+        manager
+            .update_phase(NetworkSpeedTestPhase::Parsing, Some("speedtest"))
+            .await;
+
+        assert!(status.has_changed().expect("sender should be alive"));
+        let current = status.borrow_and_update().clone();
+        assert!(matches!(current.phase, NetworkSpeedTestPhase::Parsing));
+        assert_eq!(current.tool.as_deref(), Some("speedtest"));
+    }
 }
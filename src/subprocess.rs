@@ -0,0 +1,195 @@
+//! Execução de comandos externos com captura de saída, cancelamento e timeout.
+//!
+//! Compartilhado pelo speed test e pelos comandos de alerta.
+
+use std::future::Future;
+use std::io;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, Command};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+pub(crate) struct CommandOutput {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+pub(crate) enum CommandError {
+    /// O executável não existe.
+    NotFound,
+    Spawn(io::Error),
+    Wait(io::Error),
+    Cancelled,
+    TimedOut,
+}
+
+/// Lê `reader` até EOF ou até `deadline`; o `bool` indica se o prazo expirou.
+fn spawn_reader(
+    reader: Option<impl AsyncRead + Unpin + Send + 'static>,
+    deadline: Instant,
+) -> JoinHandle<(Vec<u8>, bool)> {
+    tokio::spawn(async move {
+        let mut buffer = Vec::new();
+        let mut expired = false;
+        if let Some(mut reader) = reader {
+            expired = tokio::time::timeout_at(deadline, reader.read_to_end(&mut buffer))
+                .await
+                .is_err();
+        }
+        (buffer, expired)
+    })
+}
+
+/// Mata o grupo de processos inteiro, incluindo filhos que o comando deixou em
+/// background; `process_group(0)` faz do PID do filho o ID do grupo.
+fn kill_process_group(pgid: Option<u32>) {
+    if let Some(pgid) = pgid.and_then(|pgid| libc::pid_t::try_from(pgid).ok()) {
+        // SAFETY: `killpg` só recebe inteiros e não acessa memória do processo.
+        unsafe {
+            libc::killpg(pgid, libc::SIGKILL);
+        }
+    }
+}
+
+async fn kill_and_reap(child: &mut Child, pgid: Option<u32>) {
+    kill_process_group(pgid);
+    let _ = child.start_kill();
+    let _ = child.wait().await;
+}
+
+/// Executa `command` capturando stdout/stderr e, se houver, escrevendo `stdin`.
+///
+/// O comando roda num grupo de processos próprio. Quando `cancel` completa ou
+/// `timeout` expira, o grupo todo é encerrado; o mesmo prazo limita a leitura da
+/// saída, que processos em background herdados poderiam manter aberta.
+pub(crate) async fn run_command(
+    command: &mut Command,
+    stdin: Option<Vec<u8>>,
+    cancel: impl Future<Output = ()>,
+    timeout: Duration,
+) -> Result<CommandOutput, CommandError> {
+    if stdin.is_some() {
+        command.stdin(Stdio::piped());
+    }
+    let deadline = Instant::now() + timeout;
+    let mut child = command
+        .process_group(0)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| {
+            if error.kind() == io::ErrorKind::NotFound {
+                CommandError::NotFound
+            } else {
+                CommandError::Spawn(error)
+            }
+        })?;
+
+    if let (Some(input), Some(mut writer)) = (stdin, child.stdin.take()) {
+        // Escrita em task própria para não travar se o processo não ler a entrada;
+        // o pipe é fechado ao final para sinalizar EOF.
+        tokio::spawn(async move {
+            let _ = writer.write_all(&input).await;
+        });
+    }
+
+    let pgid = child.id();
+    let stdout_task = spawn_reader(child.stdout.take(), deadline);
+    let stderr_task = spawn_reader(child.stderr.take(), deadline);
+
+    let status = tokio::select! {
+        status = child.wait() => status.map_err(CommandError::Wait)?,
+        _ = cancel => {
+            kill_and_reap(&mut child, pgid).await;
+            return Err(CommandError::Cancelled);
+        }
+        _ = tokio::time::sleep_until(deadline) => {
+            kill_and_reap(&mut child, pgid).await;
+            return Err(CommandError::TimedOut);
+        }
+    };
+
+    let (stdout_bytes, stdout_expired) = stdout_task.await.unwrap_or_default();
+    let (stderr_bytes, stderr_expired) = stderr_task.await.unwrap_or_default();
+    if stdout_expired || stderr_expired {
+        // O shell saiu, mas algum processo em background ainda segura os pipes.
+        kill_process_group(pgid);
+    }
+
+    Ok(CommandOutput {
+        status,
+        stdout: String::from_utf8_lossy(&stdout_bytes).trim().to_string(),
+        stderr: String::from_utf8_lossy(&stderr_bytes).trim().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_command_feeds_stdin_and_captures_output() {
+        let output = run_command(
+            Command::new("sh").args(["-c", "cat; echo aviso >&2"]),
+            Some(b"entrada\n".to_vec()),
+            std::future::pending(),
+            Duration::from_secs(5),
+        )
+        .await;
+
+        let Ok(output) = output else {
+            panic!("command should succeed");
+        };
+        assert!(output.status.success());
+        assert_eq!(output.stdout, "entrada");
+        assert_eq!(output.stderr, "aviso");
+    }
+
+    #[tokio::test]
+    async fn test_run_command_kills_process_on_timeout_and_cancel() {
+        let timed_out = run_command(
+            Command::new("sleep").arg("5"),
+            None,
+            std::future::pending(),
+            Duration::from_millis(50),
+        )
+        .await;
+        assert!(matches!(timed_out, Err(CommandError::TimedOut)));
+
+        let cancelled = run_command(
+            Command::new("sleep").arg("5"),
+            None,
+            std::future::ready(()),
+            Duration::from_secs(5),
+        )
+        .await;
+        assert!(matches!(cancelled, Err(CommandError::Cancelled)));
+
+        let started = std::time::Instant::now();
+        let orphan = run_command(
+            Command::new("sh").args(["-c", "sleep 30 & echo pronto"]),
+            None,
+            std::future::pending(),
+            Duration::from_millis(300),
+        )
+        .await;
+        let Ok(orphan) = orphan else {
+            panic!("shell should exit normally");
+        };
+        assert_eq!(orphan.stdout, "pronto");
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let missing = run_command(
+            &mut Command::new("monitor-tray-comando-inexistente"),
+            None,
+            std::future::pending(),
+            Duration::from_secs(5),
+        )
+        .await;
+        assert!(matches!(missing, Err(CommandError::NotFound)));
+    }
+}
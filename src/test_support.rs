@@ -0,0 +1,20 @@
+//! Utilitários compartilhados pelos testes.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Caminho único em `std::env::temp_dir()` para fixtures em disco. O diretório não é
+/// criado; cada teste cria o que precisa e o remove no final.
+pub(crate) fn temp_fixture_dir() -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    let sequence = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!(
+        "monitor-tray-tests-{}-{unique}-{sequence}",
+        std::process::id()
+    ))
+}
@@ -0,0 +1,154 @@
+//! Ícone da bandeja desenhado a partir do valor atual: uma barra que enche de baixo para
+//! cima, colorida pelo nível, com o número sobreposto em uma fonte bitmap 3×5.
+
+/// Pixmap no formato de `IconPixmap`: largura, altura e ARGB32 em ordem de rede.
+pub type Pixmap = (i32, i32, Vec<u8>);
+
+/// Grade lógica do desenho; os tamanhos entregues são múltiplos dela.
+const BASE_SIZE: usize = 22;
+/// Tamanhos oferecidos ao host, que escolhe o mais próximo do painel.
+pub const ICON_SIZES: [usize; 2] = [22, 44];
+
+const BACKGROUND: [u8; 4] = [0xd9, 0x2e, 0x34, 0x40];
+const TEXT: [u8; 4] = [0xff, 0xec, 0xef, 0xf4];
+const LEVEL_LOW: [u8; 4] = [0xff, 0x5e, 0x81, 0x5c];
+const LEVEL_HIGH: [u8; 4] = [0xff, 0xb5, 0x89, 0x00];
+const LEVEL_CRITICAL: [u8; 4] = [0xff, 0xbf, 0x3b, 0x3b];
+
+/// Dígitos de 3×5; cada linha usa os 3 bits menos significativos, o mais alto à esquerda.
+fn glyph(ch: char) -> Option<[u8; 5]> {
+    Some(match ch {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        _ => return None,
+    })
+}
+
+fn level_color(fraction: f64) -> [u8; 4] {
+    if fraction >= 0.85 {
+        LEVEL_CRITICAL
+    } else if fraction >= 0.6 {
+        LEVEL_HIGH
+    } else {
+        LEVEL_LOW
+    }
+}
+
+struct Canvas {
+    size: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            pixels: vec![0; size * size * 4],
+        }
+    }
+
+    fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, color: [u8; 4]) {
+        for row in y..(y + height).min(self.size) {
+            for column in x..(x + width).min(self.size) {
+                let offset = (row * self.size + column) * 4;
+                self.pixels[offset..offset + 4].copy_from_slice(&color);
+            }
+        }
+    }
+}
+
+/// Desenha `label` (dígitos ou `-`) sobre uma barra preenchida em `fraction` (0 a 1).
+pub fn render_icon(fraction: f64, label: &str, size: usize) -> Pixmap {
+    let scale = (size / BASE_SIZE).max(1);
+    let mut canvas = Canvas::new(size);
+    let fraction = if fraction.is_finite() {
+        fraction.clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    // Fundo com cantos recortados, para não parecer um quadrado colado no painel.
+    canvas.fill(scale, 0, size - 2 * scale, size, BACKGROUND);
+    canvas.fill(0, scale, size, size - 2 * scale, BACKGROUND);
+    let level_height = (fraction * size as f64).round() as usize;
+    if level_height > 0 {
+        canvas.fill(
+            scale,
+            size - level_height,
+            size - 2 * scale,
+            level_height.saturating_sub(scale),
+            level_color(fraction),
+        );
+    }
+
+    // Cada pixel do glifo vira um bloco de 2×2 na grade lógica, com 1 de espaço entre dígitos.
+    let glyphs: Vec<[u8; 5]> = label.chars().filter_map(glyph).take(3).collect();
+    let pixel = 2 * scale;
+    let advance = 3 * pixel + scale;
+    let text_width = (glyphs.len() * advance).saturating_sub(scale);
+    let left = size.saturating_sub(text_width) / 2;
+    let top = size.saturating_sub(5 * pixel) / 2;
+    for (index, rows) in glyphs.iter().enumerate() {
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..3 {
+                if bits & (0b100 >> column) != 0 {
+                    canvas.fill(
+                        left + index * advance + column * pixel,
+                        top + row * pixel,
+                        pixel,
+                        pixel,
+                        TEXT,
+                    );
+                }
+            }
+        }
+    }
+
+    (size as i32, size as i32, canvas.pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(pixmap: &Pixmap, x: usize, y: usize) -> [u8; 4] {
+        let offset = (y * pixmap.0 as usize + x) * 4;
+        pixmap.2[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_render_icon_fills_level_and_draws_digits() {
+        let icon = render_icon(0.5, "50", 22);
+        assert_eq!((icon.0, icon.1, icon.2.len()), (22, 22, 22 * 22 * 4));
+
+        // Canto recortado, fundo acima do nível, cor do nível abaixo e texto no meio.
+        assert_eq!(pixel(&icon, 0, 0), [0, 0, 0, 0]);
+        assert_eq!(pixel(&icon, 1, 1), BACKGROUND);
+        assert_eq!(pixel(&icon, 1, 18), LEVEL_LOW);
+        assert_eq!(pixel(&icon, 4, 6), TEXT);
+
+        let hot = render_icon(0.95, "95", 44);
+        assert_eq!(hot.0, 44);
+        assert_eq!(pixel(&hot, 2, 40), LEVEL_CRITICAL);
+    }
+
+    #[test]
+    fn test_render_icon_without_value_shows_dash_and_empty_bar() {
+        let icon = render_icon(f64::NAN, "--", 22);
+        assert!(!icon
+            .2
+            .chunks(4)
+            .any(|color| color == LEVEL_LOW || color == LEVEL_CRITICAL));
+        assert_eq!(pixel(&icon, 4, 10), TEXT);
+        assert_eq!(pixel(&icon, 4, 6), BACKGROUND);
+    }
+}
@@ -0,0 +1,109 @@
+//! Objeto `org.kde.StatusNotifierItem` exportado em `/StatusNotifierItem`.
+
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc;
+use zbus::zvariant::OwnedObjectPath;
+use zbus::{interface, SignalContext};
+
+use super::icon::Pixmap;
+use super::menu::MENU_OBJECT_PATH;
+use super::{TrayAction, TrayState};
+
+pub const ITEM_OBJECT_PATH: &str = "/StatusNotifierItem";
+const ITEM_ID: &str = "monitor-tray";
+const ITEM_TITLE: &str = "Monitor Tray";
+
+pub struct StatusNotifierItem {
+    state: Arc<Mutex<TrayState>>,
+    actions: mpsc::UnboundedSender<TrayAction>,
+}
+
+impl StatusNotifierItem {
+    pub fn new(state: Arc<Mutex<TrayState>>, actions: mpsc::UnboundedSender<TrayAction>) -> Self {
+        Self { state, actions }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, TrayState> {
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+#[interface(name = "org.kde.StatusNotifierItem")]
+impl StatusNotifierItem {
+    /// Clique principal: alterna o valor desenhado no ícone.
+    async fn activate(&self, _x: i32, _y: i32) {
+        let _ = self.actions.send(TrayAction::CycleIndicator);
+    }
+
+    async fn secondary_activate(&self, _x: i32, _y: i32) {
+        let _ = self.actions.send(TrayAction::CycleIndicator);
+    }
+
+    /// O menu é servido por `com.canonical.dbusmenu`; nada a abrir por conta própria.
+    async fn context_menu(&self, _x: i32, _y: i32) {}
+
+    async fn scroll(&self, _delta: i32, _orientation: String) {}
+
+    #[zbus(property)]
+    async fn category(&self) -> &str {
+        "Hardware"
+    }
+
+    #[zbus(property)]
+    async fn id(&self) -> &str {
+        ITEM_ID
+    }
+
+    #[zbus(property)]
+    async fn title(&self) -> &str {
+        ITEM_TITLE
+    }
+
+    #[zbus(property)]
+    async fn status(&self) -> &str {
+        "Active"
+    }
+
+    #[zbus(property)]
+    async fn window_id(&self) -> i32 {
+        0
+    }
+
+    /// Vazio: hosts usam `IconPixmap`, desenhado a cada atualização.
+    #[zbus(property)]
+    async fn icon_name(&self) -> &str {
+        ""
+    }
+
+    #[zbus(property)]
+    async fn icon_pixmap(&self) -> Vec<Pixmap> {
+        self.state().icon.clone()
+    }
+
+    #[zbus(property)]
+    async fn tool_tip(&self) -> (String, Vec<Pixmap>, String, String) {
+        (
+            String::new(),
+            Vec::new(),
+            ITEM_TITLE.to_string(),
+            self.state().tooltip.clone(),
+        )
+    }
+
+    #[zbus(property)]
+    async fn item_is_menu(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    async fn menu(&self) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(MENU_OBJECT_PATH).expect("caminho do menu é válido")
+    }
+
+    #[zbus(signal)]
+    pub async fn new_icon(signal_context: &SignalContext<'_>) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    pub async fn new_tool_tip(signal_context: &SignalContext<'_>) -> zbus::Result<()>;
+}
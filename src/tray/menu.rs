@@ -0,0 +1,296 @@
+//! Menu do ícone em `com.canonical.dbusmenu`, o protocolo que os hosts de
+//! StatusNotifierItem usam para desenhar o menu de contexto.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc;
+use zbus::zvariant::Value;
+use zbus::{interface, SignalContext};
+
+use super::{TrayAction, TrayIndicator, TrayState};
+
+pub const MENU_OBJECT_PATH: &str = "/MenuBar";
+const ROOT_ID: i32 = 0;
+
+/// Itens do menu, com ids estáveis entre revisões do layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Entry {
+    SpeedTest,
+    SpeedTestResult,
+    CopySnapshot,
+    Separator(i32),
+    Indicator(TrayIndicator),
+    Quit,
+}
+
+impl Entry {
+    const ALL: [Entry; 9] = [
+        Entry::SpeedTest,
+        Entry::SpeedTestResult,
+        Entry::CopySnapshot,
+        Entry::Separator(4),
+        Entry::Indicator(TrayIndicator::Cpu),
+        Entry::Indicator(TrayIndicator::Memory),
+        Entry::Indicator(TrayIndicator::Temperature),
+        Entry::Separator(8),
+        Entry::Quit,
+    ];
+
+    fn id(self) -> i32 {
+        match self {
+            Entry::SpeedTest => 1,
+            Entry::SpeedTestResult => 2,
+            Entry::CopySnapshot => 3,
+            Entry::Separator(id) => id,
+            Entry::Indicator(TrayIndicator::Cpu) => 5,
+            Entry::Indicator(TrayIndicator::Memory) => 6,
+            Entry::Indicator(TrayIndicator::Temperature) => 7,
+            Entry::Quit => 9,
+        }
+    }
+
+    fn from_id(id: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|entry| entry.id() == id)
+    }
+
+    fn action(self) -> Option<TrayAction> {
+        match self {
+            Entry::SpeedTest => Some(TrayAction::ToggleSpeedTest),
+            Entry::CopySnapshot => Some(TrayAction::CopySnapshot),
+            Entry::Indicator(indicator) => Some(TrayAction::SetIndicator(indicator)),
+            Entry::Quit => Some(TrayAction::Quit),
+            Entry::SpeedTestResult | Entry::Separator(_) => None,
+        }
+    }
+
+    fn properties(self, state: &TrayState) -> HashMap<String, Value<'static>> {
+        let mut properties = HashMap::new();
+        let mut set = |name: &str, value: Value<'static>| {
+            properties.insert(name.to_string(), value);
+        };
+        match self {
+            Entry::SpeedTest => set(
+                "label",
+                Value::from(if state.speed_test_running() {
+                    "Cancelar teste de velocidade"
+                } else {
+                    "Iniciar teste de velocidade"
+                }),
+            ),
+            Entry::SpeedTestResult => {
+                set("label", Value::from(state.speed_test_summary()));
+                set("enabled", Value::from(false));
+            }
+            Entry::CopySnapshot => set("label", Value::from("Copiar snapshot JSON")),
+            Entry::Separator(_) => set("type", Value::from("separator")),
+            Entry::Indicator(indicator) => {
+                set(
+                    "label",
+                    Value::from(format!("Ícone: {}", indicator.label())),
+                );
+                set("toggle-type", Value::from("radio"));
+                set(
+                    "toggle-state",
+                    Value::from(i32::from(indicator == state.indicator)),
+                );
+            }
+            Entry::Quit => set("label", Value::from("Sair")),
+        }
+        properties
+    }
+}
+
+/// Filtra `properties` pelos nomes pedidos; lista vazia significa todas.
+fn select(
+    mut properties: HashMap<String, Value<'static>>,
+    names: &[String],
+) -> HashMap<String, Value<'static>> {
+    if !names.is_empty() {
+        properties.retain(|name, _| names.contains(name));
+    }
+    properties
+}
+
+fn root_properties() -> HashMap<String, Value<'static>> {
+    HashMap::from([("children-display".to_string(), Value::from("submenu"))])
+}
+
+pub struct DbusMenu {
+    state: Arc<Mutex<TrayState>>,
+    actions: mpsc::UnboundedSender<TrayAction>,
+}
+
+impl DbusMenu {
+    pub fn new(state: Arc<Mutex<TrayState>>, actions: mpsc::UnboundedSender<TrayAction>) -> Self {
+        Self { state, actions }
+    }
+
+    fn dispatch(&self, id: i32, event_id: &str) -> bool {
+        let Some(entry) = Entry::from_id(id) else {
+            return false;
+        };
+        if event_id == "clicked" {
+            if let Some(action) = entry.action() {
+                let _ = self.actions.send(action);
+            }
+        }
+        true
+    }
+}
+
+#[interface(name = "com.canonical.dbusmenu")]
+impl DbusMenu {
+    /// Menu plano: a raiz tem todos os itens como filhos diretos, em qualquer profundidade pedida.
+    #[zbus(out_args("revision", "layout"))]
+    async fn get_layout(
+        &self,
+        parent_id: i32,
+        recursion_depth: i32,
+        property_names: Vec<String>,
+    ) -> zbus::fdo::Result<(
+        u32,
+        (i32, HashMap<String, Value<'static>>, Vec<Value<'static>>),
+    )> {
+        let state = self.state.lock().unwrap_or_else(|error| error.into_inner());
+        let revision = state.menu_revision;
+        if parent_id != ROOT_ID {
+            let entry = Entry::from_id(parent_id).ok_or_else(|| {
+                zbus::fdo::Error::InvalidArgs(format!("item desconhecido: {parent_id}"))
+            })?;
+            let properties = select(entry.properties(&state), &property_names);
+            return Ok((revision, (parent_id, properties, Vec::new())));
+        }
+
+        let children = if recursion_depth == 0 {
+            Vec::new()
+        } else {
+            Entry::ALL
+                .iter()
+                .map(|entry| {
+                    let properties = select(entry.properties(&state), &property_names);
+                    Value::from((entry.id(), properties, Vec::<Value>::new()))
+                })
+                .collect()
+        };
+        Ok((
+            revision,
+            (
+                ROOT_ID,
+                select(root_properties(), &property_names),
+                children,
+            ),
+        ))
+    }
+
+    async fn get_group_properties(
+        &self,
+        ids: Vec<i32>,
+        property_names: Vec<String>,
+    ) -> Vec<(i32, HashMap<String, Value<'static>>)> {
+        let state = self.state.lock().unwrap_or_else(|error| error.into_inner());
+        let ids = if ids.is_empty() {
+            Entry::ALL.iter().map(|entry| entry.id()).collect()
+        } else {
+            ids
+        };
+        ids.into_iter()
+            .filter_map(|id| {
+                let properties = if id == ROOT_ID {
+                    root_properties()
+                } else {
+                    Entry::from_id(id)?.properties(&state)
+                };
+                Some((id, select(properties, &property_names)))
+            })
+            .collect()
+    }
+
+    async fn get_property(&self, id: i32, name: String) -> zbus::fdo::Result<Value<'static>> {
+        let state = self.state.lock().unwrap_or_else(|error| error.into_inner());
+        Entry::from_id(id)
+            .and_then(|entry| entry.properties(&state).remove(&name))
+            .ok_or_else(|| {
+                zbus::fdo::Error::InvalidArgs(format!("propriedade desconhecida: {name}"))
+            })
+    }
+
+    async fn event(&self, id: i32, event_id: String, _data: Value<'_>, _timestamp: u32) {
+        self.dispatch(id, &event_id);
+    }
+
+    /// Retorna os ids que não correspondem a nenhum item.
+    async fn event_group(&self, events: Vec<(i32, String, Value<'_>, u32)>) -> Vec<i32> {
+        events
+            .into_iter()
+            .filter(|(id, event_id, _, _)| !self.dispatch(*id, event_id))
+            .map(|(id, ..)| id)
+            .collect()
+    }
+
+    /// O layout já está sempre atualizado; nada a recarregar antes de abrir.
+    async fn about_to_show(&self, _id: i32) -> bool {
+        false
+    }
+
+    #[zbus(out_args("updates_needed", "id_errors"))]
+    async fn about_to_show_group(&self, _ids: Vec<i32>) -> (Vec<i32>, Vec<i32>) {
+        (Vec::new(), Vec::new())
+    }
+
+    #[zbus(property)]
+    async fn version(&self) -> u32 {
+        3
+    }
+
+    #[zbus(property)]
+    async fn text_direction(&self) -> &str {
+        "ltr"
+    }
+
+    #[zbus(property)]
+    async fn status(&self) -> &str {
+        "normal"
+    }
+
+    #[zbus(property)]
+    async fn icon_theme_path(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[zbus(signal)]
+    pub async fn layout_updated(
+        signal_context: &SignalContext<'_>,
+        revision: u32,
+        parent: i32,
+    ) -> zbus::Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_entries_have_unique_ids_and_reflect_state() {
+        let ids: Vec<i32> = Entry::ALL.iter().map(|entry| entry.id()).collect();
+        assert_eq!(ids, (1..=9).collect::<Vec<_>>());
+        assert!(Entry::ALL
+            .iter()
+            .all(|entry| Entry::from_id(entry.id()) == Some(*entry)));
+
+        let state = TrayState::new(TrayIndicator::Memory);
+        let memory = Entry::Indicator(TrayIndicator::Memory).properties(&state);
+        let cpu = Entry::Indicator(TrayIndicator::Cpu).properties(&state);
+        assert_eq!(memory["toggle-state"], Value::from(1));
+        assert_eq!(cpu["toggle-state"], Value::from(0));
+        assert_eq!(
+            Entry::SpeedTest.properties(&state)["label"],
+            Value::from("Iniciar teste de velocidade")
+        );
+        assert_eq!(
+            Entry::Indicator(TrayIndicator::Temperature).action(),
+            Some(TrayAction::SetIndicator(TrayIndicator::Temperature))
+        );
+        assert_eq!(Entry::SpeedTestResult.action(), None);
+    }
+}
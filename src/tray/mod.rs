@@ -0,0 +1,445 @@
+//! Modo `tray`: ícone `org.kde.StatusNotifierItem` para desktops sem o plasmoid
+//! (GNOME com a extensão AppIndicator, XFCE, trays do sway e do Waybar).
+//!
+//! O ícone é redesenhado a cada atualização com o uso de CPU, RAM ou a temperatura,
+//! o tooltip resume o snapshot e o menu (`com.canonical.dbusmenu`) dispara o speed test
+//! e copia o JSON de `GetMetricsJson` para a área de transferência.
+
+mod icon;
+mod item;
+mod menu;
+
+use std::error::Error;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use clap::ValueEnum;
+use futures_util::StreamExt;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
+use zbus::{proxy, Connection, SignalContext};
+
+use crate::monitor::{NetworkSpeedTestState, NetworkSpeedTestStatus, SystemMetrics};
+use crate::tui::{MetricsSource, Sample};
+
+use icon::{render_icon, Pixmap, ICON_SIZES};
+use item::{StatusNotifierItem, ITEM_OBJECT_PATH};
+use menu::{DbusMenu, MENU_OBJECT_PATH};
+
+/// Tempo máximo para o utilitário de área de transferência aceitar o texto.
+const CLIPBOARD_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[proxy(
+    interface = "org.kde.StatusNotifierWatcher",
+    default_service = "org.kde.StatusNotifierWatcher",
+    default_path = "/StatusNotifierWatcher"
+)]
+trait StatusNotifierWatcher {
+    fn register_status_notifier_item(&self, service: &str) -> zbus::Result<()>;
+}
+
+/// Valor desenhado no ícone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TrayIndicator {
+    /// Uso total de CPU em %
+    Cpu,
+    /// Uso de RAM em %
+    Memory,
+    /// CPU mais quente em °C (ou o sensor mais quente, sem sensor de CPU)
+    Temperature,
+}
+
+impl TrayIndicator {
+    fn label(self) -> &'static str {
+        match self {
+            TrayIndicator::Cpu => "CPU",
+            TrayIndicator::Memory => "RAM",
+            TrayIndicator::Temperature => "Temperatura",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            TrayIndicator::Cpu => TrayIndicator::Memory,
+            TrayIndicator::Memory => TrayIndicator::Temperature,
+            TrayIndicator::Temperature => TrayIndicator::Cpu,
+        }
+    }
+
+    /// Valor atual; porcentagens e °C compartilham a escala de 0 a 100 do ícone.
+    fn value(self, metrics: &SystemMetrics) -> Option<f64> {
+        match self {
+            TrayIndicator::Cpu => Some(f64::from(metrics.cpu.usage_percent)),
+            TrayIndicator::Memory => Some(f64::from(metrics.memory.usage_percent)),
+            TrayIndicator::Temperature => metrics
+                .sensors
+                .hottest_cpu_celsius
+                .or(metrics.sensors.hottest_temperature_celsius)
+                .map(f64::from),
+        }
+    }
+}
+
+/// Ações pedidas pelo menu ou por cliques, tratadas no laço principal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    ToggleSpeedTest,
+    CopySnapshot,
+    SetIndicator(TrayIndicator),
+    CycleIndicator,
+    Quit,
+}
+
+/// Estado compartilhado entre o laço principal e os objetos DBus.
+pub struct TrayState {
+    indicator: TrayIndicator,
+    icon: Vec<Pixmap>,
+    tooltip: String,
+    speed_test: NetworkSpeedTestStatus,
+    metrics: Option<SystemMetrics>,
+    /// Revisão do layout do menu; muda quando algum rótulo ou marcação muda.
+    menu_revision: u32,
+}
+
+impl TrayState {
+    fn new(indicator: TrayIndicator) -> Self {
+        let mut state = Self {
+            indicator,
+            icon: Vec::new(),
+            tooltip: String::new(),
+            speed_test: NetworkSpeedTestStatus::default(),
+            metrics: None,
+            menu_revision: 1,
+        };
+        state.redraw_icon();
+        state
+    }
+
+    fn speed_test_running(&self) -> bool {
+        matches!(self.speed_test.state, NetworkSpeedTestState::Running)
+    }
+
+    fn speed_test_summary(&self) -> String {
+        let status = &self.speed_test;
+        match status.state {
+            NetworkSpeedTestState::Idle => "Nenhum teste executado".to_string(),
+            NetworkSpeedTestState::Running => "Teste em andamento…".to_string(),
+            NetworkSpeedTestState::Cancelled => "Último teste cancelado".to_string(),
+            NetworkSpeedTestState::Error => format!(
+                "Último teste falhou: {}",
+                status.error.as_deref().unwrap_or("erro desconhecido")
+            ),
+            NetworkSpeedTestState::Success => format!(
+                "↓ {} ↑ {} · ping {}",
+                format_optional(status.download_mbps, "Mbps"),
+                format_optional(status.upload_mbps, "Mbps"),
+                format_optional(status.ping_ms, "ms"),
+            ),
+        }
+    }
+
+    fn redraw_icon(&mut self) {
+        let value = self
+            .metrics
+            .as_ref()
+            .and_then(|metrics| self.indicator.value(metrics))
+            .filter(|value| value.is_finite());
+        let label = value.map_or_else(
+            || "--".to_string(),
+            |value| format!("{:.0}", value.clamp(0.0, 999.0)),
+        );
+        let fraction = value.map_or(0.0, |value| value / 100.0);
+        self.icon = ICON_SIZES
+            .iter()
+            .map(|size| render_icon(fraction, &label, *size))
+            .collect();
+    }
+
+    fn set_indicator(&mut self, indicator: TrayIndicator) {
+        if indicator != self.indicator {
+            self.indicator = indicator;
+            self.menu_revision += 1;
+            self.redraw_icon();
+        }
+    }
+
+    /// Aplica uma nova amostra; retorna se o menu precisa ser redesenhado.
+    fn apply(&mut self, sample: Sample) -> bool {
+        let previous_menu = (self.speed_test_running(), self.speed_test_summary());
+        self.speed_test = sample.speed_test;
+        self.tooltip = tooltip_text(&sample.metrics, &self.speed_test);
+        self.metrics = Some(sample.metrics);
+        self.redraw_icon();
+
+        let menu_changed = previous_menu != (self.speed_test_running(), self.speed_test_summary());
+        if menu_changed {
+            self.menu_revision += 1;
+        }
+        menu_changed
+    }
+}
+
+fn format_optional(value: Option<f32>, unit: &str) -> String {
+    value.map_or_else(|| "-".to_string(), |value| format!("{value:.1} {unit}"))
+}
+
+/// Resumo exibido no tooltip, uma linha por grupo.
+fn tooltip_text(metrics: &SystemMetrics, speed_test: &NetworkSpeedTestStatus) -> String {
+    let mut lines = vec![
+        format!(
+            "CPU {:.0}% · {} MHz",
+            metrics.cpu.usage_percent, metrics.cpu.frequency
+        ),
+        format!(
+            "RAM {:.1} de {:.1} GB ({:.0}%)",
+            metrics.memory.used_memory, metrics.memory.total_memory, metrics.memory.usage_percent
+        ),
+    ];
+    let temperatures: Vec<String> = [
+        ("CPU", metrics.sensors.hottest_cpu_celsius),
+        ("GPU", metrics.sensors.hottest_gpu_celsius),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| value.map(|value| format!("{name} {value:.0}°C")))
+    .collect();
+    if !temperatures.is_empty() {
+        lines.push(format!("Temperatura {}", temperatures.join(" · ")));
+    }
+    for gpu in &metrics.gpus {
+        if let Some(usage) = gpu.usage_percent {
+            lines.push(format!("{} {usage:.0}%", gpu.name));
+        }
+    }
+    if matches!(speed_test.state, NetworkSpeedTestState::Success) {
+        lines.push(format!(
+            "Rede ↓ {} ↑ {}",
+            format_optional(speed_test.download_mbps, "Mbps"),
+            format_optional(speed_test.upload_mbps, "Mbps")
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Copia `text` com `wl-copy` no Wayland ou `xclip`/`xsel` no X11, o primeiro instalado.
+/// Saídas vão para `/dev/null`: esses utilitários ficam em segundo plano servindo a seleção.
+async fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let mut candidates: Vec<&[&str]> = Vec::new();
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        candidates.push(&["wl-copy"]);
+    }
+    candidates.push(&["xclip", "-selection", "clipboard"]);
+    candidates.push(&["xsel", "--clipboard", "--input"]);
+
+    for argv in candidates {
+        let mut child = match Command::new(argv[0])
+            .args(&argv[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(format!("{}: {error}", argv[0])),
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .await
+                .map_err(|error| format!("{}: {error}", argv[0]))?;
+        }
+        return match tokio::time::timeout(CLIPBOARD_TIMEOUT, child.wait()).await {
+            Ok(Ok(status)) if status.success() => Ok(()),
+            Ok(Ok(status)) => Err(format!("{} terminou com {status}", argv[0])),
+            Ok(Err(error)) => Err(format!("{}: {error}", argv[0])),
+            Err(_) => {
+                let _ = child.start_kill();
+                Err(format!("{} não respondeu", argv[0]))
+            }
+        };
+    }
+    Err("nenhum utilitário de área de transferência encontrado (wl-copy, xclip ou xsel)".into())
+}
+
+async fn register_with_watcher(connection: &Connection, service: &str) {
+    let result = match StatusNotifierWatcherProxy::new(connection).await {
+        Ok(watcher) => watcher.register_status_notifier_item(service).await,
+        Err(error) => Err(error),
+    };
+    if let Err(error) = result {
+        eprintln!(
+            "monitor-tray: nenhum StatusNotifierWatcher respondeu ({error}); \
+             o ícone aparece quando uma bandeja for iniciada"
+        );
+    }
+}
+
+/// Registra o ícone na bandeja e o atualiza a cada `interval` até `Sair`, `Ctrl+C` ou SIGTERM.
+/// Sem `local`, lê os caches do serviço DBus quando ele está em execução, como a TUI.
+pub async fn run_tray(
+    local: bool,
+    indicator: TrayIndicator,
+    interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    let mut source = MetricsSource::connect(local).await;
+    let connection = Connection::session().await?;
+    let state = Arc::new(Mutex::new(TrayState::new(indicator)));
+    let (actions_tx, mut actions) = mpsc::unbounded_channel();
+
+    connection
+        .object_server()
+        .at(
+            ITEM_OBJECT_PATH,
+            StatusNotifierItem::new(state.clone(), actions_tx.clone()),
+        )
+        .await?;
+    connection
+        .object_server()
+        .at(MENU_OBJECT_PATH, DbusMenu::new(state.clone(), actions_tx))
+        .await?;
+    let service = format!("org.kde.StatusNotifierItem-{}-1", std::process::id());
+    connection.request_name(service.as_str()).await?;
+    let item_context = SignalContext::new(&connection, ITEM_OBJECT_PATH)?;
+    let menu_context = SignalContext::new(&connection, MENU_OBJECT_PATH)?;
+
+    // A bandeja pode ser iniciada (ou reiniciada) depois do ícone: registra de novo a cada dono novo.
+    let watcher = StatusNotifierWatcherProxy::new(&connection).await?;
+    let mut watcher_owner = watcher.inner().receive_owner_changed().await?;
+    register_with_watcher(&connection, &service).await;
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        let mut menu_changed = false;
+        tokio::select! {
+            _ = ticker.tick() => {
+                match source.sample().await {
+                    Ok(sample) => {
+                        menu_changed = state
+                            .lock()
+                            .unwrap_or_else(|error| error.into_inner())
+                            .apply(sample);
+                        let _ = StatusNotifierItem::new_tool_tip(&item_context).await;
+                    }
+                    Err(error) => eprintln!("monitor-tray: falha ao coletar métricas: {error}"),
+                }
+            }
+            Some(action) = actions.recv() => match action {
+                TrayAction::Quit => return Ok(()),
+                TrayAction::ToggleSpeedTest => {
+                    let running = state
+                        .lock()
+                        .unwrap_or_else(|error| error.into_inner())
+                        .speed_test_running();
+                    let result = if running {
+                        source.cancel_speed_test().await
+                    } else {
+                        source.start_speed_test().await
+                    };
+                    if let Err(error) = result {
+                        eprintln!("monitor-tray: falha no teste de velocidade: {error}");
+                    }
+                    ticker.reset_immediately();
+                }
+                TrayAction::CopySnapshot => {
+                    let snapshot = state
+                        .lock()
+                        .unwrap_or_else(|error| error.into_inner())
+                        .metrics
+                        .as_ref()
+                        .map(serde_json::to_string_pretty);
+                    match snapshot {
+                        Some(Ok(json)) => {
+                            if let Err(error) = copy_to_clipboard(&json).await {
+                                eprintln!("monitor-tray: falha ao copiar o snapshot: {error}");
+                            }
+                        }
+                        Some(Err(error)) => eprintln!("monitor-tray: {error}"),
+                        None => {}
+                    }
+                }
+                TrayAction::SetIndicator(_) | TrayAction::CycleIndicator => {
+                    let mut state = state.lock().unwrap_or_else(|error| error.into_inner());
+                    let indicator = match action {
+                        TrayAction::SetIndicator(indicator) => indicator,
+                        _ => state.indicator.next(),
+                    };
+                    let previous = state.menu_revision;
+                    state.set_indicator(indicator);
+                    menu_changed = state.menu_revision != previous;
+                }
+            },
+            Some(owner) = watcher_owner.next() => {
+                if owner.is_some() {
+                    register_with_watcher(&connection, &service).await;
+                }
+            }
+            _ = terminate.recv() => return Ok(()),
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+
+        let _ = StatusNotifierItem::new_icon(&item_context).await;
+        if menu_changed {
+            let revision = state
+                .lock()
+                .unwrap_or_else(|error| error.into_inner())
+                .menu_revision;
+            let _ = DbusMenu::layout_updated(&menu_context, revision, 0).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::SystemMonitor;
+
+    #[test]
+    fn test_tray_state_tracks_indicator_and_speed_test() {
+        let mut metrics = SystemMonitor::new_empty().get_all_metrics();
+        metrics.cpu.usage_percent = 42.0;
+        metrics.cpu.frequency = 3600;
+        metrics.memory.usage_percent = 50.0;
+        metrics.memory.used_memory = 8.0;
+        metrics.memory.total_memory = 16.0;
+        metrics.sensors.hottest_cpu_celsius = Some(71.0);
+        metrics.sensors.hottest_gpu_celsius = None;
+        metrics.gpus.clear();
+        let running = NetworkSpeedTestStatus {
+            state: NetworkSpeedTestState::Running,
+            ..NetworkSpeedTestStatus::default()
+        };
+        let mut state = TrayState::new(TrayIndicator::Cpu);
+        let revision = state.menu_revision;
+
+        let menu_changed = state.apply(Sample {
+            metrics: metrics.clone(),
+            history: SystemMonitor::new_empty().get_history_metrics(),
+            speed_test: running,
+        });
+
+        assert!(menu_changed);
+        assert_eq!(state.menu_revision, revision + 1);
+        assert!(state.speed_test_running());
+        assert_eq!(
+            state.tooltip,
+            "CPU 42% · 3600 MHz\nRAM 8.0 de 16.0 GB (50%)\nTemperatura CPU 71°C"
+        );
+        assert_eq!(state.icon.len(), ICON_SIZES.len());
+        assert_eq!(state.icon[0], render_icon(0.42, "42", 22));
+
+        state.set_indicator(state.indicator.next());
+        assert_eq!(state.indicator, TrayIndicator::Memory);
+        assert_eq!(state.icon[0], render_icon(0.5, "50", 22));
+        assert_eq!(
+            TrayIndicator::Temperature.value(&metrics),
+            Some(71.0),
+            "temperatura usa a CPU mais quente"
+        );
+    }
+}
@@ -0,0 +1,269 @@
+//! Modo `tui`: as sete abas do plasmoid no terminal, com navegação por teclado,
+//! sparklines do histórico e disparo do speed test.
+
+mod source;
+mod ui;
+
+use std::error::Error;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::DefaultTerminal;
+use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
+
+use crate::cli::MIN_WATCH_INTERVAL;
+
+pub use source::{MetricsSource, Sample};
+
+/// Intervalo de espera por teclas na thread de entrada; limita a latência de encerramento.
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Abas na mesma ordem do plasmoid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tab {
+    Cpu,
+    Memory,
+    Gpu,
+    Disk,
+    Network,
+    Sensors,
+    System,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 7] = [
+        Tab::Cpu,
+        Tab::Memory,
+        Tab::Gpu,
+        Tab::Disk,
+        Tab::Network,
+        Tab::Sensors,
+        Tab::System,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Tab::Cpu => "CPU",
+            Tab::Memory => "RAM",
+            Tab::Gpu => "GPU",
+            Tab::Disk => "Disco",
+            Tab::Network => "Rede",
+            Tab::Sensors => "Sensores",
+            Tab::System => "Sistema",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|tab| *tab == self)
+            .unwrap_or_default()
+    }
+
+    fn offset(self, offset: isize) -> Self {
+        let len = Self::ALL.len() as isize;
+        Self::ALL[(self.index() as isize + offset).rem_euclid(len) as usize]
+    }
+}
+
+/// Ação pedida pelo teclado que depende da origem dos dados.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    None,
+    Quit,
+    Refresh,
+    StartSpeedTest,
+    CancelSpeedTest,
+}
+
+pub struct App {
+    pub tab: Tab,
+    pub sample: Option<Sample>,
+    pub source: &'static str,
+    /// Última mensagem de erro ou de ação, exibida na barra de status.
+    pub message: Option<String>,
+}
+
+impl App {
+    pub fn new(source: &'static str) -> Self {
+        Self {
+            tab: Tab::Cpu,
+            sample: None,
+            source,
+            message: None,
+        }
+    }
+
+    fn update(&mut self, sample: Result<Sample, Box<dyn Error>>) {
+        match sample {
+            Ok(sample) => {
+                self.sample = Some(sample);
+                self.message = None;
+            }
+            // Mantém a última amostra na tela; o backend pode voltar no próximo ciclo.
+            Err(error) => self.message = Some(format!("falha ao atualizar: {error}")),
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::None;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => {
+                self.tab = self.tab.offset(1);
+                Action::None
+            }
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => {
+                self.tab = self.tab.offset(-1);
+                Action::None
+            }
+            KeyCode::Char(digit @ '1'..='7') => {
+                self.tab = Tab::ALL[digit as usize - '1' as usize];
+                Action::None
+            }
+            KeyCode::Char('r') => Action::Refresh,
+            KeyCode::Char('s') => Action::StartSpeedTest,
+            KeyCode::Char('c') => Action::CancelSpeedTest,
+            _ => Action::None,
+        }
+    }
+}
+
+/// Lê eventos do terminal numa thread própria, já que a leitura do crossterm é bloqueante.
+/// A thread termina quando o receptor é descartado.
+fn spawn_input_reader() -> mpsc::UnboundedReceiver<Event> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while !sender.is_closed() {
+            match event::poll(INPUT_POLL_INTERVAL) {
+                Ok(true) => {
+                    let Ok(event) = event::read() else { break };
+                    if sender.send(event).is_err() {
+                        break;
+                    }
+                }
+                Ok(false) => {}
+                Err(_) => break,
+            }
+        }
+    });
+    receiver
+}
+
+async fn run_app(
+    terminal: &mut DefaultTerminal,
+    source: &mut MetricsSource,
+    interval: Duration,
+) -> Result<(), Box<dyn Error>> {
+    let mut app = App::new(source.label());
+    let mut input = spawn_input_reader();
+    let mut ticker = tokio::time::interval(interval.max(MIN_WATCH_INTERVAL));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+
+        let action = tokio::select! {
+            _ = ticker.tick() => Action::Refresh,
+            event = input.recv() => match event {
+                Some(Event::Key(key)) => app.handle_key(key),
+                Some(_) => Action::None,
+                None => Action::Quit,
+            },
+        };
+
+        match action {
+            Action::None => {}
+            Action::Quit => return Ok(()),
+            Action::Refresh => app.update(source.sample().await),
+            Action::StartSpeedTest => {
+                app.message = Some(match source.start_speed_test().await {
+                    Ok(true) => "speed test iniciado".to_string(),
+                    Ok(false) => "já existe um speed test em andamento".to_string(),
+                    Err(error) => format!("falha ao iniciar o speed test: {error}"),
+                });
+            }
+            Action::CancelSpeedTest => {
+                app.message = Some(match source.cancel_speed_test().await {
+                    Ok(true) => "cancelando speed test".to_string(),
+                    Ok(false) => "nenhum speed test em andamento".to_string(),
+                    Err(error) => format!("falha ao cancelar o speed test: {error}"),
+                });
+            }
+        }
+    }
+}
+
+/// Abre a TUI em tela alternativa até `q`, `Esc` ou `Ctrl+C`.
+pub async fn run_tui(local: bool, interval: Duration) -> Result<(), Box<dyn Error>> {
+    let mut source = MetricsSource::connect(local).await;
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, &mut source, interval).await;
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_tab_navigation_wraps_around() {
+        let mut app = App::new("local");
+
+        assert_eq!(app.handle_key(press(KeyCode::BackTab)), Action::None);
+        assert_eq!(app.tab, Tab::System);
+        app.handle_key(press(KeyCode::Tab));
+        assert_eq!(app.tab, Tab::Cpu);
+        app.handle_key(press(KeyCode::Char('5')));
+        assert_eq!(app.tab, Tab::Network);
+        app.handle_key(press(KeyCode::Right));
+        assert_eq!(app.tab, Tab::Sensors);
+    }
+
+    #[test]
+    fn test_keys_map_to_source_actions() {
+        let mut app = App::new("local");
+
+        assert_eq!(
+            app.handle_key(press(KeyCode::Char('s'))),
+            Action::StartSpeedTest
+        );
+        assert_eq!(
+            app.handle_key(press(KeyCode::Char('c'))),
+            Action::CancelSpeedTest
+        );
+        assert_eq!(
+            app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Action::Quit
+        );
+        assert_eq!(app.handle_key(press(KeyCode::Char('q'))), Action::Quit);
+    }
+
+    #[test]
+    fn test_failed_refresh_keeps_last_sample() {
+        let monitor = crate::monitor::SystemMonitor::new_empty();
+        let mut app = App::new("DBus");
+        app.update(Ok(Sample {
+            metrics: monitor.get_all_metrics(),
+            history: monitor.get_history_metrics(),
+            speed_test: Default::default(),
+        }));
+
+        app.update(Err("serviço indisponível".into()));
+
+        assert!(app.sample.is_some());
+        assert_eq!(
+            app.message.as_deref(),
+            Some("falha ao atualizar: serviço indisponível")
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! Origem dos dados da TUI: o backend DBus já em execução ou um `SystemMonitor` próprio.
+
+use std::error::Error;
+
+use crate::cli::load_cli_config;
+use crate::dbus::client::{connect_backend, BackendProxy};
+use crate::monitor::{
+    FastMetrics, HistoryMetrics, NetworkSpeedTestStatus, SlowMetrics, SystemMetrics, SystemMonitor,
+};
+use crate::speedtest::NetworkSpeedTestManager;
+
+/// Uma amostra completa para desenhar todas as abas.
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub metrics: SystemMetrics,
+    pub history: HistoryMetrics,
+    pub speed_test: NetworkSpeedTestStatus,
+}
+
+pub enum MetricsSource {
+    /// Coleta no próprio processo, com um speed test local.
+    Local {
+        monitor: Box<SystemMonitor>,
+        speed_test: NetworkSpeedTestManager,
+    },
+    /// Lê os caches do serviço `com.monitortray.Backend`, sem coletar nada localmente.
+    Remote(BackendProxy<'static>),
+}
+
+impl MetricsSource {
+    /// Usa o serviço DBus quando ele responde ao `Ping`; caso contrário, ou com `local`,
+    /// coleta diretamente.
+    pub async fn connect(local: bool) -> Self {
+        if !local {
+            if let Some(proxy) = connect_backend().await {
+                return Self::Remote(proxy);
+            }
+        }
+        Self::Local {
+            monitor: Box::new(SystemMonitor::with_config(load_cli_config())),
+            speed_test: NetworkSpeedTestManager::new(),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Local { .. } => "local",
+            Self::Remote(_) => "DBus",
+        }
+    }
+
+    pub async fn sample(&mut self) -> Result<Sample, Box<dyn Error>> {
+        match self {
+            Self::Local {
+                monitor,
+                speed_test,
+            } => {
+                monitor.update_fast_metrics().await;
+                monitor.refresh_slow_metrics(false).await;
+                Ok(Sample {
+                    metrics: monitor.get_all_metrics(),
+                    history: monitor.get_history_metrics(),
+                    speed_test: speed_test.get_status().await,
+                })
+            }
+            Self::Remote(proxy) => {
+                let fast: FastMetrics = serde_json::from_str(&proxy.fast_metrics_json().await?)?;
+                let slow: SlowMetrics = serde_json::from_str(&proxy.slow_metrics_json().await?)?;
+                Ok(Sample {
+                    metrics: SystemMetrics::from_parts(fast, slow),
+                    history: serde_json::from_str(&proxy.history_metrics_json().await?)?,
+                    speed_test: serde_json::from_str(
+                        &proxy.get_network_speed_test_status_json().await?,
+                    )?,
+                })
+            }
+        }
+    }
+
+    /// Inicia o speed test; `false` quando já havia um em andamento.
+    pub async fn start_speed_test(&self) -> Result<bool, Box<dyn Error>> {
+        match self {
+            Self::Local { speed_test, .. } => Ok(speed_test.start().await),
+            Self::Remote(proxy) => Ok(proxy.start_network_speed_test().await?),
+        }
+    }
+
+    /// Cancela o speed test; `false` quando nenhum estava em andamento.
+    pub async fn cancel_speed_test(&self) -> Result<bool, Box<dyn Error>> {
+        match self {
+            Self::Local { speed_test, .. } => Ok(speed_test.cancel().await),
+            Self::Remote(proxy) => Ok(proxy.cancel_network_speed_test().await?),
+        }
+    }
+}
@@ -0,0 +1,480 @@
+//! Desenho das abas da TUI. Tabelas e listas reaproveitam o texto de
+//! `json --format table`; gauges e sparklines ficam por conta do ratatui.
+
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Gauge, Paragraph, Sparkline, Tabs};
+use ratatui::Frame;
+
+use crate::cli::table::{
+    bar, cpu_heading, format_celsius, format_gib, format_percent, format_rate, or_missing,
+    render_disks, render_gpus, render_network, render_processes, render_sensors, render_system,
+};
+use crate::monitor::{
+    CpuCoreType, HistorySeries, NetworkSpeedTestPhase, NetworkSpeedTestState,
+    NetworkSpeedTestStatus,
+};
+
+use super::{App, Sample, Tab};
+
+const SPARKLINE_HEIGHT: u16 = 6;
+/// Os valores do histórico são `f64`; a escala preserva duas casas na conversão para `u64`.
+const SPARKLINE_SCALE: f64 = 100.0;
+const ACCENT: Color = Color::Cyan;
+
+/// Últimas `width` amostras de `series`, escaladas para o `Sparkline`.
+fn sparkline_data(series: &HistorySeries, width: usize) -> Vec<u64> {
+    let samples: Vec<f64> = series.samples().collect();
+    samples[samples.len().saturating_sub(width)..]
+        .iter()
+        .map(|value| (value.max(0.0) * SPARKLINE_SCALE).round() as u64)
+        .collect()
+}
+
+/// Desenha `series` com o título seguido do valor mais recente formatado por `format`.
+/// Com `max`, a escala é fixa (por exemplo 100 para porcentagens).
+fn render_sparkline(
+    frame: &mut Frame,
+    area: Rect,
+    title: &str,
+    series: &HistorySeries,
+    max: Option<f64>,
+    format: impl Fn(f64) -> String,
+) {
+    let latest = or_missing(
+        series.samples().last().filter(|value| value.is_finite()),
+        format,
+    );
+    let mut sparkline = Sparkline::default()
+        .block(Block::bordered().title(format!(" {title} · {latest} ")))
+        .data(sparkline_data(
+            series,
+            area.width.saturating_sub(2) as usize,
+        ))
+        .style(Style::default().fg(ACCENT));
+    if let Some(max) = max {
+        sparkline = sparkline.max((max * SPARKLINE_SCALE) as u64);
+    }
+    frame.render_widget(sparkline, area);
+}
+
+fn render_text(frame: &mut Frame, area: Rect, title: &str, render: impl FnOnce(&mut String)) {
+    let mut text = String::new();
+    render(&mut text);
+    frame.render_widget(
+        Paragraph::new(text).block(Block::bordered().title(format!(" {title} "))),
+        area,
+    );
+}
+
+fn gauge(title: String, percent: f32, label: String) -> Gauge<'static> {
+    let ratio = if percent.is_finite() {
+        (percent as f64 / 100.0).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    Gauge::default()
+        .block(Block::bordered().title(title))
+        .gauge_style(Style::default().fg(ACCENT))
+        .ratio(ratio)
+        .label(label)
+}
+
+fn draw_cpu(frame: &mut Frame, area: Rect, sample: &Sample) {
+    let cpu = &sample.metrics.cpu;
+    let [total, history, cores] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(SPARKLINE_HEIGHT),
+        Constraint::Min(0),
+    ])
+    .areas(area);
+
+    frame.render_widget(
+        gauge(
+            format!(" {} ", cpu_heading(cpu)),
+            cpu.usage_percent,
+            format!(
+                "{} · user {} · nice {} · system {} · iowait {} · irq {} · softirq {} · steal {}",
+                format_percent(cpu.usage_percent),
+                format_percent(cpu.user_percent),
+                format_percent(cpu.nice_percent),
+                format_percent(cpu.system_percent),
+                format_percent(cpu.iowait_percent),
+                format_percent(cpu.irq_percent),
+                format_percent(cpu.softirq_percent),
+                format_percent(cpu.steal_percent),
+            ),
+        ),
+        total,
+    );
+    render_sparkline(
+        frame,
+        history,
+        "Uso de CPU",
+        &sample.history.cpu_usage,
+        Some(100.0),
+        |value| format!("{value:.1}%"),
+    );
+
+    // Distribui os núcleos em colunas quando não cabem na altura disponível.
+    let block = Block::bordered().title(" Núcleos ");
+    let inner = block.inner(cores);
+    frame.render_widget(block, cores);
+    let rows = inner.height.max(1) as usize;
+    let lines: Vec<Line> = cpu
+        .per_core_usage
+        .iter()
+        .enumerate()
+        .map(|(index, usage)| {
+            let frequency = cpu
+                .cores
+                .iter()
+                .find(|core| core.id == index)
+                .map(|core| {
+                    let core_type = match core.core_type {
+                        Some(CpuCoreType::Performance) => " P",
+                        Some(CpuCoreType::Efficiency) => " E",
+                        None => "",
+                    };
+                    format!(" {:>5} MHz{core_type}", core.frequency_mhz)
+                })
+                .unwrap_or_default();
+            Line::from(format!(
+                "cpu{index:<3} {} {:>6}{frequency}",
+                bar(*usage),
+                format_percent(*usage)
+            ))
+        })
+        .collect();
+    let columns = lines.len().div_ceil(rows).max(1);
+    let areas = Layout::horizontal(vec![Constraint::Fill(1); columns]).split(inner);
+    for (chunk, area) in lines.chunks(rows).zip(areas.iter()) {
+        frame.render_widget(Paragraph::new(chunk.to_vec()), *area);
+    }
+}
+
+fn draw_memory(frame: &mut Frame, area: Rect, sample: &Sample) {
+    let memory = &sample.metrics.memory;
+    let [ram, swap, history, details] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Length(SPARKLINE_HEIGHT),
+        Constraint::Min(0),
+    ])
+    .areas(area);
+
+    frame.render_widget(
+        gauge(
+            " RAM ".to_string(),
+            memory.usage_percent,
+            format!(
+                "{} · {} de {}",
+                format_percent(memory.usage_percent),
+                format_gib(memory.used_memory),
+                format_gib(memory.total_memory)
+            ),
+        ),
+        ram,
+    );
+    let swap_percent = if memory.total_swap > 0.0 {
+        (memory.used_swap / memory.total_swap * 100.0) as f32
+    } else {
+        0.0
+    };
+    frame.render_widget(
+        gauge(
+            " Swap ".to_string(),
+            swap_percent,
+            if memory.total_swap > 0.0 {
+                format!(
+                    "{} · {} de {}",
+                    format_percent(swap_percent),
+                    format_gib(memory.used_swap),
+                    format_gib(memory.total_swap)
+                )
+            } else {
+                "desativado".to_string()
+            },
+        ),
+        swap,
+    );
+    render_sparkline(
+        frame,
+        history,
+        "Uso de memória",
+        &sample.history.memory_usage,
+        Some(100.0),
+        |value| format!("{value:.1}%"),
+    );
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(format!("Total       {}", format_gib(memory.total_memory))),
+            Line::from(format!("Usada       {}", format_gib(memory.used_memory))),
+            Line::from(format!(
+                "Disponível  {}",
+                format_gib(memory.available_memory)
+            )),
+        ])
+        .block(Block::bordered().title(" Detalhes ")),
+        details,
+    );
+}
+
+fn draw_gpu(frame: &mut Frame, area: Rect, sample: &Sample) {
+    let [history, table] =
+        Layout::vertical([Constraint::Length(SPARKLINE_HEIGHT), Constraint::Min(0)]).areas(area);
+    render_sparkline(
+        frame,
+        history,
+        "Uso de GPU",
+        &sample.history.gpu_usage,
+        Some(100.0),
+        |value| format!("{value:.1}%"),
+    );
+    render_text(frame, table, "GPUs", |out| {
+        render_gpus(out, &sample.metrics.gpus)
+    });
+}
+
+fn draw_disk(frame: &mut Frame, area: Rect, sample: &Sample) {
+    let [history, table] =
+        Layout::vertical([Constraint::Length(SPARKLINE_HEIGHT), Constraint::Min(0)]).areas(area);
+    let [read, write] =
+        Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(history);
+    render_sparkline(
+        frame,
+        read,
+        "Leitura",
+        &sample.history.disk_read,
+        None,
+        |value| format_rate(value as u64),
+    );
+    render_sparkline(
+        frame,
+        write,
+        "Escrita",
+        &sample.history.disk_write,
+        None,
+        |value| format_rate(value as u64),
+    );
+    render_text(frame, table, "Discos", |out| {
+        render_disks(out, &sample.metrics.disk)
+    });
+}
+
+fn speed_test_lines(status: &NetworkSpeedTestStatus) -> Vec<Line<'static>> {
+    let state = match (&status.state, &status.phase) {
+        (NetworkSpeedTestState::Idle, _) => "não executado",
+        (NetworkSpeedTestState::Running, NetworkSpeedTestPhase::Preparing) => "preparando…",
+        (NetworkSpeedTestState::Running, NetworkSpeedTestPhase::Parsing) => "processando…",
+        (NetworkSpeedTestState::Running, _) => "em andamento…",
+        (NetworkSpeedTestState::Success, _) => "concluído",
+        (NetworkSpeedTestState::Error, _) => "falhou",
+        (NetworkSpeedTestState::Cancelled, _) => "cancelado",
+    };
+    let mut lines = vec![
+        Line::from(format!("Estado    {state}")),
+        Line::from(format!(
+            "Ping      {}",
+            or_missing(status.ping_ms, |ms| format!("{ms:.1} ms"))
+        )),
+        Line::from(format!(
+            "Download  {}",
+            or_missing(status.download_mbps, |mbps| format!("{mbps:.1} Mbps"))
+        )),
+        Line::from(format!(
+            "Upload    {}",
+            or_missing(status.upload_mbps, |mbps| format!("{mbps:.1} Mbps"))
+        )),
+    ];
+    if let Some(server) = &status.server_name {
+        let location = status
+            .server_location
+            .as_deref()
+            .map(|location| format!(" ({location})"))
+            .unwrap_or_default();
+        lines.push(Line::from(format!("Servidor  {server}{location}")));
+    }
+    if let Some(error) = &status.error {
+        lines.push(Line::from(error.clone()).red());
+    }
+    lines
+}
+
+fn draw_network(frame: &mut Frame, area: Rect, sample: &Sample) {
+    let [history, body] =
+        Layout::vertical([Constraint::Length(SPARKLINE_HEIGHT), Constraint::Min(0)]).areas(area);
+    let [download, upload] =
+        Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(history);
+    let [interfaces, speed_test] =
+        Layout::horizontal([Constraint::Fill(2), Constraint::Fill(1)]).areas(body);
+
+    render_sparkline(
+        frame,
+        download,
+        "Download",
+        &sample.history.network_download,
+        None,
+        |value| format_rate(value as u64),
+    );
+    render_sparkline(
+        frame,
+        upload,
+        "Upload",
+        &sample.history.network_upload,
+        None,
+        |value| format_rate(value as u64),
+    );
+    render_text(frame, interfaces, "Interfaces", |out| {
+        render_network(out, &sample.metrics.network)
+    });
+    frame.render_widget(
+        Paragraph::new(speed_test_lines(&sample.speed_test))
+            .block(Block::bordered().title(" Speed test · s inicia · c cancela ")),
+        speed_test,
+    );
+}
+
+fn draw_sensors(frame: &mut Frame, area: Rect, sample: &Sample) {
+    let [history, table] =
+        Layout::vertical([Constraint::Length(SPARKLINE_HEIGHT), Constraint::Min(0)]).areas(area);
+    render_sparkline(
+        frame,
+        history,
+        "Mais quente",
+        &sample.history.sensor_hottest_temperature,
+        None,
+        |value| format_celsius(value as f32),
+    );
+    render_text(frame, table, "Sensores", |out| {
+        render_sensors(out, &sample.metrics.sensors)
+    });
+}
+
+fn draw_system(frame: &mut Frame, area: Rect, sample: &Sample) {
+    let metrics = &sample.metrics;
+    let [info, history, processes] = Layout::vertical([
+        Constraint::Length(9),
+        Constraint::Length(SPARKLINE_HEIGHT),
+        Constraint::Min(0),
+    ])
+    .areas(area);
+    render_text(frame, info, "Sistema", |out| {
+        render_system(
+            out,
+            Some(&metrics.system_info),
+            Some(metrics.uptime),
+            Some(metrics.load_average),
+        )
+    });
+    render_sparkline(
+        frame,
+        history,
+        "Carga (1 min)",
+        &sample.history.system_load_1,
+        None,
+        |value| format!("{value:.2}"),
+    );
+    render_text(frame, processes, "Processos", |out| {
+        render_processes(out, &metrics.top_processes)
+    });
+}
+
+fn status_line(app: &App) -> Line<'static> {
+    let speed_test = app
+        .sample
+        .as_ref()
+        .filter(|sample| matches!(sample.speed_test.state, NetworkSpeedTestState::Running))
+        .map(|_| " · speed test em andamento")
+        .unwrap_or_default();
+    let help = "←/→ abas · 1-7 · s speed test · r atualizar · q sair";
+    match &app.message {
+        Some(message) => Line::from(format!(" {}{speed_test} · {message}", app.source)).yellow(),
+        None => Line::from(format!(" {}{speed_test} · {help}", app.source)).dark_gray(),
+    }
+}
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [tabs, body, status] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let titles = Tab::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, tab)| format!("{} {}", index + 1, tab.title()));
+    frame.render_widget(
+        Tabs::new(titles)
+            .select(app.tab.index())
+            .highlight_style(Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)),
+        tabs,
+    );
+    frame.render_widget(status_line(app), status);
+
+    let Some(sample) = &app.sample else {
+        frame.render_widget(Paragraph::new("Coletando métricas…"), body);
+        return;
+    };
+    match app.tab {
+        Tab::Cpu => draw_cpu(frame, body, sample),
+        Tab::Memory => draw_memory(frame, body, sample),
+        Tab::Gpu => draw_gpu(frame, body, sample),
+        Tab::Disk => draw_disk(frame, body, sample),
+        Tab::Network => draw_network(frame, body, sample),
+        Tab::Sensors => draw_sensors(frame, body, sample),
+        Tab::System => draw_system(frame, body, sample),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    use super::*;
+    use crate::monitor::SystemMonitor;
+
+    #[test]
+    fn test_sparkline_data_keeps_most_recent_samples() {
+        let series = HistorySeries {
+            buffer: vec![4.0, 5.0, 1.0, 2.0, 3.0],
+            start: 2,
+            count: 5,
+            sample_interval_ms: 1000,
+        };
+
+        assert_eq!(sparkline_data(&series, 3), vec![300, 400, 500]);
+        assert_eq!(sparkline_data(&series, 10).len(), 5);
+    }
+
+    #[test]
+    fn test_every_tab_draws_on_a_small_terminal() {
+        let monitor = SystemMonitor::new_empty();
+        let mut app = App::new("local");
+        app.sample = Some(Sample {
+            metrics: monitor.get_all_metrics(),
+            history: monitor.get_history_metrics(),
+            speed_test: NetworkSpeedTestStatus::default(),
+        });
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).expect("test backend");
+
+        for tab in Tab::ALL {
+            app.tab = tab;
+            terminal
+                .draw(|frame| draw(frame, &app))
+                .expect("tab should draw");
+            let screen: String = terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect();
+            assert!(screen.contains(tab.title()));
+        }
+    }
+}
@@ -0,0 +1,12 @@
+//! Relógio de parede em tempo Unix.
+//!
+//! Compartilhado pela CLI, pelos exportadores e pelo gravador de snapshots.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Tempo desde a época Unix; zero se o relógio estiver antes de 1970.
+pub(crate) fn unix_now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}